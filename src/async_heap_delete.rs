@@ -1,8 +1,8 @@
 use crate::{
+    lazyfree::LazyFreeOption,
     lru::{estimate_entry_bytes, LruManager},
-    model::{DB, Heap, MinHeap},
+    model::{DB, Heap, MinHeap, unix_millis},
 };
-use std::time::Instant;
 use tokio::time::{Duration, sleep};
 
 pub fn async_clean_db_heap(mut _db: DB, mut _heap: Heap, lru: LruManager) {
@@ -10,12 +10,13 @@ pub fn async_clean_db_heap(mut _db: DB, mut _heap: Heap, lru: LruManager) {
         loop {
             sleep(Duration::from_millis(100)).await;
 
-            let now = Instant::now();
+            let now = unix_millis();
 
             let mut heap = _heap.lock().await;
             let mut db = _db.write().await;
             let mut removed_bytes = 0usize;
             let mut removed_keys: Vec<String> = Vec::new();
+            let mut removed_values = Vec::new();
 
             while let Some(top) = heap.peek() {
                 if top.expires_at > now {
@@ -24,12 +25,33 @@ pub fn async_clean_db_heap(mut _db: DB, mut _heap: Heap, lru: LruManager) {
 
                 let MinHeap { expires_at, key } = heap.pop().unwrap();
 
-                if let Some(entry) = db.get(&key) {
-                    if entry.expires_at == Some(expires_at) {
-                        if let Some((stored_key, removed_entry)) = db.remove_entry(&key) {
-                            removed_bytes += estimate_entry_bytes(&stored_key, &removed_entry);
-                            removed_keys.push(stored_key);
-                        }
+                if let Some(entry) = db.get(&key)
+                    && entry.expires_at == Some(expires_at)
+                    && let Some((stored_key, removed_entry)) = db.remove_entry(&key)
+                {
+                    removed_bytes += estimate_entry_bytes(&stored_key, &removed_entry);
+                    removed_keys.push(stored_key);
+                    removed_values.push(removed_entry.value);
+                } else if let Some(entry) = db.get_mut(&key)
+                    && entry.value.as_hash().is_some()
+                {
+                    // Not the key's own TTL, so a hash field deadline.
+                    let old_size = estimate_entry_bytes(&key, entry);
+                    let Some(hash) = entry.value.as_hash_mut() else {
+                        continue;
+                    };
+                    if hash.purge_expired() == 0 {
+                        continue;
+                    }
+                    if hash.is_empty()
+                        && let Some((stored_key, removed_entry)) = db.remove_entry(&key)
+                    {
+                        removed_bytes += old_size;
+                        removed_keys.push(stored_key);
+                        removed_values.push(removed_entry.value);
+                    } else {
+                        removed_bytes +=
+                            old_size.saturating_sub(estimate_entry_bytes(&key, &db[&key]));
                     }
                 }
             }
@@ -37,11 +59,14 @@ pub fn async_clean_db_heap(mut _db: DB, mut _heap: Heap, lru: LruManager) {
             drop(db);
             drop(heap);
 
+            let lazy = lru.lazyfree().enabled(LazyFreeOption::Expire);
+            lru.lazyfree().free(removed_values, lazy);
+
             if removed_bytes > 0 {
                 lru.adjust_used_bytes(-(removed_bytes as isize));
             }
             if !removed_keys.is_empty() {
-                lru.remove_keys(&removed_keys).await;
+                lru.remove_keys(&_db, &removed_keys).await;
             }
         }
     });
@@ -0,0 +1,155 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    net::TcpStream,
+    sync::Notify,
+    time::{Instant, sleep_until},
+};
+
+/// Registry of clients blocked on keys (XREAD BLOCK, BLPOP and friends).
+///
+/// Writers call `wake_all` or `wake_one` after changing a key; each blocked
+/// client holds a `Waiter` and re-checks the keyspace once notified. A waiter
+/// unregisters itself when dropped, so timeouts and disconnects need no extra
+/// cleanup.
+#[derive(Clone, Default)]
+pub struct BlockingManager {
+    inner: Arc<Mutex<Registry>>,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: u64,
+    keys: HashMap<String, VecDeque<(u64, Arc<Notify>)>>,
+    /// Waiters signalled by `wake_one` that have not picked the wake-up up yet.
+    woken: HashSet<u64>,
+}
+
+pub struct Waiter {
+    id: u64,
+    keys: Vec<String>,
+    notify: Arc<Notify>,
+    manager: BlockingManager,
+}
+
+pub enum WaitOutcome {
+    Woken,
+    TimedOut,
+    Disconnected,
+}
+
+impl BlockingManager {
+    /// Registers interest in `keys`. Register before checking the keyspace so
+    /// a write landing in between is not missed.
+    pub fn register(&self, keys: &[String]) -> Waiter {
+        let mut registry = self.inner.lock().unwrap();
+        registry.next_id += 1;
+        let id = registry.next_id;
+        let notify = Arc::new(Notify::new());
+        for key in keys {
+            registry
+                .keys
+                .entry(key.clone())
+                .or_default()
+                .push_back((id, notify.clone()));
+        }
+        Waiter {
+            id,
+            keys: keys.to_vec(),
+            notify,
+            manager: self.clone(),
+        }
+    }
+
+    /// Wakes every client blocked on `key`.
+    pub fn wake_all(&self, key: &str) {
+        let registry = self.inner.lock().unwrap();
+        if let Some(waiters) = registry.keys.get(key) {
+            for (_, notify) in waiters {
+                notify.notify_one();
+            }
+        }
+    }
+
+    /// Wakes the longest-blocked client on `key` that is not already woken.
+    /// List pushes use this so blocked pops are served in FIFO order; the
+    /// served client calls it again if elements remain.
+    pub fn wake_one(&self, key: &str) {
+        let mut registry = self.inner.lock().unwrap();
+        let Registry { keys, woken, .. } = &mut *registry;
+        if let Some(waiters) = keys.get(key)
+            && let Some((id, notify)) = waiters.iter().find(|(id, _)| !woken.contains(id))
+        {
+            woken.insert(*id);
+            notify.notify_one();
+        }
+    }
+
+    /// Wakes every blocked client whatever it waits on, for when a whole
+    /// keyspace changes under them as SWAPDB does.
+    pub fn wake_everyone(&self) {
+        let registry = self.inner.lock().unwrap();
+        for waiters in registry.keys.values() {
+            for (_, notify) in waiters {
+                notify.notify_one();
+            }
+        }
+    }
+}
+
+impl Waiter {
+    /// Waits until woken, `deadline` passes, or the client hangs up.
+    pub async fn wait(&self, deadline: Option<Instant>, socket: &TcpStream) -> WaitOutcome {
+        let timeout = async {
+            match deadline {
+                Some(deadline) => sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            _ = self.notify.notified() => {
+                self.manager.inner.lock().unwrap().woken.remove(&self.id);
+                WaitOutcome::Woken
+            }
+            _ = timeout => WaitOutcome::TimedOut,
+            _ = closed(socket) => WaitOutcome::Disconnected,
+        }
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        let mut registry = self.manager.inner.lock().unwrap();
+        let missed = registry.woken.remove(&self.id);
+        for key in &self.keys {
+            if let Some(waiters) = registry.keys.get_mut(key) {
+                waiters.retain(|(id, _)| *id != self.id);
+                if waiters.is_empty() {
+                    registry.keys.remove(key);
+                }
+            }
+        }
+        drop(registry);
+
+        // A wake-up that arrived as this client timed out or hung up is passed
+        // on so the element it announced is not left unserved.
+        if missed {
+            for key in &self.keys {
+                self.manager.wake_one(key);
+            }
+        }
+    }
+}
+
+/// Resolves once the peer closes the connection. Pipelined input stays in
+/// the socket buffer for the command loop to read after the block ends.
+async fn closed(socket: &TcpStream) {
+    let mut buf = [0u8; 1];
+    match socket.peek(&mut buf).await {
+        Ok(0) | Err(_) => {}
+        Ok(_) => std::future::pending::<()>().await,
+    }
+}
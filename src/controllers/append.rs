@@ -0,0 +1,65 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Value},
+    util::{OOM_ERR, WRONGTYPE_ERR, drop_expired, integer},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn append_cmd(
+    key: String,
+    value: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::String(Vec::new()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(bytes) = entry.value.as_string_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let old_len = bytes.len();
+    bytes.extend_from_slice(&value);
+    let len = bytes.len();
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(bytes) = db.get_mut(&key).and_then(|e| e.value.as_string_mut()) {
+            bytes.truncate(old_len);
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(len as i64)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, integer, read_value, string_range};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn bitcount_cmd(
+    key: String,
+    range: Option<(i64, i64)>,
+    bit_unit: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_string_bytes().map(|bytes| {
+            let Some((start, end)) = range else {
+                return count_ones(&bytes);
+            };
+            let len = if bit_unit {
+                bytes.len() * 8
+            } else {
+                bytes.len()
+            };
+            match string_range(len, start, end) {
+                Some((start, end)) if bit_unit => count_bits(&bytes, start, end),
+                Some((start, end)) => count_ones(&bytes[start..=end]),
+                None => 0,
+            }
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(count) => socket.write_all(&integer(count as i64)).await?,
+        Lookup::Missing => socket.write_all(b":0\r\n").await?,
+    }
+
+    Ok(())
+}
+
+fn count_ones(bytes: &[u8]) -> usize {
+    bytes.iter().map(|b| b.count_ones() as usize).sum()
+}
+
+/// Counts set bits between the inclusive bit positions `start` and `end`.
+fn count_bits(bytes: &[u8], start: usize, end: usize) -> usize {
+    let (first, last) = (start / 8, end / 8);
+    let mut count = count_ones(&bytes[first..=last]);
+    // Take back the bits of the edge bytes that fall outside the range.
+    count -= (bytes[first] & !(0xff >> (start % 8))).count_ones() as usize;
+    count -= (bytes[last] & (0xffu16 >> (end % 8 + 1)) as u8).count_ones() as usize;
+    count
+}
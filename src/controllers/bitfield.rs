@@ -0,0 +1,151 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{BitFieldOp, BitFieldType, BitOverflow, DB, Entry, Heap, Value},
+    util::{
+        Lookup, OOM_ERR, WRONGTYPE_ERR, array_len, drop_expired, integer, null_bulk, read_value,
+    },
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn bitfield_cmd(
+    key: String,
+    ops: Vec<BitFieldOp>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::String(Vec::new()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(bytes) = entry.value.as_string_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let previous = bytes.clone();
+    let resp = apply_ops(&ops, bytes);
+    // Only GETs, or writes that all failed, leave a new key empty.
+    if created_new && bytes.is_empty() {
+        db.remove(&key);
+        drop(db);
+        socket.write_all(&resp).await?;
+        return Ok(());
+    }
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(bytes) = db.get_mut(&key).and_then(|e| e.value.as_string_mut()) {
+            *bytes = previous;
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
+
+pub async fn bitfield_ro_cmd(
+    key: String,
+    gets: Vec<(BitFieldType, u64)>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_string_bytes().map(|bytes| {
+            let mut resp = array_len(gets.len());
+            for (ty, offset) in &gets {
+                resp.extend_from_slice(&integer(ty.read(&bytes, *offset)));
+            }
+            resp
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => {
+            let mut resp = array_len(gets.len());
+            for _ in &gets {
+                resp.extend_from_slice(&integer(0));
+            }
+            socket.write_all(&resp).await?
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the subcommands in order and builds the reply. SET answers the old
+/// value, INCRBY the new one, and either answers nil when `OVERFLOW FAIL`
+/// rejects the write.
+fn apply_ops(ops: &[BitFieldOp], bytes: &mut Vec<u8>) -> Vec<u8> {
+    let replies = ops
+        .iter()
+        .filter(|op| !matches!(op, BitFieldOp::Overflow(_)))
+        .count();
+    let mut resp = array_len(replies);
+    let mut overflow = BitOverflow::Wrap;
+    for op in ops {
+        match *op {
+            BitFieldOp::Get { ty, offset } => {
+                resp.extend_from_slice(&integer(ty.read(bytes, offset)));
+            }
+            BitFieldOp::Set { ty, offset, value } => {
+                let old = ty.read(bytes, offset);
+                match ty.fit(value as i128, overflow) {
+                    Some(value) => {
+                        ty.write(bytes, offset, value);
+                        resp.extend_from_slice(&integer(old));
+                    }
+                    None => resp.extend_from_slice(&null_bulk()),
+                }
+            }
+            BitFieldOp::IncrBy {
+                ty,
+                offset,
+                increment,
+            } => {
+                let old = ty.read(bytes, offset);
+                match ty.fit(old as i128 + increment as i128, overflow) {
+                    Some(value) => {
+                        ty.write(bytes, offset, value);
+                        resp.extend_from_slice(&integer(value));
+                    }
+                    None => resp.extend_from_slice(&null_bulk()),
+                }
+            }
+            BitFieldOp::Overflow(mode) => overflow = mode,
+        }
+    }
+    resp
+}
@@ -0,0 +1,94 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{BitOp, DB, Entry, Heap, Value},
+    util::{OOM_ERR, WRONGTYPE_ERR, integer, is_expired},
+};
+use anyhow::Result;
+use std::collections::HashMap;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn bitop_cmd(
+    op: BitOp,
+    destination: String,
+    keys: Vec<String>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let Some(result) = combine(&db, &keys, op) else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let len = result.len();
+    let old = db.remove(&destination);
+    let old_size = old
+        .as_ref()
+        .map(|entry| estimate_entry_bytes(&destination, entry))
+        .unwrap_or(0);
+
+    let mut new_size = 0usize;
+    if len > 0 {
+        let new_entry = Entry {
+            value: Value::String(result),
+            expires_at: None,
+        };
+        new_size = estimate_entry_bytes(&destination, &new_entry);
+        db.insert(destination.clone(), new_entry);
+    }
+    drop(db);
+
+    if len == 0 && old.is_some() {
+        lru.remove_key(_db, &destination).await;
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        match old {
+            Some(old_entry) => {
+                db.insert(destination.clone(), old_entry);
+            }
+            None => {
+                db.remove(&destination);
+            }
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(len as i64)).await?;
+
+    Ok(())
+}
+
+/// Applies `op` byte by byte, padding shorter sources with zeros. Missing
+/// and expired keys count as empty strings. Returns `None` if any source is
+/// not a string.
+fn combine(db: &HashMap<String, Entry>, keys: &[String], op: BitOp) -> Option<Vec<u8>> {
+    let mut sources = Vec::with_capacity(keys.len());
+    for key in keys {
+        match db.get(key) {
+            Some(entry) if !is_expired(entry) => sources.push(entry.value.as_string_bytes()?),
+            _ => sources.push(Default::default()),
+        }
+    }
+
+    let len = sources.iter().map(|s| s.len()).max().unwrap_or(0);
+    let byte_at = |source: &[u8], i: usize| source.get(i).copied().unwrap_or(0);
+    let result = (0..len)
+        .map(|i| match op {
+            BitOp::Not => !byte_at(&sources[0], i),
+            BitOp::And => sources.iter().fold(0xff, |acc, s| acc & byte_at(s, i)),
+            BitOp::Or => sources.iter().fold(0, |acc, s| acc | byte_at(s, i)),
+            BitOp::Xor => sources.iter().fold(0, |acc, s| acc ^ byte_at(s, i)),
+        })
+        .collect();
+    Some(result)
+}
@@ -0,0 +1,68 @@
+use crate::model::{DB, Heap, get_bit};
+use crate::util::{Lookup, WRONGTYPE_ERR, integer, read_value, string_range};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn bitpos_cmd(
+    key: String,
+    bit: bool,
+    start: i64,
+    end: Option<i64>,
+    bit_unit: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_string_bytes().map(|bytes| {
+            let len = if bit_unit {
+                bytes.len() * 8
+            } else {
+                bytes.len()
+            };
+            let Some((start, stop)) = string_range(len, start, end.unwrap_or(-1)) else {
+                return -1;
+            };
+            let (first, last) = if bit_unit {
+                (start, stop)
+            } else {
+                (start * 8, stop * 8 + 7)
+            };
+            match find_bit(&bytes, bit, first, last) {
+                Some(pos) => pos as i64,
+                // Looking for a clear bit without an explicit end treats the
+                // string as padded with zeros on the right.
+                None if !bit && end.is_none() => last as i64 + 1,
+                None => -1,
+            }
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(pos) => socket.write_all(&integer(pos)).await?,
+        Lookup::Missing => socket.write_all(&integer(if bit { -1 } else { 0 })).await?,
+    }
+
+    Ok(())
+}
+
+/// Returns the first bit position in `first..=last` holding `bit`.
+fn find_bit(bytes: &[u8], bit: bool, first: usize, last: usize) -> Option<usize> {
+    let skip = if bit { 0x00 } else { 0xff };
+    let mut pos = first;
+    while pos <= last {
+        if pos.is_multiple_of(8) && pos + 7 <= last && bytes[pos / 8] == skip {
+            pos += 8;
+            continue;
+        }
+        if get_bit(bytes, pos as u64) == bit {
+            return Some(pos);
+        }
+        pos += 1;
+    }
+    None
+}
@@ -0,0 +1,230 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{BloomFilter, DB, Entry, Heap, Value},
+    util::{
+        Create, Lookup, OOM_ERR, WRONGTYPE_ERR, array_len, bulk_str, create_value, drop_expired,
+        integer, null_bulk, read_value,
+    },
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+const FULL_ERR: &[u8] = b"-ERR non scaling filter is full\r\n";
+const TOO_LARGE_ERR: &[u8] = b"-ERR filter would exceed the maximum size\r\n";
+
+#[allow(clippy::too_many_arguments)]
+pub async fn bf_reserve_cmd(
+    key: String,
+    error_rate: f64,
+    capacity: u64,
+    expansion: Option<u32>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let Some(footprint) = BloomFilter::footprint(error_rate, capacity) else {
+        socket.write_all(TOO_LARGE_ERR).await?;
+        return Ok(());
+    };
+    let make =
+        || Value::Bloom(BloomFilter::new(error_rate, capacity, expansion).unwrap_or_default());
+    match create_value(_db, lru, &key, footprint, make).await? {
+        Create::Created => socket.write_all(b"+OK\r\n").await?,
+        Create::Exists => socket.write_all(b"-ERR item exists\r\n").await?,
+        Create::OutOfMemory => socket.write_all(OOM_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn bf_add_cmd(
+    key: String,
+    item: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let Some(added) = add_items(&key, &[item], _db, _heap, lru, socket).await? else {
+        return Ok(());
+    };
+
+    match added[0] {
+        Some(new) => socket.write_all(&integer(new as i64)).await?,
+        None => socket.write_all(FULL_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn bf_madd_cmd(
+    key: String,
+    items: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let Some(added) = add_items(&key, &items, _db, _heap, lru, socket).await? else {
+        return Ok(());
+    };
+
+    let mut resp = array_len(added.len());
+    for result in added {
+        match result {
+            Some(new) => resp.extend_from_slice(&integer(new as i64)),
+            None => resp.extend_from_slice(FULL_ERR),
+        }
+    }
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
+
+pub async fn bf_exists_cmd(
+    key: String,
+    item: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_bloom().map(|bloom| bloom.contains(&item))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(found) => socket.write_all(&integer(found as i64)).await?,
+        Lookup::Missing => socket.write_all(&integer(0)).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn bf_mexists_cmd(
+    key: String,
+    items: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let bloom = value.as_bloom()?;
+        Some(items.iter().map(|item| bloom.contains(item)).collect())
+    })
+    .await;
+
+    let found: Vec<bool> = match lookup {
+        Lookup::WrongType => {
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+        Lookup::Found(found) => found,
+        Lookup::Missing => vec![false; items.len()],
+    };
+
+    let mut resp = array_len(found.len());
+    for exists in found {
+        resp.extend_from_slice(&integer(exists as i64));
+    }
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
+
+pub async fn bf_info_cmd(
+    key: String,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let bloom = value.as_bloom()?;
+        let mut resp = array_len(10);
+        resp.extend_from_slice(&bulk_str("Capacity"));
+        resp.extend_from_slice(&integer(bloom.capacity() as i64));
+        resp.extend_from_slice(&bulk_str("Size"));
+        resp.extend_from_slice(&integer(bloom.bytes() as i64));
+        resp.extend_from_slice(&bulk_str("Number of filters"));
+        resp.extend_from_slice(&integer(bloom.filter_count() as i64));
+        resp.extend_from_slice(&bulk_str("Number of items inserted"));
+        resp.extend_from_slice(&integer(bloom.items() as i64));
+        resp.extend_from_slice(&bulk_str("Expansion rate"));
+        match bloom.expansion() {
+            Some(expansion) => resp.extend_from_slice(&integer(expansion as i64)),
+            None => resp.extend_from_slice(&null_bulk()),
+        }
+        Some(resp)
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(b"-ERR not found\r\n").await?,
+    }
+
+    Ok(())
+}
+
+/// Adds `items` to the filter at `key`, creating a default one if needed.
+/// Each result is whether the item was new, or `None` if a non-scaling
+/// filter was full. Returns `None` once an error has been written.
+async fn add_items(
+    key: &str,
+    items: &[Vec<u8>],
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<Option<Vec<Option<bool>>>> {
+    let key = key.to_string();
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::Bloom(BloomFilter::default()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(bloom) = entry.value.as_bloom_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(None);
+    };
+    let filters_before = bloom.filter_count();
+    let added: Vec<Option<bool>> = items.iter().map(|item| bloom.add(item)).collect();
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        // Only new sub-filters take memory, so dropping them undoes the
+        // growth; items that fit the existing ones stay added.
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(bloom) = db.get_mut(&key).and_then(|e| e.value.as_bloom_mut()) {
+            bloom.truncate(filters_before);
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(None);
+    }
+
+    Ok(Some(added))
+}
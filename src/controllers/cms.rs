@@ -0,0 +1,190 @@
+use crate::{
+    lru::LruManager,
+    model::{CountMinSketch, DB, Heap, Value},
+    util::{
+        Create, Lookup, OOM_ERR, WRONGTYPE_ERR, array_len, create_value, drop_expired, integer,
+        read_value, update_value,
+    },
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+const NO_KEY_ERR: &[u8] = b"-ERR CMS: key does not exist\r\n";
+
+pub async fn cms_initbydim_cmd(
+    key: String,
+    width: usize,
+    depth: usize,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    init(key, width, depth, _db, _heap, lru, socket).await
+}
+
+pub async fn cms_initbyprob_cmd(
+    key: String,
+    error: f64,
+    probability: f64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let (width, depth) = CountMinSketch::dims_for(error, probability);
+    init(key, width, depth, _db, _heap, lru, socket).await
+}
+
+pub async fn cms_incrby_cmd(
+    key: String,
+    increments: Vec<(Vec<u8>, u64)>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        let cms = value.as_cms_mut()?;
+        Some(
+            increments
+                .iter()
+                .map(|(item, by)| cms.incr_by(item, *by))
+                .collect::<Vec<u64>>(),
+        )
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(counts) => socket.write_all(&counts_resp(&counts)).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn cms_query_cmd(
+    key: String,
+    items: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let cms = value.as_cms()?;
+        Some(
+            items
+                .iter()
+                .map(|item| cms.query(item))
+                .collect::<Vec<u64>>(),
+        )
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(counts) => socket.write_all(&counts_resp(&counts)).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn cms_merge_cmd(
+    destination: String,
+    sources: Vec<String>,
+    weights: Vec<u64>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    for key in std::iter::once(&destination).chain(&sources) {
+        let freed = drop_expired(&mut db, key);
+        if freed > 0 {
+            lru.adjust_used_bytes(-(freed as isize));
+        }
+    }
+
+    let merged = {
+        let Some(dest) = db.get(&destination) else {
+            drop(db);
+            socket.write_all(NO_KEY_ERR).await?;
+            return Ok(());
+        };
+        let Some(dest) = dest.value.as_cms() else {
+            drop(db);
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        };
+
+        let mut inputs = Vec::with_capacity(sources.len());
+        let mut error = None;
+        for (key, weight) in sources.iter().zip(&weights) {
+            match db.get(key).map(|entry| entry.value.as_cms()) {
+                None => error = Some(NO_KEY_ERR),
+                Some(None) => error = Some(WRONGTYPE_ERR),
+                Some(Some(cms)) if cms.width() != dest.width() || cms.depth() != dest.depth() => {
+                    error = Some(b"-ERR CMS: width/depth is not equal\r\n".as_slice())
+                }
+                Some(Some(cms)) => inputs.push((cms, *weight)),
+            }
+            if error.is_some() {
+                break;
+            }
+        }
+        if let Some(err) = error {
+            drop(db);
+            socket.write_all(err).await?;
+            return Ok(());
+        }
+
+        let mut merged = CountMinSketch::new(dest.width(), dest.depth());
+        merged.merge(&inputs);
+        merged
+    };
+
+    // Same dimensions, so the entry's size does not change.
+    if let Some(entry) = db.get_mut(&destination) {
+        entry.value = Value::Cms(merged);
+    }
+    drop(db);
+    socket.write_all(b"+OK\r\n").await?;
+
+    Ok(())
+}
+
+async fn init(
+    key: String,
+    width: usize,
+    depth: usize,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let footprint = CountMinSketch::footprint(width, depth);
+    let make = || Value::Cms(CountMinSketch::new(width, depth));
+    match create_value(_db, lru, &key, footprint, make).await? {
+        Create::Created => socket.write_all(b"+OK\r\n").await?,
+        Create::Exists => {
+            socket
+                .write_all(b"-ERR CMS: key already exists\r\n")
+                .await?
+        }
+        Create::OutOfMemory => socket.write_all(OOM_ERR).await?,
+    }
+
+    Ok(())
+}
+
+fn counts_resp(counts: &[u64]) -> Vec<u8> {
+    let mut resp = array_len(counts.len());
+    for count in counts {
+        resp.extend_from_slice(&integer((*count).min(i64::MAX as u64) as i64));
+    }
+    resp
+}
@@ -8,20 +8,189 @@ pub async fn command_cmd(socket: &mut TcpStream) -> Result<()> {
         CommandInfo::new("ping", 1, &["fast"], 0, 0, 0),
         CommandInfo::new("quit", 1, &["fast"], 0, 0, 0),
         CommandInfo::new("get", 2, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("incr", 2, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("decr", 2, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("incrby", 3, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("decrby", 3, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("incrbyfloat", 3, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("append", 3, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("strlen", 2, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("getrange", 4, &["readonly"], 1, 1, 1),
+        CommandInfo::new("setrange", 4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("getdel", 2, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("getex", -2, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("getset", 3, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("setnx", 3, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("mget", -2, &["readonly", "fast"], 1, -1, 1),
+        CommandInfo::new("mset", -3, &["write", "denyoom"], 1, -1, 2),
+        CommandInfo::new("msetnx", -3, &["write", "denyoom"], 1, -1, 2),
+        CommandInfo::new("setbit", 4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("getbit", 3, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("bitcount", -2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("bitpos", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("bitop", -4, &["write", "denyoom"], 2, -1, 1),
+        CommandInfo::new("bitfield", -2, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("bitfield_ro", -2, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("pfadd", -2, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("pfcount", -2, &["readonly"], 1, -1, 1),
+        CommandInfo::new("pfmerge", -2, &["write", "denyoom"], 1, -1, 1),
+        CommandInfo::new("geoadd", -5, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("geopos", -2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("geohash", -2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("geodist", -4, &["readonly"], 1, 1, 1),
+        CommandInfo::new("geosearch", -7, &["readonly"], 1, 1, 1),
+        CommandInfo::new("geosearchstore", -8, &["write", "denyoom"], 1, 2, 1),
+        CommandInfo::new("json.set", -4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("json.get", -2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("json.del", -2, &["write"], 1, 1, 1),
+        CommandInfo::new("json.type", -2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("json.numincrby", 4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("json.arrappend", -4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("json.strlen", -2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("json.objkeys", -2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("bf.reserve", -4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("bf.add", 3, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("bf.madd", -3, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("bf.exists", 3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("bf.mexists", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("bf.info", 2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("cf.add", 3, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("cf.del", 3, &["write"], 1, 1, 1),
+        CommandInfo::new("cf.exists", 3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("cms.initbydim", 4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("cms.initbyprob", 4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("cms.incrby", -4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("cms.query", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("cms.merge", -4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("topk.reserve", -3, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("topk.add", -3, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("topk.query", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("topk.count", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("topk.list", -2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("ts.create", -2, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("ts.add", -4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("ts.madd", -4, &["write", "denyoom"], 1, -1, 3),
+        CommandInfo::new("ts.range", -4, &["readonly"], 1, 1, 1),
+        CommandInfo::new("ts.revrange", -4, &["readonly"], 1, 1, 1),
+        CommandInfo::new("ts.mrange", -5, &["readonly"], 0, 0, 0),
         CommandInfo::new("set", -3, &["write"], 1, 1, 1),
         CommandInfo::new("setex", 4, &["write"], 1, 1, 1),
         CommandInfo::new("psetex", 4, &["write"], 1, 1, 1),
         CommandInfo::new("del", -2, &["write"], 1, -1, 1),
+        CommandInfo::new("unlink", -2, &["write", "fast"], 1, -1, 1),
         CommandInfo::new("exists", -2, &["readonly", "fast"], 1, -1, 1),
-        CommandInfo::new("expire", 3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("expire", -3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("pexpire", -3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("expireat", -3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("pexpireat", -3, &["write", "fast"], 1, 1, 1),
         CommandInfo::new("persist", 2, &["write", "fast"], 1, 1, 1),
         CommandInfo::new("ttl", 2, &["readonly", "fast"], 1, 1, 1),
         CommandInfo::new("pttl", 2, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("expiretime", 2, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("pexpiretime", 2, &["readonly", "fast"], 1, 1, 1),
         CommandInfo::new("type", 2, &["readonly", "fast"], 1, 1, 1),
         CommandInfo::new("lpush", -3, &["write"], 1, 1, 1),
         CommandInfo::new("rpush", -3, &["write"], 1, 1, 1),
-        CommandInfo::new("lpop", 2, &["write", "fast"], 1, 1, 1),
-        CommandInfo::new("rpop", 2, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("lpop", -2, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("rpop", -2, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("lpushx", -3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("rpushx", -3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("lrange", 4, &["readonly"], 1, 1, 1),
+        CommandInfo::new("lindex", 3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("llen", 2, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("lset", 4, &["write"], 1, 1, 1),
+        CommandInfo::new("linsert", 5, &["write"], 1, 1, 1),
+        CommandInfo::new("lrem", 4, &["write"], 1, 1, 1),
+        CommandInfo::new("ltrim", 4, &["write"], 1, 1, 1),
+        CommandInfo::new("lpos", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("lmove", 5, &["write", "denyoom"], 1, 2, 1),
+        CommandInfo::new("rpoplpush", 3, &["write", "denyoom"], 1, 2, 1),
+        CommandInfo::new("lmpop", -4, &["write"], 0, 0, 0),
+        CommandInfo::new("blpop", -3, &["write", "blocking"], 1, -2, 1),
+        CommandInfo::new("brpop", -3, &["write", "blocking"], 1, -2, 1),
+        CommandInfo::new("blmove", 6, &["write", "denyoom", "blocking"], 1, 2, 1),
+        CommandInfo::new("blmpop", -5, &["write", "blocking"], 0, 0, 0),
+        CommandInfo::new("hset", -4, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("hsetnx", 4, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("hget", 3, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("hmget", -3, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("hdel", -3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("hexpire", -6, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("hpexpire", -6, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("hexpireat", -6, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("httl", -5, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("hpttl", -5, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("hpersist", -5, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("hscan", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("sscan", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("zscan", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("scan", -2, &["readonly"], 0, 0, 0),
+        CommandInfo::new("keys", 2, &["readonly"], 0, 0, 0),
+        CommandInfo::new("rename", 3, &["write"], 1, 2, 1),
+        CommandInfo::new("renamenx", 3, &["write", "fast"], 1, 2, 1),
+        CommandInfo::new("copy", -3, &["write", "denyoom"], 1, 2, 1),
+        CommandInfo::new("randomkey", 1, &["readonly"], 0, 0, 0),
+        CommandInfo::new("dbsize", 1, &["readonly", "fast"], 0, 0, 0),
+        CommandInfo::new("touch", -2, &["readonly", "fast"], 1, -1, 1),
+        CommandInfo::new("select", 2, &["loading", "stale", "fast"], 0, 0, 0),
+        CommandInfo::new("move", 3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("swapdb", 3, &["write", "fast"], 0, 0, 0),
+        CommandInfo::new("flushdb", -1, &["write"], 0, 0, 0),
+        CommandInfo::new("flushall", -1, &["write"], 0, 0, 0),
+        CommandInfo::new("hexists", 3, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("hlen", 2, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("hstrlen", 3, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("hkeys", 2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("hvals", 2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("hgetall", 2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("hincrby", 4, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("hincrbyfloat", 4, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("hrandfield", -2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("hscan", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("sadd", -3, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("srem", -3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("sismember", 3, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("smismember", -3, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("smembers", 2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("scard", 2, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("spop", -2, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("srandmember", -2, &["readonly"], 1, 1, 1),
+        CommandInfo::new("smove", 4, &["write", "fast"], 1, 2, 1),
+        CommandInfo::new("sinter", -2, &["readonly"], 1, -1, 1),
+        CommandInfo::new("sunion", -2, &["readonly"], 1, -1, 1),
+        CommandInfo::new("sdiff", -2, &["readonly"], 1, -1, 1),
+        CommandInfo::new("sinterstore", -3, &["write", "denyoom"], 1, -1, 1),
+        CommandInfo::new("sunionstore", -3, &["write", "denyoom"], 1, -1, 1),
+        CommandInfo::new("sdiffstore", -3, &["write", "denyoom"], 1, -1, 1),
+        CommandInfo::new("sintercard", -3, &["readonly"], 0, 0, 0),
+        CommandInfo::new("zadd", -4, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("zrem", -3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("zscore", 3, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("zincrby", 4, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("zcard", 2, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("zcount", 4, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("zrank", -3, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("zrevrank", -3, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("zrange", -4, &["readonly"], 1, 1, 1),
+        CommandInfo::new("zrangestore", -5, &["write", "denyoom"], 1, 2, 1),
+        CommandInfo::new("zpopmin", -2, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("zpopmax", -2, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("zunionstore", -4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("zinterstore", -4, &["write", "denyoom"], 1, 1, 1),
+        CommandInfo::new("xadd", -5, &["write", "denyoom", "fast"], 1, 1, 1),
+        CommandInfo::new("xrange", -4, &["readonly"], 1, 1, 1),
+        CommandInfo::new("xrevrange", -4, &["readonly"], 1, 1, 1),
+        CommandInfo::new("xlen", 2, &["readonly", "fast"], 1, 1, 1),
+        CommandInfo::new("xdel", -3, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("xtrim", -4, &["write"], 1, 1, 1),
+        CommandInfo::new("xread", -4, &["readonly", "blocking"], 0, 0, 0),
+        CommandInfo::new("xreadgroup", -7, &["write", "blocking"], 0, 0, 0),
+        CommandInfo::new("xgroup", -2, &["write"], 2, 2, 1),
+        CommandInfo::new("xack", -4, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("xpending", -3, &["readonly"], 1, 1, 1),
+        CommandInfo::new("xclaim", -6, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("xautoclaim", -6, &["write", "fast"], 1, 1, 1),
+        CommandInfo::new("xinfo", -2, &["readonly"], 2, 2, 1),
         CommandInfo::new("config", -2, &["admin", "readonly"], 0, 0, 0),
         CommandInfo::new("info", -1, &["readonly"], 0, 0, 0),
         CommandInfo::new("hello", -1, &["readonly", "fast"], 0, 0, 0),
@@ -1,20 +1,25 @@
+use crate::lazyfree::LazyFreeOption;
 use crate::lru::{EvictionPolicy, LruManager};
+use crate::util::glob_match;
 use anyhow::Result;
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
 
 pub async fn config_get_cmd(
     pattern: String,
+    databases: usize,
     lru: &LruManager,
     socket: &mut TcpStream,
 ) -> Result<()> {
+    // Parameter names are matched case-insensitively, as globs.
     let pattern = pattern.to_lowercase();
+    let matches = |name: &str| glob_match(pattern.as_bytes(), name.as_bytes());
     let mut pairs: Vec<(String, String)> = Vec::new();
 
-    if pattern == "*" || pattern == "maxmemory" {
+    if matches("maxmemory") {
         pairs.push(("maxmemory".to_string(), lru.maxmemory().to_string()));
     }
-    if pattern == "*" || pattern == "maxmemory-policy" {
+    if matches("maxmemory-policy") {
         let policy = match lru.policy() {
             EvictionPolicy::NoEviction => "noeviction",
             EvictionPolicy::AllKeysLru => "allkeys-lru",
@@ -22,6 +27,19 @@ pub async fn config_get_cmd(
         };
         pairs.push(("maxmemory-policy".to_string(), policy.to_string()));
     }
+    if matches("databases") {
+        pairs.push(("databases".to_string(), databases.to_string()));
+    }
+    for option in LazyFreeOption::ALL {
+        if matches(option.name()) {
+            let enabled = if lru.lazyfree().enabled(option) {
+                "yes"
+            } else {
+                "no"
+            };
+            pairs.push((option.name().to_string(), enabled.to_string()));
+        }
+    }
 
     if pairs.is_empty() {
         socket.write_all(b"*0\r\n").await?;
@@ -48,7 +66,9 @@ pub async fn config_set_cmd(
     let key = key.to_lowercase();
     match key.as_str() {
         "maxmemory" => {
-            let v: usize = value.parse().map_err(|_| anyhow::anyhow!("invalid maxmemory"))?;
+            let v: usize = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid maxmemory"))?;
             lru.set_maxmemory(v);
             socket.write_all(b"+OK\r\n").await?;
         }
@@ -62,6 +82,19 @@ pub async fn config_set_cmd(
             lru.set_policy(policy);
             socket.write_all(b"+OK\r\n").await?;
         }
+        name if let Some(option) = LazyFreeOption::parse(name) => {
+            match value.to_lowercase().as_str() {
+                "yes" => lru.lazyfree().set_enabled(option, true),
+                "no" => lru.lazyfree().set_enabled(option, false),
+                _ => {
+                    socket
+                        .write_all(b"-ERR argument must be 'yes' or 'no'\r\n")
+                        .await?;
+                    return Ok(());
+                }
+            }
+            socket.write_all(b"+OK\r\n").await?;
+        }
         _ => {
             socket
                 .write_all(b"-ERR Unsupported CONFIG parameter\r\n")
@@ -0,0 +1,81 @@
+use crate::{
+    blocking::BlockingManager,
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap},
+    util::{OOM_ERR, drop_expired, expiry_records},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// COPY. The copy gets the source's TTL and hash field deadlines; with
+/// `replace` an existing destination is overwritten, otherwise left alone.
+#[allow(clippy::too_many_arguments)]
+pub async fn copy_cmd(
+    source: String,
+    destination: String,
+    replace: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    if source == destination {
+        socket
+            .write_all(b"-ERR source and destination objects are the same\r\n")
+            .await?;
+        return Ok(());
+    }
+
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &source) + drop_expired(&mut db, &destination);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let Some(entry) = db.get(&source) else {
+        drop(db);
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    };
+    if !replace && db.contains_key(&destination) {
+        drop(db);
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    }
+
+    let copy = entry.clone();
+    let new_size = estimate_entry_bytes(&destination, &copy);
+    let records = expiry_records(&destination, &copy);
+    let replaced = db.insert(destination.clone(), copy);
+    let replaced_size = replaced
+        .as_ref()
+        .map_or(0, |old| estimate_entry_bytes(&destination, old));
+    drop(db);
+
+    let delta = new_size as isize - replaced_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        match replaced {
+            Some(old) => db.insert(destination, old),
+            None => db.remove(&destination),
+        };
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    if !records.is_empty() {
+        let mut heap = _heap.lock().await;
+        for record in records {
+            heap.push(record);
+        }
+    }
+    blocking.wake_all(&destination);
+
+    socket.write_all(b":1\r\n").await?;
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{CuckooFilter, DB, Entry, Heap, Value},
+    util::{Lookup, OOM_ERR, WRONGTYPE_ERR, drop_expired, integer, read_value, update_value},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn cf_add_cmd(
+    key: String,
+    item: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::Cuckoo(CuckooFilter::default()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(cuckoo) = entry.value.as_cuckoo_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+    let tables_before = cuckoo.table_count();
+    cuckoo.add(&item);
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        // A failed insert leaves the full tables untouched, so the item
+        // lives alone in the table it grew.
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(cuckoo) = db.get_mut(&key).and_then(|e| e.value.as_cuckoo_mut()) {
+            cuckoo.truncate(tables_before);
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(1)).await?;
+
+    Ok(())
+}
+
+pub async fn cf_del_cmd(
+    key: String,
+    item: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        value.as_cuckoo_mut().map(|cuckoo| cuckoo.delete(&item))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(deleted) => socket.write_all(&integer(deleted as i64)).await?,
+        Lookup::Missing => socket.write_all(b"-ERR not found\r\n").await?,
+    }
+
+    Ok(())
+}
+
+pub async fn cf_exists_cmd(
+    key: String,
+    item: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_cuckoo().map(|cuckoo| cuckoo.contains(&item))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(found) => socket.write_all(&integer(found as i64)).await?,
+        Lookup::Missing => socket.write_all(&integer(0)).await?,
+    }
+
+    Ok(())
+}
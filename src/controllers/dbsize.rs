@@ -0,0 +1,13 @@
+use crate::model::DB;
+use crate::util::integer;
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// Counts every stored key, including expired ones the background task has
+/// not collected yet, as Redis does.
+pub async fn dbsize_cmd(_db: &DB, socket: &mut TcpStream) -> Result<()> {
+    let len = _db.read().await.len();
+    socket.write_all(&integer(len as i64)).await?;
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use crate::{
+    lazyfree::LazyFreeOption,
     lru::{estimate_entry_bytes, LruManager},
     model::DB,
 };
@@ -6,8 +7,12 @@ use anyhow::Result;
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
 
+/// DEL and UNLINK. The values are dropped after the write lock is released;
+/// UNLINK, or DEL with `lazyfree-lazy-user-del`, leaves large ones to the
+/// background free task.
 pub async fn del_cmd(
     keys: Vec<String>,
+    unlink: bool,
     _db: &DB,
     lru: &LruManager,
     socket: &mut TcpStream,
@@ -15,6 +20,7 @@ pub async fn del_cmd(
     let mut removed_count = 0;
     let mut removed_bytes = 0usize;
     let mut removed_keys: Vec<String> = Vec::new();
+    let mut removed_values = Vec::new();
     {
         let mut db = _db.write().await;
 
@@ -23,6 +29,7 @@ pub async fn del_cmd(
                 removed_count += 1;
                 removed_bytes += estimate_entry_bytes(&stored_key, &entry);
                 removed_keys.push(stored_key);
+                removed_values.push(entry.value);
             }
         }
     }
@@ -33,6 +40,9 @@ pub async fn del_cmd(
     if removed_bytes > 0 {
         lru.adjust_used_bytes(-(removed_bytes as isize));
     }
-    lru.remove_keys(&removed_keys).await;
+    lru.remove_keys(_db, &removed_keys).await;
+
+    let lazy = unlink || lru.lazyfree().enabled(LazyFreeOption::UserDel);
+    lru.lazyfree().free(removed_values, lazy);
     Ok(())
 }
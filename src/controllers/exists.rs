@@ -11,7 +11,7 @@ pub async fn exists_cmd(
     socket: &mut TcpStream,
 ) -> Result<()> {
     let mut removed_count = 0;
-    let mut expired: Vec<(String, std::time::Instant)> = Vec::new();
+    let mut expired: Vec<(String, u64)> = Vec::new();
     {
         let db = _db.read().await;
 
@@ -1,33 +1,66 @@
-use crate::model::{DB, Heap, MinHeap};
+use crate::{
+    lazyfree::LazyFreeOption,
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, ExpireCondition, Heap, MinHeap, SetExpiry, unix_millis},
+    util::drop_expired,
+};
 use anyhow::Result;
-use std::time::{Duration, Instant};
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
 
+/// EXPIRE, PEXPIRE, EXPIREAT and PEXPIREAT. Replies 0 if the key does not
+/// exist or one of `conditions` blocked the update, 1 otherwise; a deadline
+/// that has already passed deletes the key.
 pub async fn expire_cmd(
     key: String,
-    seconds: u64,
+    expiry: SetExpiry,
+    conditions: Vec<ExpireCondition>,
     _db: &DB,
     _heap: &mut Heap,
+    lru: &LruManager,
     socket: &mut TcpStream,
 ) -> Result<()> {
+    let Some(expires_at) = expiry.deadline() else {
+        return Ok(());
+    };
+
     let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
 
-    match db.get_mut(&key) {
-        Some(entry) => {
-            let expires_at = Instant::now() + Duration::from_secs(seconds);
-            entry.expires_at = Some(expires_at);
-            socket.write_all(format!(":1\r\n").as_bytes()).await?;
-            drop(db);
+    let Some(entry) = db.get_mut(&key) else {
+        drop(db);
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    };
+    if !conditions
+        .iter()
+        .all(|c| c.allows(entry.expires_at, expires_at))
+    {
+        drop(db);
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    }
 
-            let mut heap = _heap.lock().await;
-            heap.push(MinHeap { key, expires_at });
-            return Ok(());
-        }
-        None => {
-            socket.write_all(format!(":0\r\n").as_bytes()).await?;
+    if expires_at <= unix_millis() {
+        let removed = db.remove_entry(&key);
+        drop(db);
+        if let Some((stored_key, entry)) = removed {
+            lru.adjust_used_bytes(-(estimate_entry_bytes(&stored_key, &entry) as isize));
+            lru.remove_key(_db, &stored_key).await;
+            let lazy = lru.lazyfree().enabled(LazyFreeOption::Expire);
+            lru.lazyfree().free(vec![entry.value], lazy);
         }
+    } else {
+        entry.expires_at = Some(expires_at);
+        drop(db);
+
+        let mut heap = _heap.lock().await;
+        heap.push(MinHeap { key, expires_at });
     }
 
+    socket.write_all(b":1\r\n").await?;
     Ok(())
 }
@@ -0,0 +1,52 @@
+use crate::model::{DB, Heap, MinHeap};
+use crate::util::is_expired;
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// EXPIRETIME and PEXPIRETIME: the absolute Unix time, in seconds or
+/// milliseconds, at which the key expires; -1 if it never does and -2 if
+/// it does not exist.
+pub async fn expiretime_cmd(
+    key: String,
+    millis: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    // The deadline is copied out so the heap and the socket are only touched
+    // after the read lock is released, as the cleaner takes the heap first.
+    let mut live = None;
+    let mut expired = None;
+    {
+        let db = _db.read().await;
+        if let Some(entry) = db.get(&key) {
+            if is_expired(entry) {
+                expired = entry.expires_at;
+            } else {
+                live = Some(entry.expires_at);
+            }
+        }
+    }
+
+    if let Some(expires_at) = expired {
+        let mut heap = _heap.lock().await;
+        heap.push(MinHeap {
+            key: key.clone(),
+            expires_at,
+        });
+    }
+
+    match live {
+        None => socket.write_all(b":-2\r\n").await?,
+        Some(None) => socket.write_all(b":-1\r\n").await?,
+        Some(Some(time)) => {
+            let time = if millis { time } else { time / 1000 };
+            socket
+                .write_all(format!(":{}\r\n", time).as_bytes())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
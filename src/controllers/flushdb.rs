@@ -0,0 +1,67 @@
+use crate::{
+    databases::Databases,
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// FLUSHDB: empties the selected database.
+pub async fn flushdb_cmd(
+    lazy: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    flush(_db, _heap, lru, lazy).await;
+    socket.write_all(b"+OK\r\n").await?;
+    Ok(())
+}
+
+/// FLUSHALL: empties every database.
+pub async fn flushall_cmd(
+    lazy: bool,
+    databases: &Databases,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    for database in databases.iter() {
+        flush(&database.db, &database.heap, lru, lazy).await;
+    }
+    socket.write_all(b"+OK\r\n").await?;
+    Ok(())
+}
+
+/// Swaps the keyspace, its expiry heap and its recency out for empty ones
+/// before replying, so keys written right after the flush are tracked from a
+/// clean slate. Releasing the memory the keys were charged for means
+/// visiting every value, so with `lazy` (ASYNC) that and dropping the old
+/// data are left to a blocking thread and the caller does not wait on
+/// millions of values.
+async fn flush(_db: &DB, _heap: &Heap, lru: &LruManager, lazy: bool) {
+    let mut heap = _heap.lock().await;
+    let mut db = _db.write().await;
+    let removed = std::mem::take(&mut *db);
+    let records = std::mem::take(&mut *heap);
+    let recency = lru.take_recency(_db).await;
+    drop((db, heap));
+
+    let lru = lru.clone();
+    let release = move || {
+        let freed: usize = removed
+            .iter()
+            .map(|(key, entry)| estimate_entry_bytes(key, entry))
+            .sum();
+        if freed > 0 {
+            lru.adjust_used_bytes(-(freed as isize));
+        }
+        drop((removed, records, recency));
+    };
+    if lazy {
+        tokio::task::spawn_blocking(release);
+    } else {
+        release();
+    }
+}
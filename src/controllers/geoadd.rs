@@ -0,0 +1,24 @@
+use super::zadd_cmd;
+use crate::{
+    lru::LruManager,
+    model::{DB, Heap, ZAddFlags, geohash_encode},
+};
+use anyhow::Result;
+use tokio::net::TcpStream;
+
+/// ZADD with each `(longitude, latitude)` encoded as a geohash score.
+pub async fn geoadd_cmd(
+    key: String,
+    flags: ZAddFlags,
+    points: Vec<(f64, f64, Vec<u8>)>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let pairs = points
+        .into_iter()
+        .map(|(lon, lat, member)| (geohash_encode(lon, lat) as f64, member))
+        .collect();
+    zadd_cmd(key, flags, pairs, _db, _heap, lru, socket).await
+}
@@ -0,0 +1,36 @@
+use crate::model::{DB, GeoUnit, Heap, geo_distance, geohash_decode};
+use crate::util::{Lookup, WRONGTYPE_ERR, bulk_str, null_bulk, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn geodist_cmd(
+    key: String,
+    member1: Vec<u8>,
+    member2: Vec<u8>,
+    unit: GeoUnit,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_zset().map(|zset| {
+            let from = geohash_decode(zset.score(&member1)? as u64);
+            let to = geohash_decode(zset.score(&member2)? as u64);
+            Some(geo_distance(from, to) / unit.meters())
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(distance)) => {
+            socket
+                .write_all(&bulk_str(&format!("{:.4}", distance)))
+                .await?
+        }
+        Lookup::Found(None) | Lookup::Missing => socket.write_all(&null_bulk()).await?,
+    }
+
+    Ok(())
+}
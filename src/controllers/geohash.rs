@@ -0,0 +1,44 @@
+use crate::model::{DB, Heap, geohash_decode, geohash_string};
+use crate::util::{Lookup, WRONGTYPE_ERR, array_len, bulk_str, null_bulk, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn geohash_cmd(
+    key: String,
+    members: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_zset().map(|zset| {
+            let mut resp = array_len(members.len());
+            for member in &members {
+                match zset.score(member) {
+                    Some(score) => {
+                        let (lon, lat) = geohash_decode(score as u64);
+                        resp.extend_from_slice(&bulk_str(&geohash_string(lon, lat)));
+                    }
+                    None => resp.extend_from_slice(&null_bulk()),
+                }
+            }
+            resp
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => {
+            let mut resp = array_len(members.len());
+            for _ in &members {
+                resp.extend_from_slice(&null_bulk());
+            }
+            socket.write_all(&resp).await?
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::model::{DB, Heap, geohash_decode};
+use crate::util::{
+    Lookup, WRONGTYPE_ERR, array_len, bulk_str, format_float, null_array, read_value,
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn geopos_cmd(
+    key: String,
+    members: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_zset().map(|zset| {
+            members
+                .iter()
+                .map(|member| zset.score(member))
+                .collect::<Vec<_>>()
+        })
+    })
+    .await;
+
+    let scores = match lookup {
+        Lookup::WrongType => {
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+        Lookup::Found(scores) => scores,
+        Lookup::Missing => vec![None; members.len()],
+    };
+
+    let mut resp = array_len(scores.len());
+    for score in scores {
+        match score {
+            Some(score) => {
+                let (lon, lat) = geohash_decode(score as u64);
+                resp.extend_from_slice(&array_len(2));
+                resp.extend_from_slice(&bulk_str(&format_float(lon)));
+                resp.extend_from_slice(&bulk_str(&format_float(lat)));
+            }
+            None => resp.extend_from_slice(&null_array()),
+        }
+    }
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
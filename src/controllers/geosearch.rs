@@ -0,0 +1,200 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, GeoFrom, GeoSearch, Heap, Value, ZSet, geohash_decode},
+    util::{
+        Lookup, OOM_ERR, WRONGTYPE_ERR, array_len, bulk_bytes, bulk_str, format_float, integer,
+        is_expired, read_value,
+    },
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+const NO_MEMBER_ERR: &[u8] = b"-ERR could not decode requested zset member\r\n";
+
+struct GeoMatch<'a> {
+    member: &'a Vec<u8>,
+    score: f64,
+    /// Distance from the search centre in meters.
+    distance: f64,
+    lon: f64,
+    lat: f64,
+}
+
+pub async fn geosearch_cmd(
+    key: String,
+    search: GeoSearch,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_zset().map(|zset| {
+            let matches = find(zset, &search)?;
+            let mut resp = array_len(matches.len());
+            for found in &matches {
+                resp.extend_from_slice(&match_resp(found, &search));
+            }
+            Some(resp)
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(resp)) => socket.write_all(&resp).await?,
+        Lookup::Found(None) => socket.write_all(NO_MEMBER_ERR).await?,
+        Lookup::Missing => socket.write_all(b"*0\r\n").await?,
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn geosearchstore_cmd(
+    destination: String,
+    source: String,
+    search: GeoSearch,
+    store_dist: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+
+    let mut result = ZSet::new();
+    match db.get(&source) {
+        Some(entry) if !is_expired(entry) => {
+            let Some(zset) = entry.value.as_zset() else {
+                drop(db);
+                socket.write_all(WRONGTYPE_ERR).await?;
+                return Ok(());
+            };
+            let Some(matches) = find(zset, &search) else {
+                drop(db);
+                socket.write_all(NO_MEMBER_ERR).await?;
+                return Ok(());
+            };
+            for found in matches {
+                let score = if store_dist {
+                    found.distance / search.unit.meters()
+                } else {
+                    found.score
+                };
+                result.insert(found.member.clone(), score);
+            }
+        }
+        _ => {}
+    }
+
+    let len = result.len();
+    let old = db.remove(&destination);
+    let old_size = old
+        .as_ref()
+        .map(|entry| estimate_entry_bytes(&destination, entry))
+        .unwrap_or(0);
+
+    let mut new_size = 0usize;
+    if len > 0 {
+        let new_entry = Entry {
+            value: Value::ZSet(result),
+            expires_at: None,
+        };
+        new_size = estimate_entry_bytes(&destination, &new_entry);
+        db.insert(destination.clone(), new_entry);
+    }
+    drop(db);
+
+    if len == 0 && old.is_some() {
+        lru.remove_key(_db, &destination).await;
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        match old {
+            Some(old_entry) => {
+                db.insert(destination.clone(), old_entry);
+            }
+            None => {
+                db.remove(&destination);
+            }
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(len as i64)).await?;
+
+    Ok(())
+}
+
+/// Collects the members inside the search shape, sorted and limited as
+/// requested. Returns `None` when the FROMMEMBER member does not exist.
+fn find<'a>(zset: &'a ZSet, search: &GeoSearch) -> Option<Vec<GeoMatch<'a>>> {
+    let center = match &search.from {
+        GeoFrom::Member(member) => geohash_decode(zset.score(member)? as u64),
+        GeoFrom::LonLat(lon, lat) => (*lon, *lat),
+    };
+
+    let mut matches = Vec::new();
+    for (member, score) in zset.iter() {
+        let (lon, lat) = geohash_decode(score as u64);
+        let Some(distance) = search.shape.distance_within(center, (lon, lat)) else {
+            continue;
+        };
+        matches.push(GeoMatch {
+            member,
+            score,
+            distance,
+            lon,
+            lat,
+        });
+        if search.any && search.count.is_some_and(|count| matches.len() >= count) {
+            break;
+        }
+    }
+
+    // COUNT without ANY keeps the closest matches.
+    let asc = search
+        .asc
+        .or((search.count.is_some() && !search.any).then_some(true));
+    match asc {
+        Some(true) => matches.sort_by(|a, b| a.distance.total_cmp(&b.distance)),
+        Some(false) => matches.sort_by(|a, b| b.distance.total_cmp(&a.distance)),
+        None => {}
+    }
+    if let Some(count) = search.count {
+        matches.truncate(count);
+    }
+    Some(matches)
+}
+
+/// A bare member, or `[member, dist?, hash?, [lon, lat]?]` when any WITH
+/// option was given.
+fn match_resp(found: &GeoMatch, search: &GeoSearch) -> Vec<u8> {
+    if !(search.with_dist || search.with_hash || search.with_coord) {
+        return bulk_bytes(found.member);
+    }
+
+    let fields =
+        1 + search.with_dist as usize + search.with_hash as usize + search.with_coord as usize;
+    let mut resp = array_len(fields);
+    resp.extend_from_slice(&bulk_bytes(found.member));
+    if search.with_dist {
+        let distance = found.distance / search.unit.meters();
+        resp.extend_from_slice(&bulk_str(&format!("{:.4}", distance)));
+    }
+    if search.with_hash {
+        resp.extend_from_slice(&integer(found.score as i64));
+    }
+    if search.with_coord {
+        resp.extend_from_slice(&array_len(2));
+        resp.extend_from_slice(&bulk_str(&format_float(found.lon)));
+        resp.extend_from_slice(&bulk_str(&format_float(found.lat)));
+    }
+    resp
+}
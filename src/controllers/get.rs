@@ -1,5 +1,5 @@
-use crate::model::{DB, Heap, MinHeap};
-use crate::util::is_expired;
+use crate::model::{DB, Heap, MinHeap, Value};
+use crate::util::{WRONGTYPE_ERR, is_expired};
 use anyhow::Result;
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
@@ -14,15 +14,15 @@ pub async fn get_cmd(
     let mut resp: Option<Vec<u8>> = None;
     {
         let db = _db.read().await;
-        match db.get(&key) {
-            Some(entry) => {
-                if is_expired(entry) {
-                    expires_at = entry.expires_at;
-                } else {
-                    resp = Some(entry.value.to_resp_bytes());
-                }
+        if let Some(entry) = db.get(&key) {
+            if is_expired(entry) {
+                expires_at = entry.expires_at;
+            } else {
+                resp = Some(match entry.value {
+                    Value::String(_) | Value::Int(_) => entry.value.to_resp_bytes(),
+                    _ => WRONGTYPE_ERR.to_vec(),
+                });
             }
-            None => {}
         }
     }
 
@@ -0,0 +1,26 @@
+use crate::model::{DB, Heap, get_bit};
+use crate::util::{Lookup, WRONGTYPE_ERR, integer, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn getbit_cmd(
+    key: String,
+    offset: u64,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_string_bytes().map(|bytes| get_bit(&bytes, offset))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(bit) => socket.write_all(&integer(bit as i64)).await?,
+        Lookup::Missing => socket.write_all(b":0\r\n").await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap},
+    util::{WRONGTYPE_ERR, bulk_bytes, drop_expired, null_bulk},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn getdel_cmd(
+    key: String,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let resp = match db
+        .get(&key)
+        .map(|e| e.value.as_string_bytes().map(|b| bulk_bytes(&b)))
+    {
+        None => {
+            drop(db);
+            socket.write_all(&null_bulk()).await?;
+            return Ok(());
+        }
+        Some(None) => {
+            drop(db);
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+        Some(Some(resp)) => resp,
+    };
+
+    let removed = db
+        .remove_entry(&key)
+        .map(|(stored_key, entry)| estimate_entry_bytes(&stored_key, &entry))
+        .unwrap_or(0);
+    drop(db);
+
+    lru.adjust_used_bytes(-(removed as isize));
+    lru.remove_key(_db, &key).await;
+
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
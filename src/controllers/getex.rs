@@ -0,0 +1,51 @@
+use crate::{
+    model::{DB, Heap, MinHeap, SetExpiry},
+    util::{WRONGTYPE_ERR, bulk_bytes, is_expired, null_bulk},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// GET that can also set (`expiry`) or clear (`persist`) the key's TTL.
+pub async fn getex_cmd(
+    key: String,
+    expiry: Option<SetExpiry>,
+    persist: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut expired_at = None;
+    let mut new_expiry = None;
+    let resp = {
+        let mut db = _db.write().await;
+        match db.get_mut(&key) {
+            None => null_bulk(),
+            Some(entry) if is_expired(entry) => {
+                expired_at = entry.expires_at;
+                null_bulk()
+            }
+            Some(entry) => match entry.value.as_string_bytes().map(|b| bulk_bytes(&b)) {
+                None => WRONGTYPE_ERR.to_vec(),
+                Some(resp) => {
+                    if persist {
+                        entry.expires_at = None;
+                    } else if let Some(deadline) = expiry.and_then(|e| e.deadline()) {
+                        entry.expires_at = Some(deadline);
+                        new_expiry = Some(deadline);
+                    }
+                    resp
+                }
+            },
+        }
+    };
+
+    if let Some(expires_at) = expired_at.or(new_expiry) {
+        let mut heap = _heap.lock().await;
+        heap.push(MinHeap { key, expires_at });
+    }
+
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, bulk_bytes, read_value, string_range};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn getrange_cmd(
+    key: String,
+    start: i64,
+    end: i64,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value
+            .as_string_bytes()
+            .map(|bytes| match string_range(bytes.len(), start, end) {
+                Some((start, end)) => bulk_bytes(&bytes[start..=end]),
+                None => bulk_bytes(b""),
+            })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(&bulk_bytes(b"")).await?,
+    }
+
+    Ok(())
+}
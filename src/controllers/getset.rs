@@ -0,0 +1,23 @@
+use super::set_cmd;
+use crate::{
+    lru::LruManager,
+    model::{DB, Heap, SetOptions},
+};
+use anyhow::Result;
+use tokio::net::TcpStream;
+
+/// Same as `SET key value GET`.
+pub async fn getset_cmd(
+    key: String,
+    value: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let options = SetOptions {
+        get: true,
+        ..SetOptions::default()
+    };
+    set_cmd(key, value, options, _db, _heap, lru, socket).await
+}
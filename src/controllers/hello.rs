@@ -3,13 +3,14 @@ use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
 
 pub async fn hello_cmd(version: Option<u8>, socket: &mut TcpStream) -> Result<()> {
-    if let Some(v) = version {
-        if v != 2 && v != 3 {
-            socket
-                .write_all(b"-ERR unsupported HELLO version\r\n")
-                .await?;
-            return Ok(());
-        }
+    if let Some(v) = version
+        && v != 2
+        && v != 3
+    {
+        socket
+            .write_all(b"-ERR unsupported HELLO version\r\n")
+            .await?;
+        return Ok(());
     }
 
     let parts = [
@@ -0,0 +1,26 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn hexists_cmd(
+    key: String,
+    field: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_hash().map(|hash| hash.contains_key(&field))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(true) => socket.write_all(b":1\r\n").await?,
+        Lookup::Found(false) | Lookup::Missing => socket.write_all(b":0\r\n").await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, ExpireCondition, Heap, MinHeap, SetExpiry, unix_millis},
+    util::{WRONGTYPE_ERR, drop_expired, integer_array},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// HEXPIRE, HPEXPIRE and HEXPIREAT. Each field replies -2 if it does not
+/// exist, 0 if `condition` blocked the update, 1 if the deadline was set
+/// and 2 if the deadline had already passed and the field was deleted.
+#[allow(clippy::too_many_arguments)]
+pub async fn hexpire_cmd(
+    key: String,
+    expiry: SetExpiry,
+    condition: Option<ExpireCondition>,
+    fields: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let Some(deadline) = expiry.deadline() else {
+        return Ok(());
+    };
+
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let Some(entry) = db.get_mut(&key) else {
+        drop(db);
+        socket
+            .write_all(&integer_array(&vec![-2; fields.len()]))
+            .await?;
+        return Ok(());
+    };
+    let old_size = estimate_entry_bytes(&key, entry);
+    let Some(hash) = entry.value.as_hash_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let now = unix_millis();
+    let mut codes = Vec::with_capacity(fields.len());
+    let mut scheduled = false;
+    for field in &fields {
+        let Some(current) = hash.expires_at(field) else {
+            codes.push(-2);
+            continue;
+        };
+        if condition.is_some_and(|c| !c.allows(current, deadline)) {
+            codes.push(0);
+        } else if deadline <= now {
+            hash.remove(field);
+            codes.push(2);
+        } else {
+            hash.set_expires_at(field, deadline);
+            scheduled = true;
+            codes.push(1);
+        }
+    }
+
+    let remove_key = hash.is_empty();
+    let new_size = if remove_key {
+        db.remove(&key);
+        0
+    } else {
+        estimate_entry_bytes(&key, &db[&key])
+    };
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if delta != 0 {
+        lru.adjust_used_bytes(delta);
+    }
+    if remove_key {
+        lru.remove_key(_db, &key).await;
+    }
+
+    // One record covers every field given this deadline; the cleaner
+    // purges whatever has expired when it comes due.
+    if scheduled {
+        let mut heap = _heap.lock().await;
+        heap.push(MinHeap {
+            key,
+            expires_at: deadline,
+        });
+    }
+
+    socket.write_all(&integer_array(&codes)).await?;
+
+    Ok(())
+}
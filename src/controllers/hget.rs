@@ -0,0 +1,28 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, bulk_bytes, null_bulk, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn hget_cmd(
+    key: String,
+    field: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value
+            .as_hash()
+            .map(|hash| hash.get(&field).map(|v| bulk_bytes(v)))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(resp)) => socket.write_all(&resp).await?,
+        Lookup::Found(None) | Lookup::Missing => socket.write_all(&null_bulk()).await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::model::{DB, Hash, Heap, Value};
+use crate::util::{Lookup, WRONGTYPE_ERR, array_len, bulk_bytes, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn hgetall_cmd(
+    key: String,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| match value {
+        Value::Hash(hash) => Some(hash_resp(hash)),
+        _ => None,
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(b"*0\r\n").await?,
+    }
+
+    Ok(())
+}
+
+/// Every live field followed by its value, as one flat array.
+fn hash_resp(hash: &Hash) -> Vec<u8> {
+    let mut resp = array_len(hash.len() * 2);
+    for (field, value) in hash.iter() {
+        resp.extend_from_slice(&bulk_bytes(field));
+        resp.extend_from_slice(&bulk_bytes(value));
+    }
+    resp
+}
@@ -0,0 +1,92 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Hash, Heap, Value},
+    util::{OOM_ERR, WRONGTYPE_ERR, drop_expired, integer},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn hincrby_cmd(
+    key: String,
+    field: Vec<u8>,
+    increment: i64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::Hash(Hash::default()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(hash) = entry.value.as_hash_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let current = match hash.get(&field) {
+        Some(bytes) => match std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+        {
+            Some(n) => n,
+            None => {
+                drop(db);
+                socket
+                    .write_all(b"-ERR hash value is not an integer\r\n")
+                    .await?;
+                return Ok(());
+            }
+        },
+        None => 0,
+    };
+
+    let Some(next) = current.checked_add(increment) else {
+        drop(db);
+        socket
+            .write_all(b"-ERR increment or decrement would overflow\r\n")
+            .await?;
+        return Ok(());
+    };
+
+    let previous = hash.update(field.clone(), next.to_string().into_bytes());
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(hash) = db.get_mut(&key).and_then(|e| e.value.as_hash_mut()) {
+            match previous {
+                Some(value) => hash.update(field, value),
+                None => hash.remove(&field),
+            };
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(next)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, array_len, bulk_bytes, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn hkeys_cmd(
+    key: String,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_hash().map(|hash| {
+            let mut resp = array_len(hash.len());
+            for field in hash.keys() {
+                resp.extend_from_slice(&bulk_bytes(field));
+            }
+            resp
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(b"*0\r\n").await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, array_len, bulk_bytes, null_bulk, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn hmget_cmd(
+    key: String,
+    fields: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_hash().map(|hash| {
+            let mut resp = array_len(fields.len());
+            for field in &fields {
+                match hash.get(field) {
+                    Some(v) => resp.extend_from_slice(&bulk_bytes(v)),
+                    None => resp.extend_from_slice(&null_bulk()),
+                }
+            }
+            resp
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => {
+            let mut resp = array_len(fields.len());
+            for _ in &fields {
+                resp.extend_from_slice(&null_bulk());
+            }
+            socket.write_all(&resp).await?
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::{
+    lru::LruManager,
+    model::{DB, Heap},
+    util::{Lookup, WRONGTYPE_ERR, integer_array, update_value},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// HPERSIST: per field, 1 if its deadline was cleared, -1 if it had none
+/// and -2 if it does not exist.
+pub async fn hpersist_cmd(
+    key: String,
+    fields: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        let hash = value.as_hash_mut()?;
+        Some(
+            fields
+                .iter()
+                .map(|field| match hash.expires_at(field) {
+                    None => -2,
+                    Some(None) => -1,
+                    Some(Some(_)) => {
+                        hash.persist(field);
+                        1
+                    }
+                })
+                .collect::<Vec<i64>>(),
+        )
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(codes) => socket.write_all(&integer_array(&codes)).await?,
+        Lookup::Missing => {
+            socket
+                .write_all(&integer_array(&vec![-2; fields.len()]))
+                .await?
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::model::{DB, Heap};
+use crate::util::{
+    Lookup, REPLY_CHUNK_BYTES, WRONGTYPE_ERR, array_len, bulk_bytes, null_bulk, random_index,
+    read_value,
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn hrandfield_cmd(
+    key: String,
+    count: Option<i64>,
+    with_values: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_hash().map(|hash| {
+            let pairs = hash
+                .iter()
+                .map(|(field, value)| (field.clone(), value.clone()));
+            match count {
+                Some(count) if count >= 0 => {
+                    let mut pairs: Vec<(Vec<u8>, Vec<u8>)> = pairs.collect();
+                    let take = (count as usize).min(pairs.len());
+                    for i in 0..take {
+                        let j = i + random_index(pairs.len() - i);
+                        pairs.swap(i, j);
+                    }
+                    pairs.truncate(take);
+                    pairs
+                }
+                Some(_) => pairs.collect(),
+                None => pairs.skip(random_index(hash.len())).take(1).collect(),
+            }
+        })
+    })
+    .await;
+
+    let pairs = match lookup {
+        Lookup::WrongType => {
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+        Lookup::Missing => {
+            if count.is_some() {
+                socket.write_all(b"*0\r\n").await?;
+            } else {
+                socket.write_all(&null_bulk()).await?;
+            }
+            return Ok(());
+        }
+        Lookup::Found(pairs) => pairs,
+    };
+
+    let per_item = if with_values { 2 } else { 1 };
+    let encode = |resp: &mut Vec<u8>, (field, value): &(Vec<u8>, Vec<u8>)| {
+        resp.extend_from_slice(&bulk_bytes(field));
+        if with_values {
+            resp.extend_from_slice(&bulk_bytes(value));
+        }
+    };
+
+    match count {
+        None => socket.write_all(&bulk_bytes(&pairs[0].0)).await?,
+        Some(count) if count < 0 => {
+            // The count, not the hash, sizes this reply, so it goes out in chunks.
+            let mut resp = array_len(count.unsigned_abs() as usize * per_item);
+            for _ in 0..count.unsigned_abs() {
+                encode(&mut resp, &pairs[random_index(pairs.len())]);
+                if resp.len() >= REPLY_CHUNK_BYTES {
+                    socket.write_all(&resp).await?;
+                    resp.clear();
+                }
+            }
+            socket.write_all(&resp).await?;
+        }
+        Some(_) => {
+            let mut resp = array_len(pairs.len() * per_item);
+            for pair in &pairs {
+                encode(&mut resp, pair);
+            }
+            socket.write_all(&resp).await?;
+        }
+    }
+
+    Ok(())
+}
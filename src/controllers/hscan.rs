@@ -0,0 +1,63 @@
+use crate::model::{DB, Heap};
+use crate::util::{
+    Lookup, WRONGTYPE_ERR, array_len, bulk_bytes, bulk_str, glob_match, read_value, scan_hash,
+    scan_page,
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn hscan_cmd(
+    key: String,
+    cursor: u64,
+    pattern: Option<Vec<u8>>,
+    count: usize,
+    no_values: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_hash().map(|hash| {
+            let entries = hash
+                .iter()
+                .map(|(field, value)| (scan_hash(field), (field, value)));
+            let (next, page) = scan_page(entries, cursor, count);
+            let page: Vec<(Vec<u8>, Vec<u8>)> = page
+                .into_iter()
+                .map(|(field, value)| (field.clone(), value.clone()))
+                .collect();
+            (next, page)
+        })
+    })
+    .await;
+
+    let (next, page) = match lookup {
+        Lookup::WrongType => {
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+        Lookup::Found(found) => found,
+        Lookup::Missing => (0, Vec::new()),
+    };
+
+    let page: Vec<(Vec<u8>, Vec<u8>)> = page
+        .into_iter()
+        .filter(|(field, _)| pattern.as_ref().is_none_or(|p| glob_match(p, field)))
+        .collect();
+
+    let per_item = if no_values { 1 } else { 2 };
+    let mut resp = array_len(2);
+    resp.extend_from_slice(&bulk_str(&next.to_string()));
+    resp.extend_from_slice(&array_len(page.len() * per_item));
+    for (field, value) in page {
+        resp.extend_from_slice(&bulk_bytes(&field));
+        if !no_values {
+            resp.extend_from_slice(&bulk_bytes(&value));
+        }
+    }
+
+    socket.write_all(&resp).await?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Hash, Heap, Value},
+    util::{OOM_ERR, WRONGTYPE_ERR, drop_expired, integer},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn hset_cmd(
+    key: String,
+    pairs: Vec<(Vec<u8>, Vec<u8>)>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::Hash(Hash::default()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(hash) = entry.value.as_hash_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let mut added = 0;
+    let mut previous: Vec<(Vec<u8>, Option<Vec<u8>>)> = Vec::with_capacity(pairs.len());
+    for (field, value) in pairs {
+        let old = hash.insert(field.clone(), value);
+        if old.is_none() {
+            added += 1;
+        }
+        previous.push((field, old));
+    }
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(hash) = db.get_mut(&key).and_then(|e| e.value.as_hash_mut()) {
+            for (field, old) in previous.into_iter().rev() {
+                match old {
+                    Some(value) => hash.insert(field, value),
+                    None => hash.remove(&field),
+                };
+            }
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(added)).await?;
+
+    Ok(())
+}
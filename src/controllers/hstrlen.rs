@@ -0,0 +1,28 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, integer, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn hstrlen_cmd(
+    key: String,
+    field: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value
+            .as_hash()
+            .map(|hash| hash.get(&field).map_or(0, |v| v.len()))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(len) => socket.write_all(&integer(len as i64)).await?,
+        Lookup::Missing => socket.write_all(b":0\r\n").await?,
+    }
+
+    Ok(())
+}
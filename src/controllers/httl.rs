@@ -0,0 +1,51 @@
+use crate::model::{DB, Heap, unix_millis};
+use crate::util::{Lookup, WRONGTYPE_ERR, integer_array, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// HTTL and HPTTL: per field, the time left in seconds (or milliseconds),
+/// -1 if it never expires and -2 if it does not exist.
+pub async fn httl_cmd(
+    key: String,
+    fields: Vec<Vec<u8>>,
+    millis: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let hash = value.as_hash()?;
+        let now = unix_millis();
+        Some(
+            fields
+                .iter()
+                .map(|field| match hash.expires_at(field) {
+                    None => -2,
+                    Some(None) => -1,
+                    Some(Some(t)) => {
+                        let left = t.saturating_sub(now);
+                        if millis {
+                            left as i64
+                        } else {
+                            (left / 1000) as i64
+                        }
+                    }
+                })
+                .collect::<Vec<i64>>(),
+        )
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(codes) => socket.write_all(&integer_array(&codes)).await?,
+        Lookup::Missing => {
+            socket
+                .write_all(&integer_array(&vec![-2; fields.len()]))
+                .await?
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Value},
+    util::{OOM_ERR, WRONGTYPE_ERR, drop_expired, integer},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn incr_cmd(
+    key: String,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    incr_by(key, 1, _db, _heap, lru, socket).await
+}
+
+pub async fn decr_cmd(
+    key: String,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    incr_by(key, -1, _db, _heap, lru, socket).await
+}
+
+pub async fn incrby_cmd(
+    key: String,
+    increment: i64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    incr_by(key, increment, _db, _heap, lru, socket).await
+}
+
+pub async fn decrby_cmd(
+    key: String,
+    decrement: i64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let Some(increment) = decrement.checked_neg() else {
+        socket
+            .write_all(b"-ERR decrement would overflow\r\n")
+            .await?;
+        return Ok(());
+    };
+    incr_by(key, increment, _db, _heap, lru, socket).await
+}
+
+/// Adds `increment` to the integer stored at `key`, creating it at zero.
+/// The result is kept as `Value::Int` and any TTL is left untouched.
+async fn incr_by(
+    key: String,
+    increment: i64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::Int(0),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let current = match &entry.value {
+        Value::Int(n) => Some(*n),
+        Value::String(bytes) => parse_counter(bytes),
+        _ => {
+            drop(db);
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+    };
+    let Some(current) = current else {
+        drop(db);
+        socket
+            .write_all(b"-ERR value is not an integer or out of range\r\n")
+            .await?;
+        return Ok(());
+    };
+
+    let Some(next) = current.checked_add(increment) else {
+        if created_new {
+            db.remove(&key);
+        }
+        drop(db);
+        socket
+            .write_all(b"-ERR increment or decrement would overflow\r\n")
+            .await?;
+        return Ok(());
+    };
+
+    let previous = std::mem::replace(&mut entry.value, Value::Int(next));
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(entry) = db.get_mut(&key) {
+            entry.value = previous;
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(next)).await?;
+
+    Ok(())
+}
+
+/// Accepts only the canonical decimal form Redis would have produced, so
+/// `"+1"`, `" 1"` and `"01"` are rejected like they are upstream.
+fn parse_counter(bytes: &[u8]) -> Option<i64> {
+    let n = std::str::from_utf8(bytes).ok()?.parse::<i64>().ok()?;
+    (n.to_string().as_bytes() == bytes).then_some(n)
+}
@@ -0,0 +1,91 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Value},
+    util::{OOM_ERR, WRONGTYPE_ERR, bulk_str, drop_expired, format_float, parse_float},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn incrbyfloat_cmd(
+    key: String,
+    increment: f64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::Int(0),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let current = match &entry.value {
+        Value::Int(n) => Some(*n as f64),
+        Value::String(bytes) => parse_float(bytes),
+        _ => {
+            drop(db);
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+    };
+    let Some(current) = current else {
+        drop(db);
+        socket
+            .write_all(b"-ERR value is not a valid float\r\n")
+            .await?;
+        return Ok(());
+    };
+
+    let next = current + increment;
+    if !next.is_finite() {
+        if created_new {
+            db.remove(&key);
+        }
+        drop(db);
+        socket
+            .write_all(b"-ERR increment would produce NaN or Infinity\r\n")
+            .await?;
+        return Ok(());
+    }
+
+    // Stored as plain bytes, as Redis does, so the float text round-trips.
+    let formatted = format_float(next);
+    let previous = std::mem::replace(
+        &mut entry.value,
+        Value::String(formatted.clone().into_bytes()),
+    );
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(entry) = db.get_mut(&key) {
+            entry.value = previous;
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&bulk_str(&formatted)).await?;
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::databases::Databases;
 use crate::lru::{EvictionPolicy, LruManager};
 use crate::model::DB;
 use anyhow::Result;
@@ -7,6 +8,7 @@ use tokio::net::TcpStream;
 pub async fn info_cmd(
     section: Option<String>,
     db: &DB,
+    databases: &Databases,
     lru: &LruManager,
     socket: &mut TcpStream,
 ) -> Result<()> {
@@ -38,11 +40,31 @@ pub async fn info_cmd(
         out.push_str(&format!("used_memory:{}\r\n", used));
         out.push_str(&format!("maxmemory:{}\r\n", maxmemory));
         out.push_str(&format!("maxmemory_policy:{}\r\n", policy));
+        out.push_str(&format!(
+            "lazyfree_pending_objects:{}\r\n",
+            lru.lazyfree().pending()
+        ));
     }
     if want("stats") {
         out.push_str("# Stats\r\n");
         out.push_str(&format!("keys:{}\r\n", key_count));
     }
+    if want("keyspace") {
+        out.push_str("# Keyspace\r\n");
+        for (index, database) in databases.iter().enumerate() {
+            let db = database.db.read().await;
+            if db.is_empty() {
+                continue;
+            }
+            let expires = db.values().filter(|e| e.expires_at.is_some()).count();
+            out.push_str(&format!(
+                "db{}:keys={},expires={}\r\n",
+                index,
+                db.len(),
+                expires
+            ));
+        }
+    }
 
     let mut resp = Vec::new();
     resp.extend_from_slice(b"$");
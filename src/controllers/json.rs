@@ -0,0 +1,516 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Json, JsonPath, Value},
+    util::{
+        Lookup, OOM_ERR, WRONGTYPE_ERR, array_len, bulk_str, drop_expired, integer, null_bulk,
+        read_value, update_value,
+    },
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+const NEW_AT_ROOT_ERR: &[u8] = b"-ERR new objects must be created at the root\r\n";
+const NO_KEY_ERR: &[u8] = b"-ERR could not perform this operation on a key that doesn't exist\r\n";
+const DEPTH_ERR: &[u8] = b"-ERR the document would exceed the nesting limit\r\n";
+
+#[allow(clippy::too_many_arguments)]
+pub async fn json_set_cmd(
+    key: String,
+    path: JsonPath,
+    value: Json,
+    nx: bool,
+    xx: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    if !value.fits_at(path.steps.len()) {
+        socket.write_all(DEPTH_ERR).await?;
+        return Ok(());
+    }
+
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let (displaced, old_size) = match db.get_mut(&key) {
+        None => {
+            if !path.is_root() {
+                drop(db);
+                socket.write_all(NEW_AT_ROOT_ERR).await?;
+                return Ok(());
+            }
+            if xx {
+                drop(db);
+                socket.write_all(&null_bulk()).await?;
+                return Ok(());
+            }
+            db.insert(
+                key.clone(),
+                Entry {
+                    value: Value::Json(value),
+                    expires_at: None,
+                },
+            );
+            (None, 0)
+        }
+        Some(entry) => {
+            let old_size = estimate_entry_bytes(&key, entry);
+            let Some(doc) = entry.value.as_json_mut() else {
+                drop(db);
+                socket.write_all(WRONGTYPE_ERR).await?;
+                return Ok(());
+            };
+
+            let exists = !doc.resolve(&path).is_empty();
+            if (nx && exists) || (xx && !exists) {
+                drop(db);
+                socket.write_all(&null_bulk()).await?;
+                return Ok(());
+            }
+            // Only what the write displaces is kept for an OOM rollback.
+            let displaced = doc.set(&path.steps, &value);
+            if displaced.is_empty() {
+                drop(db);
+                socket.write_all(&null_bulk()).await?;
+                return Ok(());
+            }
+            (Some(displaced), old_size)
+        }
+    };
+
+    let new_size = db
+        .get(&key)
+        .map(|entry| estimate_entry_bytes(&key, entry))
+        .unwrap_or(0);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        match displaced {
+            None => {
+                db.remove(&key);
+            }
+            Some(displaced) => {
+                if let Some(doc) = db.get_mut(&key).and_then(|entry| entry.value.as_json_mut()) {
+                    doc.restore(&path.steps, displaced);
+                }
+            }
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(b"+OK\r\n").await?;
+
+    Ok(())
+}
+
+pub async fn json_get_cmd(
+    key: String,
+    paths: Vec<JsonPath>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let doc = value.as_json()?;
+        if let [path] = paths.as_slice() {
+            return Some(query(doc, path).map(|json| json.to_string()));
+        }
+
+        // Several paths answer with an object keyed by path.
+        let mut fields = Vec::new();
+        for path in &paths {
+            match query(doc, path) {
+                Ok(json) => fields.push((path.raw.clone(), json)),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        Some(Ok(Json::Object(fields).to_string()))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Ok(text)) => socket.write_all(&bulk_str(&text)).await?,
+        Lookup::Found(Err(err)) => socket.write_all(&err).await?,
+        Lookup::Missing => socket.write_all(&null_bulk()).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn json_del_cmd(
+    key: String,
+    path: JsonPath,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    if !path.is_root() {
+        let lookup = update_value(_db, _heap, lru, &key, |value| {
+            value.as_json_mut().map(|doc| doc.delete(&path.steps))
+        })
+        .await;
+
+        match lookup {
+            Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+            Lookup::Found(removed) => socket.write_all(&integer(removed as i64)).await?,
+            Lookup::Missing => socket.write_all(&integer(0)).await?,
+        }
+        return Ok(());
+    }
+
+    // Deleting the root deletes the key.
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+    match db.get(&key) {
+        None => {
+            drop(db);
+            socket.write_all(&integer(0)).await?;
+        }
+        Some(entry) if entry.value.as_json().is_none() => {
+            drop(db);
+            socket.write_all(WRONGTYPE_ERR).await?;
+        }
+        Some(entry) => {
+            let removed_bytes = estimate_entry_bytes(&key, entry);
+            db.remove(&key);
+            drop(db);
+            lru.adjust_used_bytes(-(removed_bytes as isize));
+            lru.remove_key(_db, &key).await;
+            socket.write_all(&integer(1)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn json_type_cmd(
+    key: String,
+    path: JsonPath,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let matches = value.as_json()?.resolve(&path);
+        if path.legacy {
+            return Some(match matches.first() {
+                Some(json) => format!("+{}\r\n", json.type_name()).into_bytes(),
+                None => null_bulk(),
+            });
+        }
+        let mut resp = array_len(matches.len());
+        for json in matches {
+            resp.extend_from_slice(&bulk_str(json.type_name()));
+        }
+        Some(resp)
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(&null_bulk()).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn json_numincrby_cmd(
+    key: String,
+    path: JsonPath,
+    by: Json,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        let doc = value.as_json_mut()?;
+        let results: Vec<Result<Json, &'static str>> = doc
+            .resolve(&path)
+            .into_iter()
+            .map(|json| add_number(json, &by))
+            .collect();
+
+        if path.legacy {
+            match results.first() {
+                None => return Some(Err(path_missing(&path))),
+                Some(Err(found)) => return Some(Err(wrong_type("a number", found))),
+                Some(Ok(_)) => {}
+            }
+        }
+        if results
+            .iter()
+            .flatten()
+            .any(|n| matches!(n, Json::Float(x) if !x.is_finite()))
+        {
+            return Some(Err(b"-ERR result is not a number or infinity\r\n".to_vec()));
+        }
+
+        let mut pending = results.iter();
+        doc.visit_mut(&path.steps, &mut |node| {
+            if let Some(Ok(number)) = pending.next() {
+                *node = number.clone();
+            }
+        });
+
+        let reply = if path.legacy {
+            results.into_iter().flatten().last().unwrap_or(Json::Null)
+        } else {
+            Json::Array(
+                results
+                    .into_iter()
+                    .map(|r| r.unwrap_or(Json::Null))
+                    .collect(),
+            )
+        };
+        Some(Ok(reply.to_string()))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Ok(text)) => socket.write_all(&bulk_str(&text)).await?,
+        Lookup::Found(Err(err)) => socket.write_all(&err).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn json_arrappend_cmd(
+    key: String,
+    path: JsonPath,
+    values: Vec<Json>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    // Appended values sit one level below the arrays the path names.
+    if !values.iter().all(|value| value.fits_at(path.steps.len() + 1)) {
+        socket.write_all(DEPTH_ERR).await?;
+        return Ok(());
+    }
+
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let Some(entry) = db.get_mut(&key) else {
+        drop(db);
+        socket.write_all(NO_KEY_ERR).await?;
+        return Ok(());
+    };
+    let old_size = estimate_entry_bytes(&key, entry);
+    let Some(doc) = entry.value.as_json_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let old_lens: Vec<Result<usize, &'static str>> = doc
+        .resolve(&path)
+        .into_iter()
+        .map(|json| match json {
+            Json::Array(items) => Ok(items.len()),
+            other => Err(other.type_name()),
+        })
+        .collect();
+    let reply = path_reply(&path, &old_lens, "array", |len| {
+        integer((len + values.len()) as i64)
+    });
+    let reply = match reply {
+        Ok(reply) => reply,
+        Err(err) => {
+            drop(db);
+            socket.write_all(&err).await?;
+            return Ok(());
+        }
+    };
+
+    doc.visit_mut(&path.steps, &mut |node| {
+        if let Json::Array(items) = node {
+            items.extend(values.iter().cloned());
+        }
+    });
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if let Some(doc) = db.get_mut(&key).and_then(|entry| entry.value.as_json_mut()) {
+            let mut lens = old_lens.iter();
+            doc.visit_mut(&path.steps, &mut |node| {
+                if let (Json::Array(items), Some(Ok(len))) = (node, lens.next()) {
+                    items.truncate(*len);
+                }
+            });
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&reply).await?;
+
+    Ok(())
+}
+
+pub async fn json_strlen_cmd(
+    key: String,
+    path: JsonPath,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let lens: Vec<Result<usize, &'static str>> = value
+            .as_json()?
+            .resolve(&path)
+            .into_iter()
+            .map(|json| match json {
+                Json::String(s) => Ok(s.chars().count()),
+                other => Err(other.type_name()),
+            })
+            .collect();
+        Some(path_reply(&path, &lens, "string", |len| {
+            integer(len as i64)
+        }))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Ok(resp)) | Lookup::Found(Err(resp)) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(&null_bulk()).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn json_objkeys_cmd(
+    key: String,
+    path: JsonPath,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let keys: Vec<Result<Vec<u8>, &'static str>> = value
+            .as_json()?
+            .resolve(&path)
+            .into_iter()
+            .map(|json| match json {
+                Json::Object(fields) => {
+                    let mut resp = array_len(fields.len());
+                    for (name, _) in fields {
+                        resp.extend_from_slice(&bulk_str(name));
+                    }
+                    Ok(resp)
+                }
+                other => Err(other.type_name()),
+            })
+            .collect();
+        Some(path_reply(&path, &keys, "object", |resp| resp))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Ok(resp)) | Lookup::Found(Err(resp)) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(&null_bulk()).await?,
+    }
+
+    Ok(())
+}
+
+/// The JSON.GET answer for one path: every match as an array for JSONPath,
+/// the first match for a legacy path.
+fn query(doc: &Json, path: &JsonPath) -> Result<Json, Vec<u8>> {
+    let matches = doc.resolve(path);
+    if !path.legacy {
+        return Ok(Json::Array(matches.into_iter().cloned().collect()));
+    }
+    matches
+        .first()
+        .map(|json| (*json).clone())
+        .ok_or_else(|| path_missing(path))
+}
+
+/// Builds the reply for per-match results, where `Err` carries the type
+/// found instead of `expected`. A JSONPath answers with an array holding nil
+/// for mismatches; a legacy path answers for its first match and fails if
+/// there is none or it has the wrong type.
+fn path_reply<T: Clone>(
+    path: &JsonPath,
+    results: &[Result<T, &'static str>],
+    expected: &str,
+    resp: impl Fn(T) -> Vec<u8>,
+) -> Result<Vec<u8>, Vec<u8>> {
+    if path.legacy {
+        return match results.first() {
+            None => Err(path_missing(path)),
+            Some(Ok(value)) => Ok(resp(value.clone())),
+            Some(Err(found)) => Err(wrong_type(expected, found)),
+        };
+    }
+
+    let mut reply = array_len(results.len());
+    for result in results {
+        match result {
+            Ok(value) => reply.extend_from_slice(&resp(value.clone())),
+            Err(_) => reply.extend_from_slice(&null_bulk()),
+        }
+    }
+    Ok(reply)
+}
+
+/// Integers stay integers until they overflow; anything involving a float
+/// gives a float.
+fn add_number(json: &Json, by: &Json) -> Result<Json, &'static str> {
+    let as_float = |n: &Json| match n {
+        Json::Int(i) => *i as f64,
+        Json::Float(x) => *x,
+        _ => 0.0,
+    };
+    match (json, by) {
+        (Json::Int(a), Json::Int(b)) => Ok(a
+            .checked_add(*b)
+            .map(Json::Int)
+            .unwrap_or(Json::Float(*a as f64 + *b as f64))),
+        (Json::Int(_) | Json::Float(_), _) => Ok(Json::Float(as_float(json) + as_float(by))),
+        (other, _) => Err(other.type_name()),
+    }
+}
+
+fn path_missing(path: &JsonPath) -> Vec<u8> {
+    format!("-ERR Path '{}' does not exist\r\n", path.raw).into_bytes()
+}
+
+fn wrong_type(expected: &str, found: &str) -> Vec<u8> {
+    format!(
+        "-ERR wrong type of path value - expected {} but found {}\r\n",
+        expected, found
+    )
+    .into_bytes()
+}
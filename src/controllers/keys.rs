@@ -0,0 +1,32 @@
+use crate::model::{DB, Heap};
+use crate::util::{array_len, bulk_str, glob_match, is_expired};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// KEYS walks the whole keyspace under one read lock, so it is meant for
+/// debugging; SCAN is the way to iterate a large database.
+pub async fn keys_cmd(
+    pattern: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let resp = {
+        let db = _db.read().await;
+        let keys: Vec<&String> = db
+            .iter()
+            .filter(|(key, entry)| !is_expired(entry) && glob_match(&pattern, key.as_bytes()))
+            .map(|(key, _)| key)
+            .collect();
+
+        let mut resp = array_len(keys.len());
+        for key in keys {
+            resp.extend_from_slice(&bulk_str(key));
+        }
+        resp
+    };
+
+    socket.write_all(&resp).await?;
+    Ok(())
+}
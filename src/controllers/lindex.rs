@@ -0,0 +1,30 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, bulk_bytes, list_index, null_bulk, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn lindex_cmd(
+    key: String,
+    index: i64,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_list().map(|list| {
+            list_index(list.len(), index)
+                .and_then(|i| list.get(i))
+                .map_or_else(null_bulk, |item| bulk_bytes(item))
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(&null_bulk()).await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap},
+    util::{OOM_ERR, WRONGTYPE_ERR, drop_expired, integer},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn linsert_cmd(
+    key: String,
+    before: bool,
+    pivot: Vec<u8>,
+    value: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let Some(entry) = db.get_mut(&key) else {
+        drop(db);
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    };
+    let old_size = estimate_entry_bytes(&key, entry);
+
+    let Some(list) = entry.value.as_list_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let Some(pos) = list.iter().position(|item| *item == pivot) else {
+        drop(db);
+        socket.write_all(b":-1\r\n").await?;
+        return Ok(());
+    };
+
+    let at = if before { pos } else { pos + 1 };
+    list.insert(at, value);
+    let len = list.len();
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if let Some(list) = db.get_mut(&key).and_then(|e| e.value.as_list_mut()) {
+            list.remove(at);
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(len as i64)).await?;
+
+    Ok(())
+}
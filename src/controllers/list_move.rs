@@ -0,0 +1,294 @@
+use crate::{
+    blocking::{BlockingManager, WaitOutcome},
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Keyspace, Value},
+    util::{Lookup, OOM_ERR, WRONGTYPE_ERR, bulk_bytes, drop_expired, null_array, null_bulk},
+};
+use anyhow::Result;
+use std::collections::VecDeque;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::{Duration, Instant};
+
+#[allow(clippy::too_many_arguments)]
+pub async fn lmove_cmd(
+    source: String,
+    destination: String,
+    from_left: bool,
+    to_left: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    move_list(
+        source,
+        destination,
+        from_left,
+        to_left,
+        None,
+        _db,
+        _heap,
+        lru,
+        blocking,
+        socket,
+    )
+    .await
+}
+
+/// Same as `LMOVE source destination RIGHT LEFT`.
+pub async fn rpoplpush_cmd(
+    source: String,
+    destination: String,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    move_list(
+        source,
+        destination,
+        false,
+        true,
+        None,
+        _db,
+        _heap,
+        lru,
+        blocking,
+        socket,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn blmove_cmd(
+    source: String,
+    destination: String,
+    from_left: bool,
+    to_left: bool,
+    timeout: u64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    move_list(
+        source,
+        destination,
+        from_left,
+        to_left,
+        Some(timeout),
+        _db,
+        _heap,
+        lru,
+        blocking,
+        socket,
+    )
+    .await
+}
+
+/// Moves one element from `source` to `destination`. With `block` set it
+/// waits up to that many ms (zero waits forever) for `source` to get one.
+#[allow(clippy::too_many_arguments)]
+async fn move_list(
+    source: String,
+    destination: String,
+    from_left: bool,
+    to_left: bool,
+    block: Option<u64>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let deadline = block
+        .filter(|ms| *ms > 0)
+        .map(|ms| Instant::now() + Duration::from_millis(ms));
+    let waiter = block.map(|_| blocking.register(std::slice::from_ref(&source)));
+
+    loop {
+        let moved = {
+            let mut db = _db.write().await;
+            move_element(&mut db, &source, &destination, from_left, to_left)
+        };
+
+        match moved.lookup {
+            Lookup::WrongType => {
+                if moved.delta != 0 {
+                    lru.adjust_used_bytes(moved.delta);
+                }
+                socket.write_all(WRONGTYPE_ERR).await?;
+                return Ok(());
+            }
+            Lookup::Found(item) => {
+                drop(waiter);
+                if !finish_move(
+                    &source,
+                    &destination,
+                    from_left,
+                    to_left,
+                    moved.delta,
+                    moved.source_removed,
+                    _db,
+                    _heap,
+                    lru,
+                    blocking,
+                )
+                .await?
+                {
+                    socket.write_all(OOM_ERR).await?;
+                    return Ok(());
+                }
+                socket.write_all(&bulk_bytes(&item)).await?;
+                return Ok(());
+            }
+            Lookup::Missing => {
+                if moved.delta != 0 {
+                    lru.adjust_used_bytes(moved.delta);
+                }
+            }
+        }
+
+        let Some(waiter) = &waiter else {
+            socket.write_all(&null_bulk()).await?;
+            return Ok(());
+        };
+        match waiter.wait(deadline, socket).await {
+            WaitOutcome::Woken => continue,
+            WaitOutcome::TimedOut => {
+                socket.write_all(&null_array()).await?;
+                return Ok(());
+            }
+            WaitOutcome::Disconnected => return Ok(()),
+        }
+    }
+}
+
+struct Moved {
+    lookup: Lookup<Vec<u8>>,
+    /// Size change to charge to the LRU, including dropped expired entries.
+    delta: isize,
+    /// The source list emptied and its key was removed.
+    source_removed: bool,
+}
+
+/// Moves one element from the `from_left` end of `source` to the `to_left`
+/// end of `destination` under a write lock the caller already holds.
+/// `source` and `destination` may be the same key, which rotates the list.
+fn move_element(
+    db: &mut Keyspace,
+    source: &String,
+    destination: &String,
+    from_left: bool,
+    to_left: bool,
+) -> Moved {
+    let mut moved = Moved {
+        lookup: Lookup::Missing,
+        delta: -(drop_expired(db, source) as isize),
+        source_removed: false,
+    };
+    if destination != source {
+        moved.delta -= drop_expired(db, destination) as isize;
+    }
+
+    let Some(entry) = db.get_mut(source) else {
+        return moved;
+    };
+    let source_old = estimate_entry_bytes(source, entry);
+    if entry.value.as_list_mut().is_none() {
+        moved.lookup = Lookup::WrongType;
+        return moved;
+    }
+    let destination_old = match db.get(destination) {
+        Some(entry) if entry.value.as_list().is_none() => {
+            moved.lookup = Lookup::WrongType;
+            return moved;
+        }
+        Some(entry) if destination != source => estimate_entry_bytes(destination, entry),
+        _ => 0,
+    };
+
+    let Some(list) = db.get_mut(source).and_then(|e| e.value.as_list_mut()) else {
+        return moved;
+    };
+    let item = if from_left {
+        list.pop_front()
+    } else {
+        list.pop_back()
+    };
+    let Some(item) = item else {
+        return moved;
+    };
+
+    let entry = db.get_or_insert_with(destination.clone(), || Entry {
+        value: Value::List(VecDeque::new()),
+        expires_at: None,
+    });
+    if let Some(list) = entry.value.as_list_mut() {
+        if to_left {
+            list.push_front(item.clone());
+        } else {
+            list.push_back(item.clone());
+        }
+    }
+    let destination_new = estimate_entry_bytes(destination, entry);
+
+    let source_new = match db.get(source) {
+        Some(entry) if destination == source => {
+            moved.delta += estimate_entry_bytes(source, entry) as isize - source_old as isize;
+            moved.lookup = Lookup::Found(item);
+            return moved;
+        }
+        Some(entry) if entry.value.as_list().is_some_and(|l| l.is_empty()) => {
+            db.remove(source);
+            moved.source_removed = true;
+            0
+        }
+        Some(entry) => estimate_entry_bytes(source, entry),
+        None => 0,
+    };
+
+    moved.delta += source_new as isize - source_old as isize;
+    moved.delta += destination_new as isize - destination_old as isize;
+    moved.lookup = Lookup::Found(item);
+    moved
+}
+
+/// Charges a completed move to the LRU, undoing it if `maxmemory` rejects the
+/// growth, and wakes clients blocked on either list. Returns `false` on OOM.
+#[allow(clippy::too_many_arguments)]
+async fn finish_move(
+    source: &String,
+    destination: &String,
+    from_left: bool,
+    to_left: bool,
+    delta: isize,
+    source_removed: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+) -> Result<bool> {
+    if !lru.try_reserve(delta).await? {
+        {
+            let mut db = _db.write().await;
+            move_element(&mut db, destination, source, to_left, from_left);
+        }
+        lru.adjust_used_bytes(-delta);
+        return Ok(false);
+    }
+
+    if source_removed {
+        lru.remove_key(_db, source).await;
+    } else {
+        blocking.wake_one(source);
+    }
+    if destination != source {
+        blocking.wake_one(destination);
+    }
+    Ok(true)
+}
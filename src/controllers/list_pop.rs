@@ -0,0 +1,164 @@
+use crate::{
+    blocking::{BlockingManager, WaitOutcome},
+    lru::LruManager,
+    model::{DB, Heap},
+    util::{Lookup, WRONGTYPE_ERR, array_len, bulk_bytes, bulk_str, null_array, pop_list},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::{Duration, Instant};
+
+pub async fn blpop_cmd(
+    keys: Vec<String>,
+    timeout: u64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    pop_first(true, keys, None, Some(timeout), _db, lru, blocking, socket).await
+}
+
+pub async fn brpop_cmd(
+    keys: Vec<String>,
+    timeout: u64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    pop_first(false, keys, None, Some(timeout), _db, lru, blocking, socket).await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn lmpop_cmd(
+    keys: Vec<String>,
+    left: bool,
+    count: usize,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    pop_first(left, keys, Some(count), None, _db, lru, blocking, socket).await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn blmpop_cmd(
+    keys: Vec<String>,
+    left: bool,
+    count: usize,
+    timeout: u64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    pop_first(
+        left,
+        keys,
+        Some(count),
+        Some(timeout),
+        _db,
+        lru,
+        blocking,
+        socket,
+    )
+    .await
+}
+
+/// Pops from the first non-empty list in `keys`. With `block` set it waits
+/// up to that many ms (zero waits forever) for a push to land on one of them.
+///
+/// `count` selects the LMPOP reply `[key, [items]]`; without it a single item
+/// is popped and sent as `[key, item]`.
+#[allow(clippy::too_many_arguments)]
+async fn pop_first(
+    left: bool,
+    keys: Vec<String>,
+    count: Option<usize>,
+    block: Option<u64>,
+    _db: &DB,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let deadline = block
+        .filter(|ms| *ms > 0)
+        .map(|ms| Instant::now() + Duration::from_millis(ms));
+
+    // Registered once for the whole call so the client keeps its place in
+    // the FIFO queue across spurious wake-ups.
+    let waiter = block.map(|_| blocking.register(&keys));
+
+    loop {
+        let mut delta = 0isize;
+        let mut served = None;
+        let mut removed = false;
+        let mut wrong_type = false;
+        {
+            let mut db = _db.write().await;
+            for key in &keys {
+                let pop = pop_list(&mut db, key, left, count.unwrap_or(1));
+                delta += pop.delta;
+                match pop.lookup {
+                    Lookup::WrongType => wrong_type = true,
+                    Lookup::Found(items) if !items.is_empty() => {
+                        removed = pop.removed;
+                        served = Some((key.clone(), items));
+                    }
+                    _ => continue,
+                }
+                break;
+            }
+        }
+
+        if delta != 0 {
+            lru.adjust_used_bytes(delta);
+        }
+
+        if wrong_type {
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+
+        if let Some((key, items)) = served {
+            drop(waiter);
+            if removed {
+                lru.remove_key(_db, &key).await;
+            } else {
+                // Hand the remaining elements to the next blocked client.
+                blocking.wake_one(&key);
+            }
+
+            let mut resp = array_len(2);
+            resp.extend_from_slice(&bulk_str(&key));
+            if count.is_some() {
+                resp.extend_from_slice(&array_len(items.len()));
+            }
+            for item in &items {
+                resp.extend_from_slice(&bulk_bytes(item));
+            }
+            socket.write_all(&resp).await?;
+            return Ok(());
+        }
+
+        let Some(waiter) = &waiter else {
+            socket.write_all(&null_array()).await?;
+            return Ok(());
+        };
+        match waiter.wait(deadline, socket).await {
+            WaitOutcome::Woken => continue,
+            WaitOutcome::TimedOut => {
+                socket.write_all(&null_array()).await?;
+                return Ok(());
+            }
+            WaitOutcome::Disconnected => return Ok(()),
+        }
+    }
+}
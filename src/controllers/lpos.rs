@@ -0,0 +1,67 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, array_len, integer, null_bulk, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// `rank` picks which match to start from (negative scans from the tail),
+/// `count` of zero returns every match and `maxlen` of zero compares the
+/// whole list.
+#[allow(clippy::too_many_arguments)]
+pub async fn lpos_cmd(
+    key: String,
+    element: Vec<u8>,
+    rank: i64,
+    count: Option<usize>,
+    maxlen: usize,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_list().map(|list| {
+            let scanned = if maxlen == 0 { list.len() } else { maxlen };
+            let indexes: Box<dyn Iterator<Item = usize>> = if rank < 0 {
+                Box::new((0..list.len()).rev().take(scanned))
+            } else {
+                Box::new((0..list.len()).take(scanned))
+            };
+            let wanted = match count {
+                Some(0) => usize::MAX,
+                Some(n) => n,
+                None => 1,
+            };
+            indexes
+                .filter(|&i| list[i] == element)
+                .skip(rank.unsigned_abs() as usize - 1)
+                .take(wanted)
+                .collect::<Vec<usize>>()
+        })
+    })
+    .await;
+
+    let matches = match lookup {
+        Lookup::WrongType => {
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+        Lookup::Found(matches) => matches,
+        Lookup::Missing => Vec::new(),
+    };
+
+    match count {
+        Some(_) => {
+            let mut resp = array_len(matches.len());
+            for i in matches {
+                resp.extend_from_slice(&integer(i as i64));
+            }
+            socket.write_all(&resp).await?;
+        }
+        None => match matches.first() {
+            Some(i) => socket.write_all(&integer(*i as i64)).await?,
+            None => socket.write_all(&null_bulk()).await?,
+        },
+    }
+
+    Ok(())
+}
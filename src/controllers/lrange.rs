@@ -0,0 +1,36 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, array_len, bulk_bytes, list_range, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn lrange_cmd(
+    key: String,
+    start: i64,
+    stop: i64,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_list().map(|list| {
+            let Some((start, stop)) = list_range(list.len(), start, stop) else {
+                return b"*0\r\n".to_vec();
+            };
+            let mut resp = array_len(stop - start + 1);
+            for item in list.range(start..=stop) {
+                resp.extend_from_slice(&bulk_bytes(item));
+            }
+            resp
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(b"*0\r\n").await?,
+    }
+
+    Ok(())
+}
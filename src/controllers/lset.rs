@@ -0,0 +1,67 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap},
+    util::{OOM_ERR, WRONGTYPE_ERR, drop_expired, list_index},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn lset_cmd(
+    key: String,
+    index: i64,
+    value: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let Some(entry) = db.get_mut(&key) else {
+        drop(db);
+        socket.write_all(b"-ERR no such key\r\n").await?;
+        return Ok(());
+    };
+    let old_size = estimate_entry_bytes(&key, entry);
+
+    let Some(list) = entry.value.as_list_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let Some(index) = list_index(list.len(), index) else {
+        drop(db);
+        socket.write_all(b"-ERR index out of range\r\n").await?;
+        return Ok(());
+    };
+
+    let previous = std::mem::replace(&mut list[index], value);
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if let Some(item) = db
+            .get_mut(&key)
+            .and_then(|e| e.value.as_list_mut())
+            .and_then(|list| list.get_mut(index))
+        {
+            *item = previous;
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(b"+OK\r\n").await?;
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap, MinHeap},
+    util::{WRONGTYPE_ERR, is_expired, list_range},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn ltrim_cmd(
+    key: String,
+    start: i64,
+    stop: i64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut expired_at = None;
+    let mut old_size = 0usize;
+    let mut new_size = 0usize;
+    let mut remove_key = false;
+    let mut wrong_type = false;
+
+    {
+        let mut db = _db.write().await;
+        if let Some(entry) = db.get_mut(&key) {
+            if is_expired(entry) {
+                expired_at = entry.expires_at;
+            } else {
+                old_size = estimate_entry_bytes(&key, entry);
+                match entry.value.as_list_mut() {
+                    Some(list) => {
+                        match list_range(list.len(), start, stop) {
+                            Some((start, stop)) => {
+                                list.truncate(stop + 1);
+                                list.drain(..start);
+                                list.shrink_to_fit();
+                            }
+                            None => list.clear(),
+                        }
+                        if list.is_empty() {
+                            remove_key = true;
+                        } else {
+                            new_size = estimate_entry_bytes(&key, entry);
+                        }
+                    }
+                    None => wrong_type = true,
+                }
+
+                if remove_key {
+                    db.remove(&key);
+                }
+            }
+        }
+    }
+
+    if wrong_type {
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    }
+
+    if let Some(expires_at) = expired_at {
+        let mut heap = _heap.lock().await;
+        heap.push(MinHeap { key, expires_at });
+        socket.write_all(b"+OK\r\n").await?;
+        return Ok(());
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if delta != 0 {
+        lru.adjust_used_bytes(delta);
+    }
+    if remove_key {
+        lru.remove_key(_db, &key).await;
+    }
+
+    socket.write_all(b"+OK\r\n").await?;
+
+    Ok(())
+}
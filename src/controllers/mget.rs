@@ -0,0 +1,48 @@
+use crate::model::{DB, Heap, MinHeap};
+use crate::util::{array_len, bulk_bytes, is_expired, null_bulk};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn mget_cmd(
+    keys: Vec<String>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut expired = Vec::new();
+    let mut resp = array_len(keys.len());
+    {
+        let db = _db.read().await;
+        for key in &keys {
+            match db.get(key) {
+                Some(entry) if is_expired(entry) => {
+                    if let Some(expires_at) = entry.expires_at {
+                        expired.push(MinHeap {
+                            key: key.clone(),
+                            expires_at,
+                        });
+                    }
+                    resp.extend_from_slice(&null_bulk());
+                }
+                // Non-string values read as nil rather than failing the batch.
+                Some(entry) => match entry.value.as_string_bytes() {
+                    Some(bytes) => resp.extend_from_slice(&bulk_bytes(&bytes)),
+                    None => resp.extend_from_slice(&null_bulk()),
+                },
+                None => resp.extend_from_slice(&null_bulk()),
+            }
+        }
+    }
+
+    if !expired.is_empty() {
+        let mut heap = _heap.lock().await;
+        for record in expired {
+            heap.push(record);
+        }
+    }
+
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
@@ -1,39 +1,268 @@
+mod append;
+mod bitcount;
+mod bitfield;
+mod bitop;
+mod bitpos;
+mod bloom;
+mod cms;
 mod config;
 mod command_cmd;
+mod copy;
+mod cuckoo;
+mod dbsize;
 mod del;
 mod exists;
 mod expire;
+mod expiretime;
+mod flushdb;
+mod geoadd;
+mod geodist;
+mod geohash;
+mod geopos;
+mod geosearch;
 mod get;
+mod getbit;
+mod getdel;
+mod getex;
+mod getrange;
+mod getset;
+mod hdel;
 mod hello;
+mod hexists;
+mod hexpire;
+mod hget;
+mod hgetall;
+mod hincrby;
+mod hincrbyfloat;
+mod hkeys;
+mod hlen;
+mod hmget;
+mod hpersist;
+mod hrandfield;
+mod hscan;
+mod hset;
+mod hsetnx;
+mod hstrlen;
+mod httl;
+mod hvals;
+mod incr;
+mod incrbyfloat;
 mod info;
+mod json;
+mod keys;
+mod lindex;
+mod linsert;
+mod list_move;
+mod list_pop;
+mod llen;
 mod lpop;
+mod lpos;
 mod lpush;
+mod lpushx;
+mod lrange;
+mod lrem;
+mod lset;
+mod ltrim;
+mod mget;
+mod move_key;
+mod mset;
 mod persist;
+mod pfadd;
+mod pfcount;
+mod pfmerge;
 mod psetex;
 mod pttl;
+mod randomkey;
+mod rename;
 mod rpop;
 mod rpush;
+mod rpushx;
+mod sadd;
+mod scan;
+mod scard;
+mod select;
 mod set;
+mod set_algebra;
+mod setbit;
 mod setex;
+mod setnx;
+mod setrange;
+mod sismember;
+mod smembers;
+mod smismember;
+mod smove;
+mod spop;
+mod srandmember;
+mod srem;
+mod sscan;
+mod strlen;
+mod swapdb;
+mod timeseries;
+mod topk;
+mod touch;
 mod ttl;
 mod type_cmd;
+mod xack;
+mod xadd;
+mod xclaim;
+mod xdel;
+mod xgroup;
+mod xinfo;
+mod xlen;
+mod xpending;
+mod xrange;
+mod xread;
+mod xreadgroup;
+mod xtrim;
+mod zadd;
+mod zcard;
+mod zcount;
+mod zincrby;
+mod zpop;
+mod zrange;
+mod zrank;
+mod zrem;
+mod zscan;
+mod zscore;
+mod zset_algebra;
 
+pub use append::append_cmd;
+pub use bitcount::bitcount_cmd;
+pub use bitfield::{bitfield_cmd, bitfield_ro_cmd};
+pub use bitop::bitop_cmd;
+pub use bitpos::bitpos_cmd;
+pub use bloom::{
+    bf_add_cmd, bf_exists_cmd, bf_info_cmd, bf_madd_cmd, bf_mexists_cmd, bf_reserve_cmd,
+};
+pub use cms::{
+    cms_incrby_cmd, cms_initbydim_cmd, cms_initbyprob_cmd, cms_merge_cmd, cms_query_cmd,
+};
 pub use command_cmd::command_cmd;
 pub use config::{config_get_cmd, config_set_cmd};
+pub use copy::copy_cmd;
+pub use cuckoo::{cf_add_cmd, cf_del_cmd, cf_exists_cmd};
+pub use dbsize::dbsize_cmd;
 pub use del::del_cmd;
 pub use exists::exists_cmd;
 pub use expire::expire_cmd;
+pub use expiretime::expiretime_cmd;
+pub use flushdb::{flushall_cmd, flushdb_cmd};
+pub use geoadd::geoadd_cmd;
+pub use geodist::geodist_cmd;
+pub use geohash::geohash_cmd;
+pub use geopos::geopos_cmd;
+pub use geosearch::{geosearch_cmd, geosearchstore_cmd};
 pub use get::get_cmd;
+pub use getbit::getbit_cmd;
+pub use getdel::getdel_cmd;
+pub use getex::getex_cmd;
+pub use getrange::getrange_cmd;
+pub use getset::getset_cmd;
+pub use hdel::hdel_cmd;
 pub use hello::hello_cmd;
+pub use hexists::hexists_cmd;
+pub use hexpire::hexpire_cmd;
+pub use hget::hget_cmd;
+pub use hgetall::hgetall_cmd;
+pub use hincrby::hincrby_cmd;
+pub use hincrbyfloat::hincrbyfloat_cmd;
+pub use hkeys::hkeys_cmd;
+pub use hlen::hlen_cmd;
+pub use hmget::hmget_cmd;
+pub use hpersist::hpersist_cmd;
+pub use hrandfield::hrandfield_cmd;
+pub use hscan::hscan_cmd;
+pub use hset::hset_cmd;
+pub use hsetnx::hsetnx_cmd;
+pub use hstrlen::hstrlen_cmd;
+pub use httl::httl_cmd;
+pub use hvals::hvals_cmd;
+pub use incr::{decr_cmd, decrby_cmd, incr_cmd, incrby_cmd};
+pub use incrbyfloat::incrbyfloat_cmd;
 pub use info::info_cmd;
+pub use json::{
+    json_arrappend_cmd, json_del_cmd, json_get_cmd, json_numincrby_cmd, json_objkeys_cmd,
+    json_set_cmd, json_strlen_cmd, json_type_cmd,
+};
+pub use keys::keys_cmd;
+pub use lindex::lindex_cmd;
+pub use linsert::linsert_cmd;
+pub use list_move::{blmove_cmd, lmove_cmd, rpoplpush_cmd};
+pub use list_pop::{blmpop_cmd, blpop_cmd, brpop_cmd, lmpop_cmd};
+pub use llen::llen_cmd;
 pub use lpop::lpop_cmd;
+pub use lpos::lpos_cmd;
 pub use lpush::lpush_cmd;
+pub use lpushx::lpushx_cmd;
+pub use lrange::lrange_cmd;
+pub use lrem::lrem_cmd;
+pub use lset::lset_cmd;
+pub use ltrim::ltrim_cmd;
+pub use mget::mget_cmd;
+pub use move_key::move_cmd;
+pub use mset::{mset_cmd, msetnx_cmd};
 pub use persist::persist_cmd;
+pub use pfadd::pfadd_cmd;
+pub use pfcount::pfcount_cmd;
+pub use pfmerge::pfmerge_cmd;
 pub use psetex::psetex_cmd;
 pub use pttl::pttl_cmd;
+pub use randomkey::randomkey_cmd;
+pub use rename::rename_cmd;
 pub use rpop::rpop_cmd;
 pub use rpush::rpush_cmd;
+pub use rpushx::rpushx_cmd;
+pub use sadd::sadd_cmd;
+pub use scan::scan_cmd;
+pub use scard::scard_cmd;
+pub use select::select_cmd;
 pub use set::set_cmd;
+pub use set_algebra::{
+    sdiff_cmd, sdiffstore_cmd, sinter_cmd, sintercard_cmd, sinterstore_cmd, sunion_cmd,
+    sunionstore_cmd,
+};
+pub use setbit::setbit_cmd;
 pub use setex::setex_cmd;
+pub use setnx::setnx_cmd;
+pub use setrange::setrange_cmd;
+pub use sismember::sismember_cmd;
+pub use smembers::smembers_cmd;
+pub use smismember::smismember_cmd;
+pub use smove::smove_cmd;
+pub use spop::spop_cmd;
+pub use srandmember::srandmember_cmd;
+pub use srem::srem_cmd;
+pub use sscan::sscan_cmd;
+pub use strlen::strlen_cmd;
+pub use swapdb::swapdb_cmd;
+pub use timeseries::{ts_add_cmd, ts_create_cmd, ts_madd_cmd, ts_mrange_cmd, ts_range_cmd};
+pub use topk::{topk_add_cmd, topk_count_cmd, topk_list_cmd, topk_query_cmd, topk_reserve_cmd};
+pub use touch::touch_cmd;
 pub use ttl::ttl_cmd;
 pub use type_cmd::type_cmd;
+pub use xack::xack_cmd;
+pub use xadd::xadd_cmd;
+pub use xclaim::{xautoclaim_cmd, xclaim_cmd};
+pub use xdel::xdel_cmd;
+pub use xgroup::{
+    xgroup_create_cmd, xgroup_createconsumer_cmd, xgroup_delconsumer_cmd, xgroup_destroy_cmd,
+    xgroup_setid_cmd,
+};
+pub use xinfo::{xinfo_consumers_cmd, xinfo_groups_cmd, xinfo_stream_cmd};
+pub use xlen::xlen_cmd;
+pub use xpending::xpending_cmd;
+pub use xrange::{xrange_cmd, xrevrange_cmd};
+pub use xread::xread_cmd;
+pub use xreadgroup::xreadgroup_cmd;
+pub use xtrim::xtrim_cmd;
+pub use zadd::zadd_cmd;
+pub use zcard::zcard_cmd;
+pub use zcount::zcount_cmd;
+pub use zincrby::zincrby_cmd;
+pub use zpop::{zpopmax_cmd, zpopmin_cmd};
+pub use zrange::{zrange_cmd, zrangestore_cmd};
+pub use zrank::{zrank_cmd, zrevrank_cmd};
+pub use zrem::zrem_cmd;
+pub use zscan::zscan_cmd;
+pub use zscore::zscore_cmd;
+pub use zset_algebra::{zinterstore_cmd, zunionstore_cmd};
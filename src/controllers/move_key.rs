@@ -0,0 +1,80 @@
+use crate::{
+    databases::Databases,
+    lru::LruManager,
+    util::{drop_expired, expiry_records},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// MOVE: hands `key` from the selected database to `target` unless the
+/// target already holds it. The entry keeps its TTL and hash field
+/// deadlines, which are filed with the target's heap.
+pub async fn move_cmd(
+    key: String,
+    target: i64,
+    selected: usize,
+    databases: &Databases,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let Some(target) = usize::try_from(target)
+        .ok()
+        .filter(|index| *index < databases.count())
+    else {
+        socket
+            .write_all(b"-ERR DB index is out of range\r\n")
+            .await?;
+        return Ok(());
+    };
+    if target == selected {
+        socket
+            .write_all(b"-ERR source and destination objects are the same\r\n")
+            .await?;
+        return Ok(());
+    }
+    let (Some(source_db), Some(target_db)) = (databases.get(selected), databases.get(target))
+    else {
+        return Ok(());
+    };
+
+    // Both keyspaces are locked in index order so concurrent moves in
+    // opposite directions cannot deadlock.
+    let (mut source, mut destination) = if selected < target {
+        let source = source_db.db.write().await;
+        (source, target_db.db.write().await)
+    } else {
+        let destination = target_db.db.write().await;
+        (source_db.db.write().await, destination)
+    };
+    let freed = drop_expired(&mut source, &key) + drop_expired(&mut destination, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    if destination.contains_key(&key) {
+        drop((source, destination));
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    }
+    let Some((key, entry)) = source.remove_entry(&key) else {
+        drop((source, destination));
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    };
+    let records = expiry_records(&key, &entry);
+    destination.insert(key.clone(), entry);
+    drop((source, destination));
+
+    if !records.is_empty() {
+        let mut heap = target_db.heap.lock().await;
+        for record in records {
+            heap.push(record);
+        }
+    }
+    lru.move_key(selected, target, &key).await;
+    target_db.blocking.wake_all(&key);
+
+    socket.write_all(b":1\r\n").await?;
+    Ok(())
+}
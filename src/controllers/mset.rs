@@ -0,0 +1,118 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Keyspace, Value},
+    util::{OOM_ERR, drop_expired},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn mset_cmd(
+    pairs: Vec<(String, Vec<u8>)>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let (previous, delta) = insert_all(&mut db, pairs);
+    drop(db);
+
+    if !reserve_or_rollback(previous, delta, _db, _heap, lru).await? {
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(b"+OK\r\n").await?;
+
+    Ok(())
+}
+
+pub async fn msetnx_cmd(
+    pairs: Vec<(String, Vec<u8>)>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let mut freed = 0;
+    for (key, _) in &pairs {
+        freed += drop_expired(&mut db, key);
+    }
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    if pairs.iter().any(|(key, _)| db.contains_key(key)) {
+        drop(db);
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    }
+
+    let (previous, delta) = insert_all(&mut db, pairs);
+    drop(db);
+
+    if !reserve_or_rollback(previous, delta, _db, _heap, lru).await? {
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(b":1\r\n").await?;
+
+    Ok(())
+}
+
+/// Writes every pair under the caller's write lock. Returns the replaced
+/// entries in write order, for rollback, and the total size change.
+fn insert_all(
+    db: &mut Keyspace,
+    pairs: Vec<(String, Vec<u8>)>,
+) -> (Vec<(String, Option<Entry>)>, isize) {
+    let mut previous = Vec::with_capacity(pairs.len());
+    let mut delta = 0isize;
+    for (key, value) in pairs {
+        let new_entry = Entry {
+            value: Value::String(value),
+            expires_at: None,
+        };
+        delta += estimate_entry_bytes(&key, &new_entry) as isize;
+        let old = db.insert(key.clone(), new_entry);
+        if let Some(old) = &old {
+            delta -= estimate_entry_bytes(&key, old) as isize;
+        }
+        previous.push((key, old));
+    }
+    (previous, delta)
+}
+
+/// Charges `delta` against `maxmemory`. When the batch is rejected every
+/// write is undone, newest first so repeated keys end up at their original
+/// value, and `false` is returned.
+async fn reserve_or_rollback(
+    previous: Vec<(String, Option<Entry>)>,
+    delta: isize,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+) -> Result<bool> {
+    if lru.try_reserve(delta).await? {
+        return Ok(true);
+    }
+
+    let mut db = _db.write().await;
+    for (key, old) in previous.into_iter().rev() {
+        match old {
+            Some(old_entry) => {
+                db.insert(key, old_entry);
+            }
+            None => {
+                db.remove(&key);
+            }
+        }
+    }
+    drop(db);
+    lru.adjust_used_bytes(-delta);
+
+    Ok(false)
+}
@@ -0,0 +1,78 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, HyperLogLog, Value},
+    util::{HLL_WRONGTYPE_ERR, OOM_ERR, WRONGTYPE_ERR, drop_expired},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn pfadd_cmd(
+    key: String,
+    elements: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::String(HyperLogLog::default().encode()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Value::String(bytes) = &mut entry.value else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+    let Some(mut hll) = HyperLogLog::decode(bytes) else {
+        drop(db);
+        socket.write_all(HLL_WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let mut changed = false;
+    for element in &elements {
+        changed |= hll.add(element);
+    }
+    if !changed && !created_new {
+        drop(db);
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    }
+
+    let previous = std::mem::replace(bytes, hll.encode());
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(entry) = db.get_mut(&key) {
+            entry.value = Value::String(previous);
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(b":1\r\n").await?;
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::{
+    lru::LruManager,
+    model::{DB, Heap, HyperLogLog, Value, hll_cached_count, hll_set_cached_count},
+    util::{HLL_WRONGTYPE_ERR, Lookup, WRONGTYPE_ERR, integer, is_expired, update_value},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn pfcount_cmd(
+    keys: Vec<String>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    if let [key] = keys.as_slice() {
+        return count_one(key, _db, _heap, lru, socket).await;
+    }
+
+    // Several keys are counted as their union, without touching the
+    // cached cardinalities.
+    let merged = {
+        let db = _db.read().await;
+        let mut merged = HyperLogLog::default();
+        let mut result = Ok(());
+        for key in &keys {
+            let Some(entry) = db.get(key).filter(|entry| !is_expired(entry)) else {
+                continue;
+            };
+            let Value::String(bytes) = &entry.value else {
+                result = Err(WRONGTYPE_ERR);
+                break;
+            };
+            let Some(hll) = HyperLogLog::decode(bytes) else {
+                result = Err(HLL_WRONGTYPE_ERR);
+                break;
+            };
+            merged.merge(&hll);
+        }
+        result.map(|()| merged)
+    };
+
+    match merged {
+        Ok(hll) => socket.write_all(&integer(hll.count() as i64)).await?,
+        Err(err) => socket.write_all(err).await?,
+    }
+
+    Ok(())
+}
+
+/// Counts a single key, answering from the header cache when it is valid
+/// and refreshing it otherwise.
+async fn count_one(
+    key: &str,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, key, |value| match value {
+        Value::String(bytes) => Some(hll_cached_count(bytes).or_else(|| {
+            let count = HyperLogLog::decode(bytes)?.count();
+            hll_set_cached_count(bytes, count);
+            Some(count)
+        })),
+        _ => None,
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(count)) => socket.write_all(&integer(count as i64)).await?,
+        Lookup::Found(None) => socket.write_all(HLL_WRONGTYPE_ERR).await?,
+        Lookup::Missing => socket.write_all(b":0\r\n").await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, HyperLogLog, Value},
+    util::{HLL_WRONGTYPE_ERR, OOM_ERR, WRONGTYPE_ERR, drop_expired},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn pfmerge_cmd(
+    destination: String,
+    keys: Vec<String>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let mut freed = drop_expired(&mut db, &destination);
+    for key in &keys {
+        freed += drop_expired(&mut db, key);
+    }
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    // The destination's own registers take part in the union.
+    let mut merged = HyperLogLog::default();
+    for key in std::iter::once(&destination).chain(&keys) {
+        let Some(entry) = db.get(key) else {
+            continue;
+        };
+        let Value::String(bytes) = &entry.value else {
+            drop(db);
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        };
+        let Some(hll) = HyperLogLog::decode(bytes) else {
+            drop(db);
+            socket.write_all(HLL_WRONGTYPE_ERR).await?;
+            return Ok(());
+        };
+        merged.merge(&hll);
+    }
+
+    let created_new = !db.contains_key(&destination);
+
+    let entry = db.get_or_insert_with(destination.clone(), || Entry {
+        value: Value::String(Vec::new()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&destination, entry)
+    };
+    let previous = std::mem::replace(&mut entry.value, Value::String(merged.encode()));
+    let new_size = estimate_entry_bytes(&destination, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&destination);
+        } else if let Some(entry) = db.get_mut(&destination) {
+            entry.value = previous;
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(b"+OK\r\n").await?;
+
+    Ok(())
+}
@@ -1,7 +1,6 @@
-use crate::model::{DB, Heap, MinHeap};
+use crate::model::{DB, Heap, MinHeap, unix_millis};
 use crate::util::is_expired;
 use anyhow::Result;
-use std::time::Instant;
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
 
@@ -15,25 +14,29 @@ pub async fn pttl_cmd(
 
     match db.get(&key) {
         None => {
-            socket.write_all(format!(":-2\r\n").as_bytes()).await?;
+            socket.write_all(b":-2\r\n").await?;
         }
         Some(entry) if is_expired(entry) => {
-            let mut heap = _heap.lock().await;
+            // A hash whose fields all expired has no key TTL to hand back;
+            // its field records are already queued.
+            if let Some(expires_at) = entry.expires_at {
+                let mut heap = _heap.lock().await;
 
-            let val = MinHeap {
-                key: key.clone(),
-                expires_at: entry.expires_at.unwrap(),
-            };
+                let val = MinHeap {
+                    key: key.clone(),
+                    expires_at,
+                };
 
-            heap.push(val);
-            socket.write_all(format!(":-2\r\n").as_bytes()).await?;
+                heap.push(val);
+            }
+            socket.write_all(b":-2\r\n").await?;
         }
         Some(entry) => match entry.expires_at {
             None => {
-                socket.write_all(format!(":-1\r\n").as_bytes()).await?;
+                socket.write_all(b":-1\r\n").await?;
             }
             Some(time) => {
-                let ttl = time.saturating_duration_since(Instant::now()).as_millis();
+                let ttl = time.saturating_sub(unix_millis());
                 socket.write_all(format!(":{}\r\n", ttl).as_bytes()).await?;
             }
         },
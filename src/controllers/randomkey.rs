@@ -0,0 +1,45 @@
+use crate::model::{DB, Heap};
+use crate::util::{bulk_str, is_expired, null_bulk, random_index};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// Tries this many random picks before falling back to a full walk, so a
+/// keyspace full of expired-but-uncollected keys still finds a live one.
+const RANDOM_TRIES: usize = 16;
+
+pub async fn randomkey_cmd(_db: &DB, _heap: &mut Heap, socket: &mut TcpStream) -> Result<()> {
+    let key = {
+        let db = _db.read().await;
+        let mut key = None;
+        if !db.is_empty() {
+            for _ in 0..RANDOM_TRIES {
+                let Some((candidate, entry)) = db.iter().nth(random_index(db.len())) else {
+                    break;
+                };
+                if !is_expired(entry) {
+                    key = Some(candidate.clone());
+                    break;
+                }
+            }
+        }
+        if key.is_none() {
+            let live: Vec<&String> = db
+                .iter()
+                .filter(|(_, entry)| !is_expired(entry))
+                .map(|(key, _)| key)
+                .collect();
+            if !live.is_empty() {
+                key = Some(live[random_index(live.len())].clone());
+            }
+        }
+        key
+    };
+
+    match key {
+        Some(key) => socket.write_all(&bulk_str(&key)).await?,
+        None => socket.write_all(&null_bulk()).await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::{
+    blocking::BlockingManager,
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap},
+    util::{drop_expired, expiry_records},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// RENAME and RENAMENX. The entry keeps its TTL and hash field deadlines,
+/// which are filed with the heap again under the new name, and its LRU
+/// recency moves with it.
+#[allow(clippy::too_many_arguments)]
+pub async fn rename_cmd(
+    source: String,
+    destination: String,
+    nx: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &source) + drop_expired(&mut db, &destination);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    if !db.contains_key(&source) {
+        drop(db);
+        socket.write_all(b"-ERR no such key\r\n").await?;
+        return Ok(());
+    }
+    if source == destination || (nx && db.contains_key(&destination)) {
+        drop(db);
+        if nx {
+            socket.write_all(b":0\r\n").await?;
+        } else {
+            socket.write_all(b"+OK\r\n").await?;
+        }
+        return Ok(());
+    }
+
+    let Some((old_key, entry)) = db.remove_entry(&source) else {
+        return Ok(());
+    };
+    let old_size = estimate_entry_bytes(&old_key, &entry);
+    let replaced_size = db
+        .remove_entry(&destination)
+        .map_or(0, |(key, replaced)| estimate_entry_bytes(&key, &replaced));
+    let new_size = estimate_entry_bytes(&destination, &entry);
+    let records = expiry_records(&destination, &entry);
+    db.insert(destination.clone(), entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize - replaced_size as isize;
+    if delta != 0 {
+        lru.adjust_used_bytes(delta);
+    }
+    lru.rename_key(_db, &source, &destination).await;
+
+    if !records.is_empty() {
+        let mut heap = _heap.lock().await;
+        for record in records {
+            heap.push(record);
+        }
+    }
+    blocking.wake_all(&destination);
+
+    if nx {
+        socket.write_all(b":1\r\n").await?;
+    } else {
+        socket.write_all(b"+OK\r\n").await?;
+    }
+
+    Ok(())
+}
@@ -1,25 +1,27 @@
+use crate::util::{array_len, bulk_bytes, is_expired, null_array};
 use crate::{
     lru::{estimate_entry_bytes, LruManager},
     model::{DB, Heap, MinHeap},
 };
-use crate::util::is_expired;
 use anyhow::Result;
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
 
 pub async fn rpop_cmd(
     key: String,
+    count: Option<usize>,
     _db: &DB,
     _heap: &mut Heap,
     lru: &LruManager,
     socket: &mut TcpStream,
 ) -> Result<()> {
     let mut expired_at = None;
-    let mut popped: Option<Vec<u8>> = None;
+    let mut popped: Vec<Vec<u8>> = Vec::new();
     let mut old_size = 0usize;
     let mut new_size = 0usize;
     let mut remove_key = false;
     let mut wrong_type = false;
+    let mut found = false;
 
     {
         let mut db = _db.write().await;
@@ -30,7 +32,13 @@ pub async fn rpop_cmd(
                 old_size = estimate_entry_bytes(&key, entry);
                 match entry.value.as_list_mut() {
                     Some(list) => {
-                        popped = list.pop_back();
+                        found = true;
+                        for _ in 0..count.unwrap_or(1) {
+                            match list.pop_back() {
+                                Some(value) => popped.push(value),
+                                None => break,
+                            }
+                        }
                         if list.is_empty() {
                             remove_key = true;
                         } else {
@@ -62,7 +70,12 @@ pub async fn rpop_cmd(
             key: key.clone(),
             expires_at,
         });
-        socket.write_all(b"$-1\r\n").await?;
+        let resp = if count.is_some() {
+            null_array()
+        } else {
+            b"$-1\r\n".to_vec()
+        };
+        socket.write_all(&resp).await?;
         return Ok(());
     }
 
@@ -71,22 +84,32 @@ pub async fn rpop_cmd(
         lru.adjust_used_bytes(delta);
     }
     if remove_key {
-        lru.remove_key(&key).await;
+        lru.remove_key(_db, &key).await;
     }
 
-    match popped {
-        Some(value) => {
-            let mut resp = Vec::new();
-            resp.extend_from_slice(b"$");
-            resp.extend_from_slice(value.len().to_string().as_bytes());
-            resp.extend_from_slice(b"\r\n");
-            resp.extend_from_slice(&value);
-            resp.extend_from_slice(b"\r\n");
+    match count {
+        Some(_) if !found => socket.write_all(&null_array()).await?,
+        Some(_) => {
+            let mut resp = array_len(popped.len());
+            for value in &popped {
+                resp.extend_from_slice(&bulk_bytes(value));
+            }
             socket.write_all(&resp).await?;
         }
-        None => {
-            socket.write_all(b"$-1\r\n").await?;
-        }
+        None => match popped.pop() {
+            Some(value) => {
+                let mut resp = Vec::new();
+                resp.extend_from_slice(b"$");
+                resp.extend_from_slice(value.len().to_string().as_bytes());
+                resp.extend_from_slice(b"\r\n");
+                resp.extend_from_slice(&value);
+                resp.extend_from_slice(b"\r\n");
+                socket.write_all(&resp).await?;
+            }
+            None => {
+                socket.write_all(b"$-1\r\n").await?;
+            }
+        },
     }
 
     Ok(())
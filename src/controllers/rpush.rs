@@ -1,4 +1,5 @@
 use crate::{
+    blocking::BlockingManager,
     lru::{estimate_entry_bytes, LruManager},
     model::{DB, Entry, Heap, Value},
 };
@@ -13,6 +14,7 @@ pub async fn rpush_cmd(
     _db: &DB,
     _heap: &mut Heap,
     lru: &LruManager,
+    blocking: &BlockingManager,
     socket: &mut TcpStream,
 ) -> Result<()> {
     let inserted = values.len();
@@ -63,16 +65,16 @@ pub async fn rpush_cmd(
     let maxmemory = lru.maxmemory();
 
     if maxmemory > 0 && new_used > maxmemory {
-        let evicted = lru.evict_if_needed(_db, _heap).await?;
+        let evicted = lru.evict_if_needed().await?;
         if !evicted {
             let mut db = _db.write().await;
             if created_new {
                 db.remove(&key_clone);
-            } else if let Some(entry) = db.get_mut(&key_clone) {
-                if let Some(list) = entry.value.as_list_mut() {
-                    for _ in 0..inserted {
-                        let _ = list.pop_back();
-                    }
+            } else if let Some(entry) = db.get_mut(&key_clone)
+                && let Some(list) = entry.value.as_list_mut()
+            {
+                for _ in 0..inserted {
+                    let _ = list.pop_back();
                 }
             }
             drop(db);
@@ -84,6 +86,7 @@ pub async fn rpush_cmd(
         }
     }
 
+    blocking.wake_one(&key_clone);
     socket.write_all(format!(":{}\r\n", len).as_bytes()).await?;
 
     Ok(())
@@ -0,0 +1,52 @@
+use crate::model::{DB, Heap};
+use crate::util::{array_len, bulk_str, glob_match, is_expired, scan_sorted};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn scan_cmd(
+    cursor: u64,
+    pattern: Option<Vec<u8>>,
+    count: usize,
+    type_name: Option<String>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let (next, page) = {
+        let db = _db.read().await;
+        let entries = db
+            .scan_from(cursor)
+            .filter(|(_, _, entry)| !is_expired(entry))
+            .map(|(hash, key, entry)| (hash, (key, entry.value.type_name())));
+        let (next, page) = scan_sorted(entries, count);
+
+        // MATCH and TYPE filter the page after it is cut, as in Redis, so a
+        // page may come back short or empty before the walk is done.
+        let page: Vec<String> = page
+            .into_iter()
+            .filter(|(key, _)| {
+                pattern
+                    .as_ref()
+                    .is_none_or(|p| glob_match(p, key.as_bytes()))
+            })
+            .filter(|(_, name)| {
+                type_name
+                    .as_ref()
+                    .is_none_or(|t| t.eq_ignore_ascii_case(name))
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        (next, page)
+    };
+
+    let mut resp = array_len(2);
+    resp.extend_from_slice(&bulk_str(&next.to_string()));
+    resp.extend_from_slice(&array_len(page.len()));
+    for key in page {
+        resp.extend_from_slice(&bulk_str(&key));
+    }
+
+    socket.write_all(&resp).await?;
+    Ok(())
+}
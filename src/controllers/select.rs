@@ -0,0 +1,25 @@
+use crate::databases::{Database, Databases};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// Replies to SELECT and hands back the chosen database for the connection
+/// to switch to, or `None` if the index is out of range.
+pub async fn select_cmd(
+    index: i64,
+    databases: &Databases,
+    socket: &mut TcpStream,
+) -> Result<Option<Database>> {
+    let Some(database) = usize::try_from(index)
+        .ok()
+        .and_then(|index| databases.get(index))
+    else {
+        socket
+            .write_all(b"-ERR DB index is out of range\r\n")
+            .await?;
+        return Ok(None);
+    };
+
+    socket.write_all(b"+OK\r\n").await?;
+    Ok(Some(database.clone()))
+}
@@ -1,6 +1,7 @@
 use crate::{
     lru::{estimate_entry_bytes, LruManager},
-    model::{DB, Entry, Heap, Value},
+    model::{DB, Entry, Heap, MinHeap, SetExpiry, SetOptions, Value},
+    util::{WRONGTYPE_ERR, bulk_bytes, drop_expired, null_bulk},
 };
 use anyhow::Result;
 use tokio::io::AsyncWriteExt;
@@ -9,18 +10,54 @@ use tokio::net::TcpStream;
 pub async fn set_cmd(
     key: String,
     value: Vec<u8>,
+    options: SetOptions,
     _db: &DB,
     _heap: &mut Heap,
     lru: &LruManager,
     socket: &mut TcpStream,
 ) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    // GET replies with the previous string, so any other type is an error
+    // before anything is written.
+    let previous = match db.get(&key).map(|entry| entry.value.as_string_bytes()) {
+        Some(Some(bytes)) => Some(bulk_bytes(&bytes)),
+        Some(None) if options.get => {
+            drop(db);
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+        _ => None,
+    };
+    let reply = if options.get {
+        previous.unwrap_or_else(null_bulk)
+    } else {
+        b"+OK\r\n".to_vec()
+    };
+
+    let exists = db.contains_key(&key);
+    if (options.nx && exists) || (options.xx && !exists) {
+        drop(db);
+        let resp = if options.get { reply } else { null_bulk() };
+        socket.write_all(&resp).await?;
+        return Ok(());
+    }
+
+    let expires_at = match options.expiry {
+        Some(SetExpiry::KeepTtl) => db.get(&key).and_then(|entry| entry.expires_at),
+        Some(expiry) => expiry.deadline(),
+        None => None,
+    };
     let new_entry = Entry {
         value: Value::String(value),
-        expires_at: None,
+        expires_at,
     };
     let new_size = estimate_entry_bytes(&key, &new_entry);
 
-    let mut db = _db.write().await;
     let old = db.insert(key.clone(), new_entry);
     let old_size = old
         .as_ref()
@@ -28,12 +65,22 @@ pub async fn set_cmd(
         .unwrap_or(0);
     drop(db);
 
+    if let Some(expires_at) = expires_at
+        && !matches!(options.expiry, Some(SetExpiry::KeepTtl))
+    {
+        let mut heap = _heap.lock().await;
+        heap.push(MinHeap {
+            key: key.clone(),
+            expires_at,
+        });
+    }
+
     let delta = new_size as isize - old_size as isize;
     let new_used = lru.adjust_used_bytes(delta);
     let maxmemory = lru.maxmemory();
 
     if maxmemory > 0 && new_used > maxmemory {
-        let evicted = lru.evict_if_needed(_db, _heap).await?;
+        let evicted = lru.evict_if_needed().await?;
         if !evicted {
             let mut db = _db.write().await;
             match old {
@@ -53,7 +100,7 @@ pub async fn set_cmd(
         }
     }
 
-    socket.write_all(b"+OK\r\n").await?;
+    socket.write_all(&reply).await?;
 
     Ok(())
 }
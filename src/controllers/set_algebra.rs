@@ -0,0 +1,235 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Value},
+    util::{OOM_ERR, WRONGTYPE_ERR, array_len, bulk_bytes, integer, is_expired},
+};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+#[derive(Clone, Copy)]
+enum SetOp {
+    Inter,
+    Union,
+    Diff,
+}
+
+pub async fn sinter_cmd(keys: Vec<String>, _db: &DB, socket: &mut TcpStream) -> Result<()> {
+    combine_cmd(SetOp::Inter, keys, _db, socket).await
+}
+
+pub async fn sunion_cmd(keys: Vec<String>, _db: &DB, socket: &mut TcpStream) -> Result<()> {
+    combine_cmd(SetOp::Union, keys, _db, socket).await
+}
+
+pub async fn sdiff_cmd(keys: Vec<String>, _db: &DB, socket: &mut TcpStream) -> Result<()> {
+    combine_cmd(SetOp::Diff, keys, _db, socket).await
+}
+
+pub async fn sinterstore_cmd(
+    destination: String,
+    keys: Vec<String>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    store_cmd(SetOp::Inter, destination, keys, _db, _heap, lru, socket).await
+}
+
+pub async fn sunionstore_cmd(
+    destination: String,
+    keys: Vec<String>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    store_cmd(SetOp::Union, destination, keys, _db, _heap, lru, socket).await
+}
+
+pub async fn sdiffstore_cmd(
+    destination: String,
+    keys: Vec<String>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    store_cmd(SetOp::Diff, destination, keys, _db, _heap, lru, socket).await
+}
+
+pub async fn sintercard_cmd(
+    keys: Vec<String>,
+    limit: usize,
+    _db: &DB,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let count = {
+        let db = _db.read().await;
+        let Some(sets) = source_sets(&db, &keys) else {
+            drop(db);
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        };
+
+        let mut count = 0usize;
+        if let Some(sets) = sets.into_iter().collect::<Option<Vec<_>>>() {
+            let (smallest, rest) = split_smallest(sets);
+            for member in smallest {
+                if rest.iter().all(|s| s.contains(member)) {
+                    count += 1;
+                    if limit > 0 && count >= limit {
+                        break;
+                    }
+                }
+            }
+        }
+        count
+    };
+
+    socket.write_all(&integer(count as i64)).await?;
+    Ok(())
+}
+
+async fn combine_cmd(op: SetOp, keys: Vec<String>, _db: &DB, socket: &mut TcpStream) -> Result<()> {
+    let resp = {
+        let db = _db.read().await;
+        combine_sets(&db, &keys, op).map(|result| {
+            let mut resp = array_len(result.len());
+            for member in &result {
+                resp.extend_from_slice(&bulk_bytes(member));
+            }
+            resp
+        })
+    };
+
+    match resp {
+        Some(resp) => socket.write_all(&resp).await?,
+        None => socket.write_all(WRONGTYPE_ERR).await?,
+    }
+
+    Ok(())
+}
+
+async fn store_cmd(
+    op: SetOp,
+    destination: String,
+    keys: Vec<String>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let Some(result) = combine_sets(&db, &keys, op) else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let len = result.len();
+    let old = db.remove(&destination);
+    let old_size = old
+        .as_ref()
+        .map(|entry| estimate_entry_bytes(&destination, entry))
+        .unwrap_or(0);
+
+    let mut new_size = 0usize;
+    if len > 0 {
+        let new_entry = Entry {
+            value: Value::Set(result),
+            expires_at: None,
+        };
+        new_size = estimate_entry_bytes(&destination, &new_entry);
+        db.insert(destination.clone(), new_entry);
+    }
+    drop(db);
+
+    if len == 0 && old.is_some() {
+        lru.remove_key(_db, &destination).await;
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        match old {
+            Some(old_entry) => {
+                db.insert(destination.clone(), old_entry);
+            }
+            None => {
+                db.remove(&destination);
+            }
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(len as i64)).await?;
+
+    Ok(())
+}
+
+/// Looks up every source key, treating expired keys as missing. Returns
+/// `None` if any of them holds something other than a set.
+fn source_sets<'a>(
+    db: &'a HashMap<String, Entry>,
+    keys: &[String],
+) -> Option<Vec<Option<&'a HashSet<Vec<u8>>>>> {
+    let mut sets = Vec::with_capacity(keys.len());
+    for key in keys {
+        match db.get(key) {
+            Some(entry) if !is_expired(entry) => sets.push(Some(entry.value.as_set()?)),
+            _ => sets.push(None),
+        }
+    }
+    Some(sets)
+}
+
+fn split_smallest(mut sets: Vec<&HashSet<Vec<u8>>>) -> (&HashSet<Vec<u8>>, Vec<&HashSet<Vec<u8>>>) {
+    let idx = (0..sets.len()).min_by_key(|&i| sets[i].len()).unwrap_or(0);
+    let smallest = sets.swap_remove(idx);
+    (smallest, sets)
+}
+
+fn combine_sets(
+    db: &HashMap<String, Entry>,
+    keys: &[String],
+    op: SetOp,
+) -> Option<HashSet<Vec<u8>>> {
+    let sets = source_sets(db, keys)?;
+
+    let result = match op {
+        SetOp::Inter => match sets.into_iter().collect::<Option<Vec<_>>>() {
+            Some(sets) => {
+                let (smallest, rest) = split_smallest(sets);
+                smallest
+                    .iter()
+                    .filter(|member| rest.iter().all(|s| s.contains(*member)))
+                    .cloned()
+                    .collect()
+            }
+            None => HashSet::new(),
+        },
+        SetOp::Union => sets.into_iter().flatten().flatten().cloned().collect(),
+        SetOp::Diff => {
+            let mut iter = sets.into_iter();
+            match iter.next().flatten() {
+                Some(first) => {
+                    let rest: Vec<&HashSet<Vec<u8>>> = iter.flatten().collect();
+                    first
+                        .iter()
+                        .filter(|member| !rest.iter().any(|s| s.contains(*member)))
+                        .cloned()
+                        .collect()
+                }
+                None => HashSet::new(),
+            }
+        }
+    };
+
+    Some(result)
+}
@@ -1,9 +1,8 @@
 use crate::{
     lru::{estimate_entry_bytes, LruManager},
-    model::{DB, Entry, Heap, MinHeap, Value},
+    model::{DB, Entry, Heap, MinHeap, Value, unix_millis},
 };
 use anyhow::Result;
-use std::time::{Duration, Instant};
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
 
@@ -16,7 +15,7 @@ pub async fn setex_cmd(
     lru: &LruManager,
     socket: &mut TcpStream,
 ) -> Result<()> {
-    let expires_at = Instant::now() + Duration::from_secs(seconds);
+    let expires_at = unix_millis().saturating_add(seconds.saturating_mul(1000));
     let new_entry = Entry {
         value: Value::String(value),
         expires_at: Some(expires_at),
@@ -43,7 +42,7 @@ pub async fn setex_cmd(
     let maxmemory = lru.maxmemory();
 
     if maxmemory > 0 && new_used > maxmemory {
-        let evicted = lru.evict_if_needed(_db, _heap).await?;
+        let evicted = lru.evict_if_needed().await?;
         if !evicted {
             let mut db = _db.write().await;
             match old {
@@ -0,0 +1,51 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Value},
+    util::{OOM_ERR, drop_expired},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn setnx_cmd(
+    key: String,
+    value: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    if db.contains_key(&key) {
+        drop(db);
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    }
+
+    let new_entry = Entry {
+        value: Value::String(value),
+        expires_at: None,
+    };
+    let new_size = estimate_entry_bytes(&key, &new_entry);
+    db.insert(key.clone(), new_entry);
+    drop(db);
+
+    let delta = new_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        db.remove(&key);
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(b":1\r\n").await?;
+
+    Ok(())
+}
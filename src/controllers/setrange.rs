@@ -0,0 +1,94 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Value},
+    util::{OOM_ERR, WRONGTYPE_ERR, drop_expired, integer},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// Largest string SETRANGE may produce, matching Redis' default
+/// `proto-max-bulk-len` of 512MB.
+const MAX_STRING_LEN: usize = 512 * 1024 * 1024;
+
+pub async fn setrange_cmd(
+    key: String,
+    offset: usize,
+    value: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    if offset + value.len() > MAX_STRING_LEN {
+        socket
+            .write_all(b"-ERR string exceeds maximum allowed size (proto-max-bulk-len)\r\n")
+            .await?;
+        return Ok(());
+    }
+
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    // Nothing to write only reports the current length, without padding or
+    // creating the key.
+    if value.is_empty() {
+        let resp = match db.get(&key).map(|e| e.value.as_string_bytes()) {
+            Some(Some(bytes)) => integer(bytes.len() as i64),
+            Some(None) => WRONGTYPE_ERR.to_vec(),
+            None => integer(0),
+        };
+        drop(db);
+        socket.write_all(&resp).await?;
+        return Ok(());
+    }
+
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::String(Vec::new()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(bytes) = entry.value.as_string_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let previous = bytes.clone();
+    let end = offset + value.len();
+    if bytes.len() < end {
+        bytes.resize(end, 0);
+    }
+    bytes[offset..end].copy_from_slice(&value);
+    let len = bytes.len();
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(bytes) = db.get_mut(&key).and_then(|e| e.value.as_string_mut()) {
+            *bytes = previous;
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(len as i64)).await?;
+
+    Ok(())
+}
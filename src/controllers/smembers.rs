@@ -0,0 +1,35 @@
+use crate::model::{DB, Heap, Value};
+use crate::util::{Lookup, WRONGTYPE_ERR, array_len, bulk_bytes, read_value};
+use std::collections::HashSet;
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn smembers_cmd(
+    key: String,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| match value {
+        Value::Set(set) => Some(set_resp(set)),
+        _ => None,
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(b"*0\r\n").await?,
+    }
+
+    Ok(())
+}
+
+fn set_resp(set: &HashSet<Vec<u8>>) -> Vec<u8> {
+    let mut resp = array_len(set.len());
+    for member in set {
+        resp.extend_from_slice(&bulk_bytes(member));
+    }
+    resp
+}
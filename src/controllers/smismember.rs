@@ -0,0 +1,40 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, array_len, integer, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn smismember_cmd(
+    key: String,
+    members: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_set().map(|set| {
+            members
+                .iter()
+                .map(|member| set.contains(member))
+                .collect::<Vec<bool>>()
+        })
+    })
+    .await;
+
+    let found = match lookup {
+        Lookup::WrongType => {
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+        Lookup::Found(found) => found,
+        Lookup::Missing => vec![false; members.len()],
+    };
+
+    let mut resp = array_len(found.len());
+    for is_member in found {
+        resp.extend_from_slice(&integer(is_member as i64));
+    }
+
+    socket.write_all(&resp).await?;
+    Ok(())
+}
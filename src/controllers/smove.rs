@@ -0,0 +1,96 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Value},
+    util::{WRONGTYPE_ERR, drop_expired},
+};
+use anyhow::Result;
+use std::collections::HashSet;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn smove_cmd(
+    source: String,
+    destination: String,
+    member: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &source) + drop_expired(&mut db, &destination);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let wrong_type = |key: &String| db.get(key).is_some_and(|e| e.value.as_set().is_none());
+    if wrong_type(&source) || wrong_type(&destination) {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    }
+
+    let Some(src_entry) = db.get_mut(&source) else {
+        drop(db);
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    };
+
+    if source == destination {
+        let is_member = src_entry
+            .value
+            .as_set()
+            .is_some_and(|s| s.contains(&member));
+        drop(db);
+        socket
+            .write_all(if is_member { b":1\r\n" } else { b":0\r\n" })
+            .await?;
+        return Ok(());
+    }
+
+    let old_src_size = estimate_entry_bytes(&source, src_entry);
+    let Some(src_set) = src_entry.value.as_set_mut() else {
+        return Ok(());
+    };
+    if !src_set.remove(&member) {
+        drop(db);
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    }
+
+    let src_emptied = src_set.is_empty();
+    let new_src_size = if src_emptied {
+        db.remove(&source);
+        0
+    } else {
+        estimate_entry_bytes(&source, src_entry)
+    };
+
+    let created_new = !db.contains_key(&destination);
+    let dst_entry = db.get_or_insert_with(destination.clone(), || Entry {
+        value: Value::Set(HashSet::new()),
+        expires_at: None,
+    });
+    let old_dst_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&destination, dst_entry)
+    };
+    if let Some(dst_set) = dst_entry.value.as_set_mut() {
+        dst_set.insert(member);
+    }
+    let new_dst_size = estimate_entry_bytes(&destination, dst_entry);
+    drop(db);
+
+    let delta = (new_src_size + new_dst_size) as isize - (old_src_size + old_dst_size) as isize;
+    if delta != 0 {
+        lru.adjust_used_bytes(delta);
+    }
+    if src_emptied {
+        lru.remove_key(_db, &source).await;
+    }
+
+    socket.write_all(b":1\r\n").await?;
+
+    Ok(())
+}
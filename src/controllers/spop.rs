@@ -0,0 +1,97 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap, MinHeap},
+    util::{WRONGTYPE_ERR, array_len, bulk_bytes, is_expired, null_bulk, sample_distinct},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn spop_cmd(
+    key: String,
+    count: Option<usize>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut expired_at = None;
+    let mut popped: Vec<Vec<u8>> = Vec::new();
+    let mut old_size = 0usize;
+    let mut new_size = 0usize;
+    let mut remove_key = false;
+    let mut wrong_type = false;
+
+    {
+        let mut db = _db.write().await;
+        if let Some(entry) = db.get_mut(&key) {
+            if is_expired(entry) {
+                expired_at = entry.expires_at;
+            } else {
+                old_size = estimate_entry_bytes(&key, entry);
+                match entry.value.as_set_mut() {
+                    Some(set) => {
+                        let take = count.unwrap_or(1);
+                        if take >= set.len() {
+                            popped = set.drain().collect();
+                        } else {
+                            // Only the popped members are copied out of the set.
+                            popped = sample_distinct(set.iter(), set.len(), take)
+                                .into_iter()
+                                .cloned()
+                                .collect();
+                            for member in &popped {
+                                set.remove(member);
+                            }
+                        }
+
+                        if set.is_empty() {
+                            remove_key = true;
+                        } else {
+                            new_size = estimate_entry_bytes(&key, entry);
+                        }
+                    }
+                    None => wrong_type = true,
+                }
+
+                if remove_key {
+                    db.remove(&key);
+                }
+            }
+        }
+    }
+
+    if wrong_type {
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    }
+
+    if let Some(expires_at) = expired_at {
+        let mut heap = _heap.lock().await;
+        heap.push(MinHeap { key, expires_at });
+    } else {
+        let delta = new_size as isize - old_size as isize;
+        if delta != 0 {
+            lru.adjust_used_bytes(delta);
+        }
+        if remove_key {
+            lru.remove_key(_db, &key).await;
+        }
+    }
+
+    match count {
+        Some(_) => {
+            let mut resp = array_len(popped.len());
+            for member in &popped {
+                resp.extend_from_slice(&bulk_bytes(member));
+            }
+            socket.write_all(&resp).await?;
+        }
+        None => match popped.first() {
+            Some(member) => socket.write_all(&bulk_bytes(member)).await?,
+            None => socket.write_all(&null_bulk()).await?,
+        },
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::model::{DB, Heap};
+use crate::util::{
+    Lookup, REPLY_CHUNK_BYTES, WRONGTYPE_ERR, array_len, bulk_bytes, null_bulk, random_index,
+    read_value, sample_distinct, sample_repeated,
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn srandmember_cmd(
+    key: String,
+    count: Option<i64>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_set().map(|set| match count {
+            Some(count) if count >= 0 => sample_distinct(set.iter(), set.len(), count as usize)
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>(),
+            // Up to the set's size, only the picked members are copied out;
+            // beyond it the picks are drawn from the whole set while replying.
+            Some(count) if count.unsigned_abs() as usize <= set.len() => {
+                sample_repeated(set.iter(), set.len(), count.unsigned_abs() as usize)
+                    .into_iter()
+                    .cloned()
+                    .collect()
+            }
+            Some(_) => set.iter().cloned().collect(),
+            None => set
+                .iter()
+                .nth(random_index(set.len()))
+                .cloned()
+                .into_iter()
+                .collect(),
+        })
+    })
+    .await;
+
+    let members = match lookup {
+        Lookup::WrongType => {
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+        Lookup::Missing => {
+            if count.is_some() {
+                socket.write_all(b"*0\r\n").await?;
+            } else {
+                socket.write_all(&null_bulk()).await?;
+            }
+            return Ok(());
+        }
+        Lookup::Found(members) => members,
+    };
+
+    match count {
+        None => socket.write_all(&bulk_bytes(&members[0])).await?,
+        Some(count) if count < 0 => {
+            // The count, not the set, sizes this reply, so it goes out in chunks.
+            let count = count.unsigned_abs() as usize;
+            let drawn = members.len() == count;
+            let mut resp = array_len(count);
+            for i in 0..count {
+                let member = if drawn {
+                    &members[i]
+                } else {
+                    &members[random_index(members.len())]
+                };
+                resp.extend_from_slice(&bulk_bytes(member));
+                if resp.len() >= REPLY_CHUNK_BYTES {
+                    socket.write_all(&resp).await?;
+                    resp.clear();
+                }
+            }
+            socket.write_all(&resp).await?;
+        }
+        Some(_) => {
+            let mut resp = array_len(members.len());
+            for member in &members {
+                resp.extend_from_slice(&bulk_bytes(member));
+            }
+            socket.write_all(&resp).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap, MinHeap},
+    util::{WRONGTYPE_ERR, integer, is_expired},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn srem_cmd(
+    key: String,
+    members: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut expired_at = None;
+    let mut removed = 0;
+    let mut old_size = 0usize;
+    let mut new_size = 0usize;
+    let mut remove_key = false;
+    let mut wrong_type = false;
+
+    {
+        let mut db = _db.write().await;
+        if let Some(entry) = db.get_mut(&key) {
+            if is_expired(entry) {
+                expired_at = entry.expires_at;
+            } else {
+                old_size = estimate_entry_bytes(&key, entry);
+                match entry.value.as_set_mut() {
+                    Some(set) => {
+                        for member in &members {
+                            if set.remove(member) {
+                                removed += 1;
+                            }
+                        }
+                        if set.is_empty() {
+                            remove_key = true;
+                        } else {
+                            new_size = estimate_entry_bytes(&key, entry);
+                        }
+                    }
+                    None => wrong_type = true,
+                }
+
+                if remove_key {
+                    db.remove(&key);
+                }
+            }
+        }
+    }
+
+    if wrong_type {
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    }
+
+    if let Some(expires_at) = expired_at {
+        let mut heap = _heap.lock().await;
+        heap.push(MinHeap { key, expires_at });
+        socket.write_all(b":0\r\n").await?;
+        return Ok(());
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if delta != 0 {
+        lru.adjust_used_bytes(delta);
+    }
+    if remove_key {
+        lru.remove_key(_db, &key).await;
+    }
+
+    socket.write_all(&integer(removed)).await?;
+
+    Ok(())
+}
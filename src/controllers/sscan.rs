@@ -0,0 +1,52 @@
+use crate::model::{DB, Heap};
+use crate::util::{
+    Lookup, WRONGTYPE_ERR, array_len, bulk_bytes, bulk_str, glob_match, read_value, scan_hash,
+    scan_page,
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn sscan_cmd(
+    key: String,
+    cursor: u64,
+    pattern: Option<Vec<u8>>,
+    count: usize,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_set().map(|set| {
+            let entries = set.iter().map(|member| (scan_hash(member), member));
+            let (next, page) = scan_page(entries, cursor, count);
+            let page: Vec<Vec<u8>> = page.into_iter().cloned().collect();
+            (next, page)
+        })
+    })
+    .await;
+
+    let (next, page) = match lookup {
+        Lookup::WrongType => {
+            socket.write_all(WRONGTYPE_ERR).await?;
+            return Ok(());
+        }
+        Lookup::Found(found) => found,
+        Lookup::Missing => (0, Vec::new()),
+    };
+
+    let page: Vec<Vec<u8>> = page
+        .into_iter()
+        .filter(|member| pattern.as_ref().is_none_or(|p| glob_match(p, member)))
+        .collect();
+
+    let mut resp = array_len(2);
+    resp.extend_from_slice(&bulk_str(&next.to_string()));
+    resp.extend_from_slice(&array_len(page.len()));
+    for member in page {
+        resp.extend_from_slice(&bulk_bytes(&member));
+    }
+
+    socket.write_all(&resp).await?;
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::{databases::Databases, lru::LruManager};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn swapdb_cmd(
+    first: i64,
+    second: i64,
+    databases: &Databases,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let index = |i: i64| usize::try_from(i).ok().filter(|i| *i < databases.count());
+    let (Some(first), Some(second)) = (index(first), index(second)) else {
+        socket
+            .write_all(b"-ERR DB index is out of range\r\n")
+            .await?;
+        return Ok(());
+    };
+
+    databases.swap(first, second).await;
+    lru.swap_dbs(first, second).await;
+    socket.write_all(b"+OK\r\n").await?;
+    Ok(())
+}
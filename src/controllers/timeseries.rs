@@ -0,0 +1,285 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{
+        DB, DuplicatePolicy, Entry, Heap, LabelFilter, TimeSeries, TsAddError, TsRange, Value,
+        unix_millis,
+    },
+    util::{
+        Create, Lookup, OOM_ERR, WRONGTYPE_ERR, array_len, bulk_str, create_value, drop_expired,
+        format_float, integer, is_expired, read_value,
+    },
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+const NO_KEY_ERR: &[u8] = b"-ERR TSDB: the key does not exist\r\n";
+
+pub async fn ts_create_cmd(
+    key: String,
+    series: TimeSeries,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let footprint = series.bytes();
+    match create_value(_db, lru, &key, footprint, || Value::TimeSeries(series)).await? {
+        Create::Created => socket.write_all(b"+OK\r\n").await?,
+        Create::Exists => {
+            socket
+                .write_all(b"-ERR TSDB: key already exists\r\n")
+                .await?
+        }
+        Create::OutOfMemory => socket.write_all(OOM_ERR).await?,
+    }
+
+    Ok(())
+}
+
+/// TS.ADD. A missing key is created from `series`, the empty series the
+/// parser built from the command's RETENTION, DUPLICATE_POLICY and LABELS.
+#[allow(clippy::too_many_arguments)]
+pub async fn ts_add_cmd(
+    key: String,
+    timestamp: Option<u64>,
+    value: f64,
+    on_duplicate: Option<DuplicatePolicy>,
+    series: TimeSeries,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let timestamp = timestamp.unwrap_or_else(unix_millis);
+
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::TimeSeries(series),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(ts) = entry.value.as_timeseries_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+    let previous = match ts.add(timestamp, value, on_duplicate) {
+        Ok(previous) => previous,
+        Err(err) => {
+            if created_new {
+                db.remove(&key);
+            }
+            drop(db);
+            socket.write_all(&add_err(err)).await?;
+            return Ok(());
+        }
+    };
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(ts) = db.get_mut(&key).and_then(|e| e.value.as_timeseries_mut()) {
+            ts.undo_add(timestamp, previous);
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    trim_retention(_db, lru, [key.as_str()]).await;
+    socket.write_all(&integer(timestamp as i64)).await?;
+
+    Ok(())
+}
+
+/// TS.MADD. Unlike TS.ADD it never creates keys; each sample gets its own
+/// reply, so one bad key does not fail the rest.
+pub async fn ts_madd_cmd(
+    samples: Vec<(String, Option<u64>, f64)>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let now = unix_millis();
+
+    let mut db = _db.write().await;
+    let mut replies: Vec<Vec<u8>> = Vec::with_capacity(samples.len());
+    let mut applied: Vec<(&str, u64, Option<f64>)> = Vec::new();
+    let mut delta = 0isize;
+    for (key, timestamp, value) in &samples {
+        let freed = drop_expired(&mut db, key);
+        if freed > 0 {
+            lru.adjust_used_bytes(-(freed as isize));
+        }
+
+        let Some(entry) = db.get_mut(key) else {
+            replies.push(NO_KEY_ERR.to_vec());
+            continue;
+        };
+        let old_size = estimate_entry_bytes(key, entry);
+        let Some(ts) = entry.value.as_timeseries_mut() else {
+            replies.push(WRONGTYPE_ERR.to_vec());
+            continue;
+        };
+        let timestamp = timestamp.unwrap_or(now);
+        match ts.add(timestamp, *value, None) {
+            Ok(previous) => {
+                applied.push((key, timestamp, previous));
+                replies.push(integer(timestamp as i64));
+            }
+            Err(err) => replies.push(add_err(err)),
+        }
+        delta += estimate_entry_bytes(key, entry) as isize - old_size as isize;
+    }
+    drop(db);
+
+    if !lru.try_reserve(delta).await? {
+        // Undo newest first so repeated timestamps unwind to the original.
+        let mut db = _db.write().await;
+        for (key, timestamp, previous) in applied.into_iter().rev() {
+            if let Some(ts) = db.get_mut(key).and_then(|e| e.value.as_timeseries_mut()) {
+                ts.undo_add(timestamp, previous);
+            }
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    trim_retention(_db, lru, applied.iter().map(|(key, _, _)| *key)).await;
+    let mut resp = array_len(replies.len());
+    for reply in replies {
+        resp.extend_from_slice(&reply);
+    }
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
+
+/// Applies retention to the series at `keys` once their new samples are
+/// charged, as RedisTimeSeries does on insertion. Retention counts back from
+/// the newest sample, so only an add can push samples out of the window.
+async fn trim_retention<'a>(db: &DB, lru: &LruManager, keys: impl IntoIterator<Item = &'a str>) {
+    let mut db = db.write().await;
+    let mut freed = 0usize;
+    for key in keys {
+        let key = key.to_string();
+        let Some(entry) = db.get_mut(&key) else {
+            continue;
+        };
+        let old_size = estimate_entry_bytes(&key, entry);
+        if let Some(ts) = entry.value.as_timeseries_mut()
+            && ts.trim() > 0
+        {
+            freed += old_size.saturating_sub(estimate_entry_bytes(&key, entry));
+        }
+    }
+    drop(db);
+
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+}
+
+/// TS.RANGE and TS.REVRANGE; `range.rev` tells them apart.
+pub async fn ts_range_cmd(
+    key: String,
+    range: TsRange,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value
+            .as_timeseries()
+            .map(|ts| samples_resp(&ts.range(&range)))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+/// TS.MRANGE: the range of every series whose labels pass all `filters`,
+/// ordered by key. Each reply is `[key, labels, samples]`, with the labels
+/// left empty unless `with_labels` is set.
+pub async fn ts_mrange_cmd(
+    range: TsRange,
+    with_labels: bool,
+    filters: Vec<LabelFilter>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let db = _db.read().await;
+    let mut matched: Vec<(&String, &TimeSeries)> = db
+        .iter()
+        .filter(|(_, entry)| !is_expired(entry))
+        .filter_map(|(key, entry)| entry.value.as_timeseries().map(|ts| (key, ts)))
+        .filter(|(_, ts)| ts.matches(&filters))
+        .collect();
+    matched.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut resp = array_len(matched.len());
+    for (key, ts) in matched {
+        resp.extend_from_slice(&array_len(3));
+        resp.extend_from_slice(&bulk_str(key));
+        if with_labels {
+            resp.extend_from_slice(&array_len(ts.labels.len()));
+            for (label, value) in &ts.labels {
+                resp.extend_from_slice(&array_len(2));
+                resp.extend_from_slice(&bulk_str(label));
+                resp.extend_from_slice(&bulk_str(value));
+            }
+        } else {
+            resp.extend_from_slice(&array_len(0));
+        }
+        resp.extend_from_slice(&samples_resp(&ts.range(&range)));
+    }
+    drop(db);
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
+
+fn samples_resp(samples: &[(u64, f64)]) -> Vec<u8> {
+    let mut resp = array_len(samples.len());
+    for (timestamp, value) in samples {
+        resp.extend_from_slice(&array_len(2));
+        resp.extend_from_slice(&integer(*timestamp as i64));
+        resp.extend_from_slice(&bulk_str(&format_float(*value)));
+    }
+    resp
+}
+
+fn add_err(err: TsAddError) -> Vec<u8> {
+    match err {
+        TsAddError::TooOld => b"-ERR TSDB: Timestamp is older than retention\r\n".to_vec(),
+        TsAddError::Blocked => b"-ERR TSDB: Error at upsert, update is not supported when DUPLICATE_POLICY is set to BLOCK mode\r\n".to_vec(),
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    lru::LruManager,
+    model::{DB, Heap, TopK, Value},
+    util::{
+        Create, Lookup, OOM_ERR, WRONGTYPE_ERR, array_len, bulk_bytes, create_value, integer,
+        null_bulk, read_value, update_value,
+    },
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+const NO_KEY_ERR: &[u8] = b"-ERR TopK: key does not exist\r\n";
+
+#[allow(clippy::too_many_arguments)]
+pub async fn topk_reserve_cmd(
+    key: String,
+    k: usize,
+    width: usize,
+    depth: usize,
+    decay: f64,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let footprint = TopK::footprint(k, width, depth);
+    let make = || Value::TopK(TopK::new(k, width, depth, decay));
+    match create_value(_db, lru, &key, footprint, make).await? {
+        Create::Created => socket.write_all(b"+OK\r\n").await?,
+        Create::Exists => {
+            socket
+                .write_all(b"-ERR TopK: key already exists\r\n")
+                .await?
+        }
+        Create::OutOfMemory => socket.write_all(OOM_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn topk_add_cmd(
+    key: String,
+    items: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        let topk = value.as_topk_mut()?;
+        Some(items.iter().map(|item| topk.add(item)).collect::<Vec<_>>())
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(expelled) => {
+            let mut resp = array_len(expelled.len());
+            for item in expelled {
+                match item {
+                    Some(item) => resp.extend_from_slice(&bulk_bytes(&item)),
+                    None => resp.extend_from_slice(&null_bulk()),
+                }
+            }
+            socket.write_all(&resp).await?
+        }
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn topk_query_cmd(
+    key: String,
+    items: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let topk = value.as_topk()?;
+        let mut resp = array_len(items.len());
+        for item in &items {
+            resp.extend_from_slice(&integer(topk.contains(item) as i64));
+        }
+        Some(resp)
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn topk_count_cmd(
+    key: String,
+    items: Vec<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let topk = value.as_topk()?;
+        let mut resp = array_len(items.len());
+        for item in &items {
+            resp.extend_from_slice(&integer(topk.count(item) as i64));
+        }
+        Some(resp)
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn topk_list_cmd(
+    key: String,
+    with_count: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        let list = value.as_topk()?.list();
+        let per_item = if with_count { 2 } else { 1 };
+        let mut resp = array_len(list.len() * per_item);
+        for (item, count) in list {
+            resp.extend_from_slice(&bulk_bytes(item));
+            if with_count {
+                resp.extend_from_slice(&integer(count as i64));
+            }
+        }
+        Some(resp)
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
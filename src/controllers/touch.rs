@@ -0,0 +1,15 @@
+use super::exists_cmd;
+use crate::model::{DB, Heap};
+use anyhow::Result;
+use tokio::net::TcpStream;
+
+/// TOUCH replies like EXISTS; the access it exists for is recorded by the
+/// client loop, as for every other command naming keys.
+pub async fn touch_cmd(
+    keys: Vec<String>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    exists_cmd(keys, _db, _heap, socket).await
+}
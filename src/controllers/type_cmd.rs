@@ -1,4 +1,4 @@
-use crate::model::{DB, Heap, MinHeap, Value};
+use crate::model::{DB, Heap, MinHeap};
 use crate::util::is_expired;
 use anyhow::Result;
 use tokio::io::AsyncWriteExt;
@@ -19,10 +19,7 @@ pub async fn type_cmd(
             if is_expired(entry) {
                 expired_at = entry.expires_at;
             } else {
-                value_type = Some(match entry.value {
-                    Value::String(_) => "string",
-                    Value::List(_) => "list",
-                });
+                value_type = Some(entry.value.type_name());
             }
         }
     }
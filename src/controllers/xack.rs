@@ -0,0 +1,39 @@
+use crate::{
+    lru::LruManager,
+    model::{DB, Heap, StreamId},
+    util::{Lookup, WRONGTYPE_ERR, integer, update_value},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn xack_cmd(
+    key: String,
+    group: Vec<u8>,
+    ids: Vec<StreamId>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        value
+            .as_stream_mut()
+            .map(|stream| match stream.group_mut(&group) {
+                Some(group) => ids
+                    .iter()
+                    .filter(|id| group.pending.remove(id).is_some())
+                    .count(),
+                None => 0,
+            })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(acked) => socket.write_all(&integer(acked as i64)).await?,
+        Lookup::Missing => socket.write_all(b":0\r\n").await?,
+    }
+
+    Ok(())
+}
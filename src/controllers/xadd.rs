@@ -0,0 +1,95 @@
+use crate::{
+    blocking::BlockingManager,
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Stream, StreamFields, StreamIdSpec, StreamTrim, Value},
+    util::{OOM_ERR, WRONGTYPE_ERR, bulk_str, drop_expired, null_bulk},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn xadd_cmd(
+    key: String,
+    nomkstream: bool,
+    trim: Option<StreamTrim>,
+    id: StreamIdSpec,
+    fields: StreamFields,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    if nomkstream && !db.contains_key(&key) {
+        drop(db);
+        socket.write_all(&null_bulk()).await?;
+        return Ok(());
+    }
+
+    let created_new = !db.contains_key(&key);
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::Stream(Stream::new()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(stream) = entry.value.as_stream_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let Some(new_id) = stream.next_id(id) else {
+        if created_new {
+            db.remove(&key);
+        }
+        drop(db);
+        socket
+            .write_all(
+                b"-ERR The ID specified in XADD is equal or smaller than the target stream top item\r\n",
+            )
+            .await?;
+        return Ok(());
+    };
+
+    let previous_last_id = stream.last_id();
+    let previous_max_deleted_id = stream.max_deleted_id();
+    stream.insert(new_id, fields);
+    let trimmed = match &trim {
+        Some(trim) => stream.trim(trim),
+        None => Vec::new(),
+    };
+
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(stream) = db.get_mut(&key).and_then(|e| e.value.as_stream_mut()) {
+            stream.undo_insert(new_id, previous_last_id, previous_max_deleted_id, trimmed);
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    blocking.wake_all(&key);
+    socket.write_all(&bulk_str(&new_id.to_string())).await?;
+
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use crate::{
+    lru::LruManager,
+    model::{
+        DB, Heap, PendingEntry, Stream, StreamId, XClaimOptions, stream_entry_resp, unix_millis,
+    },
+    util::{Lookup, WRONGTYPE_ERR, array_len, bulk_str, nogroup_err, update_value},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+/// XAUTOCLAIM scans at most this many PEL entries per requested entry.
+const AUTOCLAIM_ATTEMPTS_FACTOR: usize = 10;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn xclaim_cmd(
+    key: String,
+    group: Vec<u8>,
+    consumer: Vec<u8>,
+    min_idle: u64,
+    ids: Vec<StreamId>,
+    options: XClaimOptions,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        value
+            .as_stream_mut()
+            .map(|stream| claim(stream, &group, &consumer, min_idle, &ids, &options))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(resp)) => socket.write_all(&resp).await?,
+        Lookup::Found(None) | Lookup::Missing => {
+            socket.write_all(&nogroup_err(&key, &group)).await?
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn xautoclaim_cmd(
+    key: String,
+    group: Vec<u8>,
+    consumer: Vec<u8>,
+    min_idle: u64,
+    start: StreamId,
+    count: usize,
+    justid: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        value
+            .as_stream_mut()
+            .map(|stream| autoclaim(stream, &group, &consumer, min_idle, start, count, justid))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(resp)) => socket.write_all(&resp).await?,
+        Lookup::Found(None) | Lookup::Missing => {
+            socket.write_all(&nogroup_err(&key, &group)).await?
+        }
+    }
+
+    Ok(())
+}
+
+fn claim(
+    stream: &mut Stream,
+    group: &[u8],
+    consumer: &[u8],
+    min_idle: u64,
+    ids: &[StreamId],
+    options: &XClaimOptions,
+) -> Option<Vec<u8>> {
+    let now = unix_millis();
+    let exists: Vec<bool> = ids.iter().map(|id| stream.get(id).is_some()).collect();
+    let group = stream.group_mut(group)?;
+    group.touch_consumer(consumer, now);
+
+    if let Some(last_id) = options.last_id
+        && last_id > group.last_id
+    {
+        group.last_id = last_id;
+    }
+
+    let delivered_at = match (options.time, options.idle) {
+        (Some(time), _) => time,
+        (None, Some(idle)) => now.saturating_sub(idle),
+        (None, None) => now,
+    };
+
+    let mut claimed = Vec::new();
+    for (id, exists) in ids.iter().zip(exists) {
+        if !exists {
+            // The entry was deleted from the stream; drop it from the PEL too.
+            group.pending.remove(id);
+            continue;
+        }
+        if !group.pending.contains_key(id) {
+            if !options.force {
+                continue;
+            }
+            group.pending.insert(
+                *id,
+                PendingEntry {
+                    consumer: consumer.to_vec(),
+                    delivered_at: now,
+                    delivery_count: 1,
+                },
+            );
+        }
+        let Some(pending) = group.pending.get_mut(id) else {
+            continue;
+        };
+        if now.saturating_sub(pending.delivered_at) < min_idle {
+            continue;
+        }
+
+        pending.consumer = consumer.to_vec();
+        pending.delivered_at = delivered_at;
+        match options.retry_count {
+            Some(retry_count) => pending.delivery_count = retry_count,
+            None if !options.justid => pending.delivery_count += 1,
+            None => {}
+        }
+        claimed.push(*id);
+    }
+
+    if !claimed.is_empty()
+        && let Some(consumer) = group.consumers.get_mut(consumer)
+    {
+        consumer.active_time = Some(now);
+    }
+
+    Some(claimed_resp(stream, &claimed, options.justid))
+}
+
+fn autoclaim(
+    stream: &mut Stream,
+    group: &[u8],
+    consumer: &[u8],
+    min_idle: u64,
+    start: StreamId,
+    count: usize,
+    justid: bool,
+) -> Option<Vec<u8>> {
+    let now = unix_millis();
+    let attempts = count.saturating_mul(AUTOCLAIM_ATTEMPTS_FACTOR);
+    let candidates: Vec<(StreamId, bool)> = stream
+        .group(group)?
+        .pending
+        .range(start..)
+        .map(|(id, _)| *id)
+        .take(attempts.saturating_add(1))
+        .map(|id| (id, stream.get(&id).is_some()))
+        .collect();
+
+    let group = stream.group_mut(group)?;
+    group.touch_consumer(consumer, now);
+
+    let mut next = StreamId::MIN;
+    let mut claimed = Vec::new();
+    let mut deleted = Vec::new();
+    for (scanned, (id, exists)) in candidates.into_iter().enumerate() {
+        if claimed.len() == count || scanned == attempts {
+            next = id;
+            break;
+        }
+
+        if !exists {
+            group.pending.remove(&id);
+            deleted.push(id);
+            continue;
+        }
+        let Some(pending) = group.pending.get_mut(&id) else {
+            continue;
+        };
+        if now.saturating_sub(pending.delivered_at) < min_idle {
+            continue;
+        }
+
+        pending.consumer = consumer.to_vec();
+        pending.delivered_at = now;
+        if !justid {
+            pending.delivery_count += 1;
+        }
+        claimed.push(id);
+    }
+
+    if !claimed.is_empty()
+        && let Some(consumer) = group.consumers.get_mut(consumer)
+    {
+        consumer.active_time = Some(now);
+    }
+
+    let mut resp = array_len(3);
+    resp.extend_from_slice(&bulk_str(&next.to_string()));
+    resp.extend_from_slice(&claimed_resp(stream, &claimed, justid));
+    resp.extend_from_slice(&array_len(deleted.len()));
+    for id in deleted {
+        resp.extend_from_slice(&bulk_str(&id.to_string()));
+    }
+    Some(resp)
+}
+
+fn claimed_resp(stream: &Stream, claimed: &[StreamId], justid: bool) -> Vec<u8> {
+    let mut resp = array_len(claimed.len());
+    for id in claimed {
+        if justid {
+            resp.extend_from_slice(&bulk_str(&id.to_string()));
+        } else {
+            resp.extend_from_slice(&stream_entry_resp(id, stream.get(id)));
+        }
+    }
+    resp
+}
@@ -0,0 +1,32 @@
+use crate::{
+    lru::LruManager,
+    model::{DB, Heap, StreamId},
+    util::{Lookup, WRONGTYPE_ERR, integer, update_value},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn xdel_cmd(
+    key: String,
+    ids: Vec<StreamId>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        value
+            .as_stream_mut()
+            .map(|stream| ids.iter().filter(|id| stream.delete(id)).count())
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(deleted) => socket.write_all(&integer(deleted as i64)).await?,
+        Lookup::Missing => socket.write_all(b":0\r\n").await?,
+    }
+
+    Ok(())
+}
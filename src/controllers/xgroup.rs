@@ -0,0 +1,207 @@
+use crate::{
+    blocking::BlockingManager,
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Stream, StreamReadId, Value, unix_millis},
+    util::{Lookup, OOM_ERR, WRONGTYPE_ERR, drop_expired, integer, nogroup_err, update_value},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+const NO_KEY_ERR: &[u8] = b"-ERR The XGROUP subcommand requires the key to exist. Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.\r\n";
+
+#[allow(clippy::too_many_arguments)]
+pub async fn xgroup_create_cmd(
+    key: String,
+    group: Vec<u8>,
+    id: StreamReadId,
+    mkstream: bool,
+    entries_read: Option<u64>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let created_new = !db.contains_key(&key);
+    if created_new && !mkstream {
+        drop(db);
+        socket.write_all(NO_KEY_ERR).await?;
+        return Ok(());
+    }
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::Stream(Stream::new()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(stream) = entry.value.as_stream_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let last_id = match id {
+        StreamReadId::After(id) => id,
+        _ => stream.last_id(),
+    };
+    if !stream.create_group(group.clone(), last_id, entries_read) {
+        drop(db);
+        socket
+            .write_all(b"-BUSYGROUP Consumer Group name already exists\r\n")
+            .await?;
+        return Ok(());
+    }
+
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(stream) = db.get_mut(&key).and_then(|e| e.value.as_stream_mut()) {
+            stream.destroy_group(&group);
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(b"+OK\r\n").await?;
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn xgroup_setid_cmd(
+    key: String,
+    group: Vec<u8>,
+    id: StreamReadId,
+    entries_read: Option<u64>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        value.as_stream_mut().map(|stream| {
+            let last_id = match id {
+                StreamReadId::After(id) => id,
+                _ => stream.last_id(),
+            };
+            let entries_read = entries_read.or_else(|| stream.entries_read_at(last_id));
+            let group = stream.group_mut(&group)?;
+            group.last_id = last_id;
+            group.entries_read = entries_read;
+            Some(())
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(())) => socket.write_all(b"+OK\r\n").await?,
+        Lookup::Found(None) => socket.write_all(&nogroup_err(&key, &group)).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn xgroup_destroy_cmd(
+    key: String,
+    group: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        value
+            .as_stream_mut()
+            .map(|stream| stream.destroy_group(&group))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(destroyed) => {
+            if destroyed {
+                // Clients blocked in XREADGROUP on this group get NOGROUP.
+                blocking.wake_all(&key);
+            }
+            socket.write_all(&integer(destroyed as i64)).await?
+        }
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn xgroup_createconsumer_cmd(
+    key: String,
+    group: Vec<u8>,
+    consumer: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        value.as_stream_mut().map(|stream| {
+            let group = stream.group_mut(&group)?;
+            Some(group.touch_consumer(&consumer, unix_millis()))
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(created)) => socket.write_all(&integer(created as i64)).await?,
+        Lookup::Found(None) => socket.write_all(&nogroup_err(&key, &group)).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn xgroup_delconsumer_cmd(
+    key: String,
+    group: Vec<u8>,
+    consumer: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        value.as_stream_mut().map(|stream| {
+            let group = stream.group_mut(&group)?;
+            Some(group.delete_consumer(&consumer).unwrap_or(0))
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(pending)) => socket.write_all(&integer(pending as i64)).await?,
+        Lookup::Found(None) => socket.write_all(&nogroup_err(&key, &group)).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use crate::model::{DB, Heap, Stream, StreamId, stream_entry_resp, unix_millis};
+use crate::util::{
+    Lookup, WRONGTYPE_ERR, array_len, bulk_bytes, bulk_str, integer, null_bulk, read_value,
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+const NO_KEY_ERR: &[u8] = b"-ERR no such key\r\n";
+
+pub async fn xinfo_stream_cmd(
+    key: String,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| value.as_stream().map(stream_info)).await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn xinfo_groups_cmd(
+    key: String,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| value.as_stream().map(groups_info)).await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+pub async fn xinfo_consumers_cmd(
+    key: String,
+    group: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_stream().map(|stream| {
+            let group = stream.group(&group)?;
+            let now = unix_millis();
+            let mut resp = array_len(group.consumers.len());
+            for (name, consumer) in &group.consumers {
+                let inactive = consumer
+                    .active_time
+                    .map(|t| now.saturating_sub(t) as i64)
+                    .unwrap_or(-1);
+                resp.extend_from_slice(&array_len(8));
+                resp.extend_from_slice(&bulk_str("name"));
+                resp.extend_from_slice(&bulk_bytes(name));
+                resp.extend_from_slice(&bulk_str("pending"));
+                resp.extend_from_slice(&integer(group.pending_for(name).count() as i64));
+                resp.extend_from_slice(&bulk_str("idle"));
+                resp.extend_from_slice(&integer(now.saturating_sub(consumer.seen_time) as i64));
+                resp.extend_from_slice(&bulk_str("inactive"));
+                resp.extend_from_slice(&integer(inactive));
+            }
+            Some(resp)
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(resp)) => socket.write_all(&resp).await?,
+        Lookup::Found(None) => {
+            let err = format!(
+                "-NOGROUP No such consumer group '{}' for key name '{}'\r\n",
+                String::from_utf8_lossy(&group),
+                key
+            );
+            socket.write_all(err.as_bytes()).await?
+        }
+        Lookup::Missing => socket.write_all(NO_KEY_ERR).await?,
+    }
+
+    Ok(())
+}
+
+fn stream_info(stream: &Stream) -> Vec<u8> {
+    let mut resp = array_len(16);
+    resp.extend_from_slice(&bulk_str("length"));
+    resp.extend_from_slice(&integer(stream.len() as i64));
+    resp.extend_from_slice(&bulk_str("last-generated-id"));
+    resp.extend_from_slice(&bulk_str(&stream.last_id().to_string()));
+    resp.extend_from_slice(&bulk_str("max-deleted-entry-id"));
+    resp.extend_from_slice(&bulk_str(&stream.max_deleted_id().to_string()));
+    resp.extend_from_slice(&bulk_str("entries-added"));
+    resp.extend_from_slice(&integer(stream.entries_added() as i64));
+    resp.extend_from_slice(&bulk_str("recorded-first-entry-id"));
+    let first = stream
+        .first_entry()
+        .map(|(id, _)| *id)
+        .unwrap_or(StreamId::MIN);
+    resp.extend_from_slice(&bulk_str(&first.to_string()));
+    resp.extend_from_slice(&bulk_str("groups"));
+    resp.extend_from_slice(&integer(stream.groups().len() as i64));
+    for (label, entry) in [
+        ("first-entry", stream.first_entry()),
+        ("last-entry", stream.last_entry()),
+    ] {
+        resp.extend_from_slice(&bulk_str(label));
+        match entry {
+            Some((id, fields)) => resp.extend_from_slice(&stream_entry_resp(id, Some(fields))),
+            None => resp.extend_from_slice(&null_bulk()),
+        }
+    }
+    resp
+}
+
+fn groups_info(stream: &Stream) -> Vec<u8> {
+    let mut resp = array_len(stream.groups().len());
+    for (name, group) in stream.groups() {
+        resp.extend_from_slice(&array_len(12));
+        resp.extend_from_slice(&bulk_str("name"));
+        resp.extend_from_slice(&bulk_bytes(name));
+        resp.extend_from_slice(&bulk_str("consumers"));
+        resp.extend_from_slice(&integer(group.consumers.len() as i64));
+        resp.extend_from_slice(&bulk_str("pending"));
+        resp.extend_from_slice(&integer(group.pending.len() as i64));
+        resp.extend_from_slice(&bulk_str("last-delivered-id"));
+        resp.extend_from_slice(&bulk_str(&group.last_id.to_string()));
+        resp.extend_from_slice(&bulk_str("entries-read"));
+        match group.entries_read {
+            Some(read) => resp.extend_from_slice(&integer(read as i64)),
+            None => resp.extend_from_slice(&null_bulk()),
+        }
+        resp.extend_from_slice(&bulk_str("lag"));
+        match stream.lag(group) {
+            Some(lag) => resp.extend_from_slice(&integer(lag as i64)),
+            None => resp.extend_from_slice(&null_bulk()),
+        }
+    }
+    resp
+}
@@ -0,0 +1,104 @@
+use crate::model::{ConsumerGroup, DB, Heap, StreamId, unix_millis};
+use crate::util::{
+    Lookup, WRONGTYPE_ERR, array_len, bulk_bytes, bulk_str, integer, nogroup_err, read_value,
+};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn xpending_cmd(
+    key: String,
+    group: Vec<u8>,
+    range: Option<(StreamId, StreamId, usize)>,
+    idle: Option<u64>,
+    consumer: Option<Vec<u8>>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_stream().map(|stream| {
+            let group = stream.group(&group)?;
+            Some(match range {
+                Some((start, end, count)) => {
+                    extended(group, start, end, count, idle, consumer.as_deref())
+                }
+                None => summary(group),
+            })
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(resp)) => socket.write_all(&resp).await?,
+        Lookup::Found(None) | Lookup::Missing => {
+            socket.write_all(&nogroup_err(&key, &group)).await?
+        }
+    }
+
+    Ok(())
+}
+
+/// `[count, smallest ID, greatest ID, [[consumer, count], ...]]`.
+fn summary(group: &ConsumerGroup) -> Vec<u8> {
+    let (Some((first, _)), Some((last, _))) = (
+        group.pending.first_key_value(),
+        group.pending.last_key_value(),
+    ) else {
+        return b"*4\r\n:0\r\n$-1\r\n$-1\r\n*-1\r\n".to_vec();
+    };
+
+    let mut per_consumer: BTreeMap<&[u8], usize> = BTreeMap::new();
+    for pending in group.pending.values() {
+        *per_consumer.entry(&pending.consumer).or_default() += 1;
+    }
+
+    let mut resp = array_len(4);
+    resp.extend_from_slice(&integer(group.pending.len() as i64));
+    resp.extend_from_slice(&bulk_str(&first.to_string()));
+    resp.extend_from_slice(&bulk_str(&last.to_string()));
+    resp.extend_from_slice(&array_len(per_consumer.len()));
+    for (name, count) in per_consumer {
+        resp.extend_from_slice(&array_len(2));
+        resp.extend_from_slice(&bulk_bytes(name));
+        resp.extend_from_slice(&bulk_str(&count.to_string()));
+    }
+    resp
+}
+
+/// `[[id, consumer, idle ms, delivery count], ...]` for pending entries in range.
+fn extended(
+    group: &ConsumerGroup,
+    start: StreamId,
+    end: StreamId,
+    count: usize,
+    idle: Option<u64>,
+    consumer: Option<&[u8]>,
+) -> Vec<u8> {
+    if start > end {
+        return b"*0\r\n".to_vec();
+    }
+
+    let now = unix_millis();
+    let rows: Vec<_> = group
+        .pending
+        .range(start..=end)
+        .filter(|(_, pending)| consumer.is_none_or(|c| pending.consumer == c))
+        .map(|(id, pending)| (id, pending, now.saturating_sub(pending.delivered_at)))
+        .filter(|(_, _, elapsed)| idle.is_none_or(|min| *elapsed >= min))
+        .take(count)
+        .collect();
+
+    let mut resp = array_len(rows.len());
+    for (id, pending, elapsed) in rows {
+        resp.extend_from_slice(&array_len(4));
+        resp.extend_from_slice(&bulk_str(&id.to_string()));
+        resp.extend_from_slice(&bulk_bytes(&pending.consumer));
+        resp.extend_from_slice(&integer(elapsed as i64));
+        resp.extend_from_slice(&integer(pending.delivery_count as i64));
+    }
+    resp
+}
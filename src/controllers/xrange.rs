@@ -0,0 +1,61 @@
+use crate::model::{DB, Heap, StreamId, stream_entry_resp};
+use crate::util::{Lookup, WRONGTYPE_ERR, array_len, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn xrange_cmd(
+    key: String,
+    start: StreamId,
+    end: StreamId,
+    count: Option<usize>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    range_cmd(key, start, end, count, false, _db, _heap, socket).await
+}
+
+pub async fn xrevrange_cmd(
+    key: String,
+    start: StreamId,
+    end: StreamId,
+    count: Option<usize>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    range_cmd(key, start, end, count, true, _db, _heap, socket).await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn range_cmd(
+    key: String,
+    start: StreamId,
+    end: StreamId,
+    count: Option<usize>,
+    rev: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_stream().map(|stream| {
+            let entries = stream.range(start, end, rev, count);
+            let mut resp = array_len(entries.len());
+            for (id, fields) in entries {
+                resp.extend_from_slice(&stream_entry_resp(id, Some(fields)));
+            }
+            resp
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(b"*0\r\n").await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use crate::{
+    blocking::{BlockingManager, WaitOutcome},
+    model::{DB, Entry, Heap, Stream, StreamId, StreamReadId, stream_entry_resp},
+    util::{WRONGTYPE_ERR, array_len, bulk_str, is_expired, null_array},
+};
+use anyhow::Result;
+use std::collections::HashMap;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::{Duration, Instant};
+
+#[allow(clippy::too_many_arguments)]
+pub async fn xread_cmd(
+    keys: Vec<String>,
+    ids: Vec<StreamReadId>,
+    count: Option<usize>,
+    block: Option<u64>,
+    _db: &DB,
+    _heap: &mut Heap,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    // `$` and `+` are resolved once up front so later wake-ups only see
+    // entries added after the call.
+    let mut after = Vec::with_capacity(keys.len());
+    {
+        let db = _db.read().await;
+        for (key, id) in keys.iter().zip(&ids) {
+            let Ok(stream) = live_stream(&db, key) else {
+                drop(db);
+                socket.write_all(WRONGTYPE_ERR).await?;
+                return Ok(());
+            };
+            let last_id = stream.map(|s| s.last_id()).unwrap_or(StreamId::MIN);
+            after.push(match id {
+                StreamReadId::After(id) => *id,
+                StreamReadId::LastEntry => stream
+                    .and_then(|s| s.last_entry())
+                    .and_then(|(id, _)| id.prev())
+                    .unwrap_or(last_id),
+                _ => last_id,
+            });
+        }
+    }
+
+    let deadline = block
+        .filter(|ms| *ms > 0)
+        .map(|ms| Instant::now() + Duration::from_millis(ms));
+
+    loop {
+        let waiter = block.map(|_| blocking.register(&keys));
+
+        let reply = {
+            let db = _db.read().await;
+            read_streams(&db, &keys, &after, count)
+        };
+        match reply {
+            Err(()) => {
+                socket.write_all(WRONGTYPE_ERR).await?;
+                return Ok(());
+            }
+            Ok(Some(resp)) => {
+                socket.write_all(&resp).await?;
+                return Ok(());
+            }
+            Ok(None) => {}
+        }
+
+        let Some(waiter) = waiter else {
+            socket.write_all(&null_array()).await?;
+            return Ok(());
+        };
+        match waiter.wait(deadline, socket).await {
+            WaitOutcome::Woken => continue,
+            WaitOutcome::TimedOut => {
+                socket.write_all(&null_array()).await?;
+                return Ok(());
+            }
+            WaitOutcome::Disconnected => return Ok(()),
+        }
+    }
+}
+
+/// Returns the stream at `key`, `Ok(None)` if absent, or `Err` for another type.
+fn live_stream<'a>(db: &'a HashMap<String, Entry>, key: &str) -> Result<Option<&'a Stream>, ()> {
+    match db.get(key) {
+        Some(entry) if !is_expired(entry) => entry.value.as_stream().map(Some).ok_or(()),
+        _ => Ok(None),
+    }
+}
+
+/// Builds the XREAD reply, or `None` when no stream has new entries.
+fn read_streams(
+    db: &HashMap<String, Entry>,
+    keys: &[String],
+    after: &[StreamId],
+    count: Option<usize>,
+) -> Result<Option<Vec<u8>>, ()> {
+    let mut found = 0;
+    let mut body = Vec::new();
+    for (key, after) in keys.iter().zip(after) {
+        let Some(stream) = live_stream(db, key)? else {
+            continue;
+        };
+        let Some(start) = after.next() else {
+            continue;
+        };
+        let entries = stream.range(start, StreamId::MAX, false, count);
+        if entries.is_empty() {
+            continue;
+        }
+
+        found += 1;
+        body.extend_from_slice(&array_len(2));
+        body.extend_from_slice(&bulk_str(key));
+        body.extend_from_slice(&array_len(entries.len()));
+        for (id, fields) in entries {
+            body.extend_from_slice(&stream_entry_resp(id, Some(fields)));
+        }
+    }
+
+    if found == 0 {
+        return Ok(None);
+    }
+    let mut resp = array_len(found);
+    resp.extend_from_slice(&body);
+    Ok(Some(resp))
+}
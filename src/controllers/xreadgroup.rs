@@ -0,0 +1,216 @@
+use crate::{
+    blocking::{BlockingManager, WaitOutcome},
+    lru::{estimate_entry_bytes, LruManager},
+    model::{
+        DB, Entry, Heap, Keyspace, PendingEntry, StreamId, StreamReadId, stream_entry_resp,
+        unix_millis,
+    },
+    util::{WRONGTYPE_ERR, array_len, bulk_str, is_expired, null_array},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::{Duration, Instant};
+
+#[allow(clippy::too_many_arguments)]
+pub async fn xreadgroup_cmd(
+    group: Vec<u8>,
+    consumer: Vec<u8>,
+    keys: Vec<String>,
+    ids: Vec<StreamReadId>,
+    count: Option<usize>,
+    block: Option<u64>,
+    noack: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    blocking: &BlockingManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let deadline = block
+        .filter(|ms| *ms > 0)
+        .map(|ms| Instant::now() + Duration::from_millis(ms));
+
+    loop {
+        let waiter = block.map(|_| blocking.register(&keys));
+
+        let mut delta = 0isize;
+        let reply = {
+            let mut db = _db.write().await;
+            let read = GroupRead {
+                group: &group,
+                consumer: &consumer,
+                count,
+                noack,
+            };
+            read.run(&mut db, &keys, &ids, &mut delta)
+        };
+        if delta != 0 {
+            lru.adjust_used_bytes(delta);
+        }
+
+        match reply {
+            Err(err) => {
+                socket.write_all(&err).await?;
+                return Ok(());
+            }
+            Ok(Some(resp)) => {
+                socket.write_all(&resp).await?;
+                return Ok(());
+            }
+            Ok(None) => {}
+        }
+
+        let Some(waiter) = waiter else {
+            socket.write_all(&null_array()).await?;
+            return Ok(());
+        };
+        match waiter.wait(deadline, socket).await {
+            WaitOutcome::Woken => continue,
+            WaitOutcome::TimedOut => {
+                socket.write_all(&null_array()).await?;
+                return Ok(());
+            }
+            WaitOutcome::Disconnected => return Ok(()),
+        }
+    }
+}
+
+struct GroupRead<'a> {
+    group: &'a [u8],
+    consumer: &'a [u8],
+    count: Option<usize>,
+    noack: bool,
+}
+
+impl GroupRead<'_> {
+    /// Serves every stream once. Returns `None` when only `>` IDs were asked
+    /// for and none of them had new entries, so the caller may block.
+    fn run(
+        &self,
+        db: &mut Keyspace,
+        keys: &[String],
+        ids: &[StreamReadId],
+        delta: &mut isize,
+    ) -> Result<Option<Vec<u8>>, Vec<u8>> {
+        for key in keys {
+            match db.get(key) {
+                Some(entry) if !is_expired(entry) => match entry.value.as_stream() {
+                    Some(stream) if stream.group(self.group).is_some() => {}
+                    Some(_) => return Err(self.nogroup(key)),
+                    None => return Err(WRONGTYPE_ERR.to_vec()),
+                },
+                _ => return Err(self.nogroup(key)),
+            }
+        }
+
+        let now = unix_millis();
+        let mut found = 0;
+        let mut body = Vec::new();
+        for (key, id) in keys.iter().zip(ids) {
+            let Some(entry) = db.get_mut(key) else {
+                continue;
+            };
+            let old_size = estimate_entry_bytes(key, entry);
+            let entries = match id {
+                StreamReadId::After(after) => Some(self.history(entry, *after, now)),
+                _ => self.undelivered(entry, now),
+            };
+            *delta += estimate_entry_bytes(key, entry) as isize - old_size as isize;
+
+            if let Some((len, entries)) = entries {
+                found += 1;
+                body.extend_from_slice(&array_len(2));
+                body.extend_from_slice(&bulk_str(key));
+                body.extend_from_slice(&array_len(len));
+                body.extend_from_slice(&entries);
+            }
+        }
+
+        if found == 0 {
+            return Ok(None);
+        }
+        let mut resp = array_len(found);
+        resp.extend_from_slice(&body);
+        Ok(Some(resp))
+    }
+
+    /// Delivers entries past the group's last ID and adds them to the PEL.
+    fn undelivered(&self, entry: &mut Entry, now: u64) -> Option<(usize, Vec<u8>)> {
+        let stream = entry.value.as_stream_mut()?;
+        let start = stream.group(self.group)?.last_id.next()?;
+
+        let mut resp = Vec::new();
+        let mut delivered: Vec<StreamId> = Vec::new();
+        for (id, fields) in stream.range(start, StreamId::MAX, false, self.count) {
+            resp.extend_from_slice(&stream_entry_resp(id, Some(fields)));
+            delivered.push(*id);
+        }
+
+        let entries_read = delivered.last().map(|last| stream.entries_read_at(*last));
+        let group = stream.group_mut(self.group)?;
+        group.touch_consumer(self.consumer, now);
+        let (Some(last), Some(entries_read)) = (delivered.last(), entries_read) else {
+            return None;
+        };
+
+        group.last_id = *last;
+        group.entries_read = entries_read;
+        if let Some(consumer) = group.consumers.get_mut(self.consumer) {
+            consumer.active_time = Some(now);
+        }
+        if !self.noack {
+            for id in &delivered {
+                group.pending.insert(
+                    *id,
+                    PendingEntry {
+                        consumer: self.consumer.to_vec(),
+                        delivered_at: now,
+                        delivery_count: 1,
+                    },
+                );
+            }
+        }
+
+        Some((delivered.len(), resp))
+    }
+
+    /// Re-delivers this consumer's pending entries with IDs after `after`.
+    fn history(&self, entry: &mut Entry, after: StreamId, now: u64) -> (usize, Vec<u8>) {
+        let Some(stream) = entry.value.as_stream_mut() else {
+            return (0, Vec::new());
+        };
+        let Some(group) = stream.group_mut(self.group) else {
+            return (0, Vec::new());
+        };
+        group.touch_consumer(self.consumer, now);
+
+        let ids: Vec<StreamId> = group
+            .pending_for(self.consumer)
+            .map(|(id, _)| *id)
+            .filter(|id| *id > after)
+            .take(self.count.unwrap_or(usize::MAX))
+            .collect();
+        for id in &ids {
+            if let Some(pending) = group.pending.get_mut(id) {
+                pending.delivered_at = now;
+                pending.delivery_count += 1;
+            }
+        }
+
+        let mut resp = Vec::new();
+        for id in &ids {
+            resp.extend_from_slice(&stream_entry_resp(id, stream.get(id)));
+        }
+        (ids.len(), resp)
+    }
+
+    fn nogroup(&self, key: &str) -> Vec<u8> {
+        format!(
+            "-NOGROUP No such key '{}' or consumer group '{}' in XREADGROUP with GROUP option\r\n",
+            key,
+            String::from_utf8_lossy(self.group)
+        )
+        .into_bytes()
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{
+    lru::LruManager,
+    model::{DB, Heap, StreamTrim},
+    util::{Lookup, WRONGTYPE_ERR, integer, update_value},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn xtrim_cmd(
+    key: String,
+    trim: StreamTrim,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = update_value(_db, _heap, lru, &key, |value| {
+        value.as_stream_mut().map(|stream| stream.trim(&trim).len())
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(trimmed) => socket.write_all(&integer(trimmed as i64)).await?,
+        Lookup::Missing => socket.write_all(b":0\r\n").await?,
+    }
+
+    Ok(())
+}
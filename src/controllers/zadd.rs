@@ -0,0 +1,134 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Value, ZAddFlags, ZSet},
+    util::{OOM_ERR, WRONGTYPE_ERR, bulk_str, drop_expired, format_float, integer, null_bulk},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn zadd_cmd(
+    key: String,
+    flags: ZAddFlags,
+    pairs: Vec<(f64, Vec<u8>)>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    if flags.xx && !db.contains_key(&key) {
+        drop(db);
+        let resp = if flags.incr { null_bulk() } else { integer(0) };
+        socket.write_all(&resp).await?;
+        return Ok(());
+    }
+
+    let created_new = !db.contains_key(&key);
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::ZSet(ZSet::new()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(zset) = entry.value.as_zset_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let mut added = 0;
+    let mut changed = 0;
+    let mut last_score: Option<f64> = None;
+    let mut previous: Vec<(Vec<u8>, Option<f64>)> = Vec::new();
+
+    for (score, member) in pairs {
+        let old = zset.score(&member);
+        let next = match old {
+            Some(_) if flags.nx => continue,
+            None if flags.xx => continue,
+            Some(old) if flags.incr => old + score,
+            _ => score,
+        };
+
+        if next.is_nan() {
+            if created_new && zset.is_empty() {
+                db.remove(&key);
+            }
+            drop(db);
+            socket
+                .write_all(b"-ERR resulting score is not a number (NaN)\r\n")
+                .await?;
+            return Ok(());
+        }
+
+        if let Some(old) = old {
+            if (flags.gt && next <= old) || (flags.lt && next >= old) {
+                continue;
+            }
+            if next != old {
+                zset.insert(member.clone(), next);
+                previous.push((member, Some(old)));
+                changed += 1;
+            }
+        } else {
+            zset.insert(member.clone(), next);
+            previous.push((member, None));
+            added += 1;
+        }
+        last_score = Some(next);
+    }
+
+    if created_new && zset.is_empty() {
+        db.remove(&key);
+        drop(db);
+        let resp = if flags.incr { null_bulk() } else { integer(0) };
+        socket.write_all(&resp).await?;
+        return Ok(());
+    }
+
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(zset) = db.get_mut(&key).and_then(|e| e.value.as_zset_mut()) {
+            for (member, old) in previous.into_iter().rev() {
+                match old {
+                    Some(score) => zset.insert(member, score),
+                    None => zset.remove(&member),
+                };
+            }
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    let resp = if flags.incr {
+        match last_score {
+            Some(score) => bulk_str(&format_float(score)),
+            None => null_bulk(),
+        }
+    } else if flags.ch {
+        integer(added + changed)
+    } else {
+        integer(added)
+    };
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
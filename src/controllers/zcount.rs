@@ -0,0 +1,29 @@
+use crate::model::{DB, Heap, ScoreBound};
+use crate::util::{Lookup, WRONGTYPE_ERR, integer, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn zcount_cmd(
+    key: String,
+    min: ScoreBound,
+    max: ScoreBound,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value
+            .as_zset()
+            .map(|zset| zset.count_in_score_range(&min, &max))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(count) => socket.write_all(&integer(count as i64)).await?,
+        Lookup::Missing => socket.write_all(b":0\r\n").await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Value, ZSet},
+    util::{OOM_ERR, WRONGTYPE_ERR, bulk_str, drop_expired, format_float},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn zincrby_cmd(
+    key: String,
+    increment: f64,
+    member: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let freed = drop_expired(&mut db, &key);
+    let created_new = !db.contains_key(&key);
+
+    let entry = db.get_or_insert_with(key.clone(), || Entry {
+        value: Value::ZSet(ZSet::new()),
+        expires_at: None,
+    });
+    let old_size = if created_new {
+        0
+    } else {
+        estimate_entry_bytes(&key, entry)
+    };
+
+    let Some(zset) = entry.value.as_zset_mut() else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let previous = zset.score(&member);
+    let next = previous.unwrap_or(0.0) + increment;
+    if next.is_nan() {
+        drop(db);
+        socket
+            .write_all(b"-ERR resulting score is not a number (NaN)\r\n")
+            .await?;
+        return Ok(());
+    }
+
+    zset.insert(member.clone(), next);
+    let new_size = estimate_entry_bytes(&key, entry);
+    drop(db);
+
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        if created_new {
+            db.remove(&key);
+        } else if let Some(zset) = db.get_mut(&key).and_then(|e| e.value.as_zset_mut()) {
+            match previous {
+                Some(score) => zset.insert(member, score),
+                None => zset.remove(&member),
+            };
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&bulk_str(&format_float(next))).await?;
+
+    Ok(())
+}
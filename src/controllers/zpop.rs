@@ -0,0 +1,108 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap, MinHeap},
+    util::{WRONGTYPE_ERR, array_len, bulk_bytes, bulk_str, format_float, is_expired},
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn zpopmin_cmd(
+    key: String,
+    count: Option<usize>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    pop_cmd(key, count, false, _db, _heap, lru, socket).await
+}
+
+pub async fn zpopmax_cmd(
+    key: String,
+    count: Option<usize>,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    pop_cmd(key, count, true, _db, _heap, lru, socket).await
+}
+
+async fn pop_cmd(
+    key: String,
+    count: Option<usize>,
+    max: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut expired_at = None;
+    let mut popped: Vec<(Vec<u8>, f64)> = Vec::new();
+    let mut old_size = 0usize;
+    let mut new_size = 0usize;
+    let mut remove_key = false;
+    let mut wrong_type = false;
+
+    {
+        let mut db = _db.write().await;
+        if let Some(entry) = db.get_mut(&key) {
+            if is_expired(entry) {
+                expired_at = entry.expires_at;
+            } else {
+                old_size = estimate_entry_bytes(&key, entry);
+                match entry.value.as_zset_mut() {
+                    Some(zset) => {
+                        for _ in 0..count.unwrap_or(1) {
+                            let item = if max { zset.pop_max() } else { zset.pop_min() };
+                            match item {
+                                Some(item) => popped.push(item),
+                                None => break,
+                            }
+                        }
+                        if zset.is_empty() {
+                            remove_key = true;
+                        } else {
+                            new_size = estimate_entry_bytes(&key, entry);
+                        }
+                    }
+                    None => wrong_type = true,
+                }
+
+                if remove_key {
+                    db.remove(&key);
+                }
+            }
+        }
+    }
+
+    if wrong_type {
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    }
+
+    if let Some(expires_at) = expired_at {
+        let mut heap = _heap.lock().await;
+        heap.push(MinHeap { key, expires_at });
+        socket.write_all(b"*0\r\n").await?;
+        return Ok(());
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if delta != 0 {
+        lru.adjust_used_bytes(delta);
+    }
+    if remove_key {
+        lru.remove_key(_db, &key).await;
+    }
+
+    let mut resp = array_len(popped.len() * 2);
+    for (member, score) in popped {
+        resp.extend_from_slice(&bulk_bytes(&member));
+        resp.extend_from_slice(&bulk_str(&format_float(score)));
+    }
+    socket.write_all(&resp).await?;
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Heap, Value, ZRangeSpec, ZSet},
+    util::{
+        Lookup, OOM_ERR, WRONGTYPE_ERR, array_len, bulk_bytes, bulk_str, format_float, integer,
+        is_expired, read_value,
+    },
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn zrange_cmd(
+    key: String,
+    spec: ZRangeSpec,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_zset().map(|zset| {
+            let items = zset.range(&spec);
+            let per_item = if spec.with_scores { 2 } else { 1 };
+            let mut resp = array_len(items.len() * per_item);
+            for (member, score) in items {
+                resp.extend_from_slice(&bulk_bytes(member));
+                if spec.with_scores {
+                    resp.extend_from_slice(&bulk_str(&format_float(score)));
+                }
+            }
+            resp
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(resp) => socket.write_all(&resp).await?,
+        Lookup::Missing => socket.write_all(b"*0\r\n").await?,
+    }
+
+    Ok(())
+}
+
+pub async fn zrangestore_cmd(
+    destination: String,
+    source: String,
+    spec: ZRangeSpec,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+
+    let mut result = ZSet::new();
+    match db.get(&source) {
+        Some(entry) if !is_expired(entry) => match entry.value.as_zset() {
+            Some(zset) => {
+                for (member, score) in zset.range(&spec) {
+                    result.insert(member.clone(), score);
+                }
+            }
+            None => {
+                drop(db);
+                socket.write_all(WRONGTYPE_ERR).await?;
+                return Ok(());
+            }
+        },
+        _ => {}
+    }
+
+    let len = result.len();
+    let old = db.remove(&destination);
+    let old_size = old
+        .as_ref()
+        .map(|entry| estimate_entry_bytes(&destination, entry))
+        .unwrap_or(0);
+
+    let mut new_size = 0usize;
+    if len > 0 {
+        let new_entry = Entry {
+            value: Value::ZSet(result),
+            expires_at: None,
+        };
+        new_size = estimate_entry_bytes(&destination, &new_entry);
+        db.insert(destination.clone(), new_entry);
+    }
+    drop(db);
+
+    if len == 0 && old.is_some() {
+        lru.remove_key(_db, &destination).await;
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        match old {
+            Some(old_entry) => {
+                db.insert(destination.clone(), old_entry);
+            }
+            None => {
+                db.remove(&destination);
+            }
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(len as i64)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::model::{DB, Heap};
+use crate::util::{
+    Lookup, WRONGTYPE_ERR, array_len, bulk_str, format_float, integer, null_bulk, read_value,
+};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn zrank_cmd(
+    key: String,
+    member: Vec<u8>,
+    with_score: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    rank_cmd(key, member, with_score, false, _db, _heap, socket).await
+}
+
+pub async fn zrevrank_cmd(
+    key: String,
+    member: Vec<u8>,
+    with_score: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    rank_cmd(key, member, with_score, true, _db, _heap, socket).await
+}
+
+async fn rank_cmd(
+    key: String,
+    member: Vec<u8>,
+    with_score: bool,
+    rev: bool,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_zset().map(|zset| {
+            let rank = zset.rank(&member, rev)?;
+            Some((rank, zset.score(&member)?))
+        })
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some((rank, score))) => {
+            if with_score {
+                let mut resp = array_len(2);
+                resp.extend_from_slice(&integer(rank as i64));
+                resp.extend_from_slice(&bulk_str(&format_float(score)));
+                socket.write_all(&resp).await?
+            } else {
+                socket.write_all(&integer(rank as i64)).await?
+            }
+        }
+        Lookup::Found(None) | Lookup::Missing => socket.write_all(&null_bulk()).await?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::model::{DB, Heap};
+use crate::util::{Lookup, WRONGTYPE_ERR, bulk_str, format_float, null_bulk, read_value};
+use anyhow::Result;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+pub async fn zscore_cmd(
+    key: String,
+    member: Vec<u8>,
+    _db: &DB,
+    _heap: &mut Heap,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let lookup = read_value(_db, _heap, &key, |value| {
+        value.as_zset().map(|zset| zset.score(&member))
+    })
+    .await;
+
+    match lookup {
+        Lookup::WrongType => socket.write_all(WRONGTYPE_ERR).await?,
+        Lookup::Found(Some(score)) => socket.write_all(&bulk_str(&format_float(score))).await?,
+        Lookup::Found(None) | Lookup::Missing => socket.write_all(&null_bulk()).await?,
+    }
+
+    Ok(())
+}
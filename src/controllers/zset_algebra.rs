@@ -0,0 +1,188 @@
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{Aggregate, DB, Entry, Heap, Value, ZSet},
+    util::{OOM_ERR, WRONGTYPE_ERR, integer, is_expired},
+};
+use anyhow::Result;
+use std::collections::HashMap;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn zunionstore_cmd(
+    destination: String,
+    keys: Vec<String>,
+    weights: Vec<f64>,
+    aggregate: Aggregate,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    store_cmd(
+        false,
+        destination,
+        keys,
+        weights,
+        aggregate,
+        _db,
+        _heap,
+        lru,
+        socket,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn zinterstore_cmd(
+    destination: String,
+    keys: Vec<String>,
+    weights: Vec<f64>,
+    aggregate: Aggregate,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    store_cmd(
+        true,
+        destination,
+        keys,
+        weights,
+        aggregate,
+        _db,
+        _heap,
+        lru,
+        socket,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn store_cmd(
+    intersect: bool,
+    destination: String,
+    keys: Vec<String>,
+    weights: Vec<f64>,
+    aggregate: Aggregate,
+    _db: &DB,
+    _heap: &mut Heap,
+    lru: &LruManager,
+    socket: &mut TcpStream,
+) -> Result<()> {
+    let mut db = _db.write().await;
+    let Some(result) = combine(&db, &keys, &weights, aggregate, intersect) else {
+        drop(db);
+        socket.write_all(WRONGTYPE_ERR).await?;
+        return Ok(());
+    };
+
+    let len = result.len();
+    let old = db.remove(&destination);
+    let old_size = old
+        .as_ref()
+        .map(|entry| estimate_entry_bytes(&destination, entry))
+        .unwrap_or(0);
+
+    let mut new_size = 0usize;
+    if len > 0 {
+        let new_entry = Entry {
+            value: Value::ZSet(result),
+            expires_at: None,
+        };
+        new_size = estimate_entry_bytes(&destination, &new_entry);
+        db.insert(destination.clone(), new_entry);
+    }
+    drop(db);
+
+    if len == 0 && old.is_some() {
+        lru.remove_key(_db, &destination).await;
+    }
+
+    let delta = new_size as isize - old_size as isize;
+    if !lru.try_reserve(delta).await? {
+        let mut db = _db.write().await;
+        match old {
+            Some(old_entry) => {
+                db.insert(destination.clone(), old_entry);
+            }
+            None => {
+                db.remove(&destination);
+            }
+        }
+        drop(db);
+        lru.adjust_used_bytes(-delta);
+        socket.write_all(OOM_ERR).await?;
+        return Ok(());
+    }
+
+    socket.write_all(&integer(len as i64)).await?;
+
+    Ok(())
+}
+
+/// Reads every source as `(member, score)` pairs; plain sets count as score 1.
+/// Returns `None` if any source holds another type.
+fn combine(
+    db: &HashMap<String, Entry>,
+    keys: &[String],
+    weights: &[f64],
+    aggregate: Aggregate,
+    intersect: bool,
+) -> Option<ZSet> {
+    let mut sources: Vec<Option<HashMap<&Vec<u8>, f64>>> = Vec::with_capacity(keys.len());
+    for key in keys {
+        let source = match db.get(key) {
+            Some(entry) if !is_expired(entry) => match &entry.value {
+                Value::ZSet(zset) => Some(zset.iter().collect()),
+                Value::Set(set) => Some(set.iter().map(|m| (m, 1.0)).collect()),
+                _ => return None,
+            },
+            _ => None,
+        };
+        sources.push(source);
+    }
+
+    let weighted = |score: f64, weight: f64| {
+        let v = score * weight;
+        if v.is_nan() { 0.0 } else { v }
+    };
+
+    let mut result = ZSet::new();
+    if intersect {
+        let Some(sources) = sources.into_iter().collect::<Option<Vec<_>>>() else {
+            return Some(result);
+        };
+        let Some((first, rest)) = sources.split_first() else {
+            return Some(result);
+        };
+        'members: for (member, score) in first {
+            let mut acc = weighted(*score, weights[0]);
+            for (i, source) in rest.iter().enumerate() {
+                match source.get(member) {
+                    Some(s) => acc = aggregate.apply(acc, weighted(*s, weights[i + 1])),
+                    None => continue 'members,
+                }
+            }
+            result.insert((*member).clone(), acc);
+        }
+    } else {
+        let mut acc: HashMap<&Vec<u8>, f64> = HashMap::new();
+        for (i, source) in sources.iter().enumerate() {
+            let Some(source) = source else {
+                continue;
+            };
+            for (member, score) in source {
+                let score = weighted(*score, weights[i]);
+                acc.entry(member)
+                    .and_modify(|v| *v = aggregate.apply(*v, score))
+                    .or_insert(score);
+            }
+        }
+        for (member, score) in acc {
+            result.insert(member.clone(), score);
+        }
+    }
+
+    Some(result)
+}
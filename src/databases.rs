@@ -0,0 +1,63 @@
+use crate::{
+    blocking::BlockingManager,
+    model::{DB, Heap},
+};
+use std::sync::Arc;
+
+/// One logical database: its keyspace, the expiry heap the background
+/// cleaner drains for it, and the clients blocked on its keys.
+#[derive(Clone, Default)]
+pub struct Database {
+    pub db: DB,
+    pub heap: Heap,
+    pub blocking: BlockingManager,
+}
+
+/// The numbered databases picked with SELECT, fixed in number at startup
+/// and shared by every connection.
+#[derive(Clone)]
+pub struct Databases {
+    inner: Arc<Vec<Database>>,
+}
+
+impl Databases {
+    pub fn new(count: usize) -> Self {
+        Self {
+            inner: Arc::new((0..count.max(1)).map(|_| Database::default()).collect()),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Database> {
+        self.inner.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Database> {
+        self.inner.iter()
+    }
+
+    /// Exchanges the contents of two databases. Connections keep their
+    /// selected index, so they see the other keyspace from here on; clients
+    /// blocked on either are woken to re-check their keys.
+    pub async fn swap(&self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        let (first, second) = (&self.inner[a.min(b)], &self.inner[a.max(b)]);
+
+        // Heaps before keyspaces, the order the background cleaner takes them.
+        let mut first_heap = first.heap.lock().await;
+        let mut second_heap = second.heap.lock().await;
+        let mut first_db = first.db.write().await;
+        let mut second_db = second.db.write().await;
+        std::mem::swap(&mut *first_heap, &mut *second_heap);
+        std::mem::swap(&mut *first_db, &mut *second_db);
+        drop((first_db, second_db, first_heap, second_heap));
+
+        first.blocking.wake_everyone();
+        second.blocking.wake_everyone();
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
     controllers,
+    databases::{Database, Databases},
     lru::LruManager,
-    model::{Command, DB, Heap, RESP},
+    model::{Command, RESP},
     parser::{parse_command, parse_resp},
 };
 use anyhow::Result;
@@ -10,13 +11,19 @@ use tokio::net::TcpStream;
 
 pub async fn process_client(
     mut socket: TcpStream,
-    mut _db: DB,
-    mut _heap: Heap,
+    databases: Databases,
     lru: LruManager,
 ) -> Result<()> {
+    let mut selected = 0usize;
+    let Database {
+        db: mut _db,
+        heap: mut _heap,
+        mut blocking,
+    } = databases.get(selected).cloned().unwrap();
+
     let mut read_buf: Vec<u8> = Vec::new();
     let mut tmp = [0u8; 4096];
-    let mut access_buffer: Vec<String> = Vec::new();
+    let mut access_buffer: Vec<(usize, String)> = Vec::new();
 
     loop {
         let n = socket.read(&mut tmp).await?;
@@ -84,16 +91,21 @@ pub async fn process_client(
                     socket.write_all(b"+OK\r\n").await?;
                     break;
                 }
-                Command::SET { key, value } => {
-                    lru.record_access(&mut access_buffer, &key);
-                    controllers::set_cmd(key, value, &_db, &mut _heap, &lru, &mut socket).await?
+                Command::SET {
+                    key,
+                    value,
+                    options,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::set_cmd(key, value, options, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
                 }
                 Command::SETEX {
                     key,
                     value,
                     seconds,
                 } => {
-                    lru.record_access(&mut access_buffer, &key);
+                    lru.record_access(&mut access_buffer, selected, &key);
                     controllers::setex_cmd(key, value, seconds, &_db, &mut _heap, &lru, &mut socket)
                         .await?
                 }
@@ -102,7 +114,7 @@ pub async fn process_client(
                     value,
                     seconds,
                 } => {
-                    lru.record_access(&mut access_buffer, &key);
+                    lru.record_access(&mut access_buffer, selected, &key);
                     controllers::psetex_cmd(
                         key,
                         value,
@@ -115,68 +127,1641 @@ pub async fn process_client(
                     .await?
                 }
                 Command::GET { key } => {
-                    lru.record_access(&mut access_buffer, &key);
+                    lru.record_access(&mut access_buffer, selected, &key);
                     controllers::get_cmd(key, &_db, &mut _heap, &mut socket).await?
                 }
-                Command::DEL { keys } => {
+                Command::INCR { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::incr_cmd(key, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::DECR { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::decr_cmd(key, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::INCRBY { key, increment } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::incrby_cmd(key, increment, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::DECRBY { key, decrement } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::decrby_cmd(key, decrement, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::INCRBYFLOAT { key, increment } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::incrbyfloat_cmd(
+                        key,
+                        increment,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::APPEND { key, value } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::append_cmd(key, value, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::STRLEN { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::strlen_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::GETRANGE { key, start, end } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::getrange_cmd(key, start, end, &_db, &mut _heap, &mut socket)
+                        .await?
+                }
+                Command::SETRANGE { key, offset, value } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::setrange_cmd(
+                        key,
+                        offset,
+                        value,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::GETDEL { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::getdel_cmd(key, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::GETEX {
+                    key,
+                    expiry,
+                    persist,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::getex_cmd(key, expiry, persist, &_db, &mut _heap, &mut socket)
+                        .await?
+                }
+                Command::GETSET { key, value } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::getset_cmd(key, value, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::SETNX { key, value } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::setnx_cmd(key, value, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::MGET { keys } => {
                     for key in &keys {
-                        lru.record_access(&mut access_buffer, key);
+                        lru.record_access(&mut access_buffer, selected, key);
                     }
-                    controllers::del_cmd(keys, &_db, &lru, &mut socket).await?
+                    controllers::mget_cmd(keys, &_db, &mut _heap, &mut socket).await?
                 }
-                Command::EXISTS { keys } => {
+                Command::MSET { pairs } => {
+                    for (key, _) in &pairs {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::mset_cmd(pairs, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::MSETNX { pairs } => {
+                    for (key, _) in &pairs {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::msetnx_cmd(pairs, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::SETBIT { key, offset, value } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::setbit_cmd(key, offset, value, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::GETBIT { key, offset } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::getbit_cmd(key, offset, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::BITCOUNT {
+                    key,
+                    range,
+                    bit_unit,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::bitcount_cmd(key, range, bit_unit, &_db, &mut _heap, &mut socket)
+                        .await?
+                }
+                Command::BITPOS {
+                    key,
+                    bit,
+                    start,
+                    end,
+                    bit_unit,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::bitpos_cmd(
+                        key,
+                        bit,
+                        start,
+                        end,
+                        bit_unit,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::BITOP {
+                    op,
+                    destination,
+                    keys,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &destination);
                     for key in &keys {
-                        lru.record_access(&mut access_buffer, key);
+                        lru.record_access(&mut access_buffer, selected, key);
                     }
-                    controllers::exists_cmd(keys, &_db, &mut _heap, &mut socket).await?
+                    controllers::bitop_cmd(
+                        op,
+                        destination,
+                        keys,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
                 }
-                Command::EXPIRE { key, seconds } => {
-                    lru.record_access(&mut access_buffer, &key);
-                    controllers::expire_cmd(key, seconds, &_db, &mut _heap, &mut socket).await?
+                Command::BITFIELD { key, ops } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::bitfield_cmd(key, ops, &_db, &mut _heap, &lru, &mut socket).await?
                 }
-                Command::PERSIST { key } => {
-                    lru.record_access(&mut access_buffer, &key);
-                    controllers::persist_cmd(key, &_db, &mut _heap, &mut socket).await?
+                Command::BITFIELD_RO { key, gets } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::bitfield_ro_cmd(key, gets, &_db, &mut _heap, &mut socket).await?
                 }
-                Command::TTL { key } => {
-                    lru.record_access(&mut access_buffer, &key);
-                    controllers::ttl_cmd(key, &_db, &mut _heap, &mut socket).await?
+                Command::PFADD { key, elements } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::pfadd_cmd(key, elements, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
                 }
-                Command::PTTL { key } => {
-                    lru.record_access(&mut access_buffer, &key);
-                    controllers::pttl_cmd(key, &_db, &mut _heap, &mut socket).await?
+                Command::PFCOUNT { keys } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::pfcount_cmd(keys, &_db, &mut _heap, &lru, &mut socket).await?
                 }
-                Command::TYPE { key } => {
-                    lru.record_access(&mut access_buffer, &key);
-                    controllers::type_cmd(key, &_db, &mut _heap, &mut socket).await?
+                Command::PFMERGE { destination, keys } => {
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::pfmerge_cmd(destination, keys, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
                 }
-                Command::INFO { section } => {
-                    controllers::info_cmd(section, &_db, &lru, &mut socket).await?
+                Command::GEOADD { key, flags, points } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::geoadd_cmd(key, flags, points, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
                 }
-                Command::ConfigGet { pattern } => {
-                    controllers::config_get_cmd(pattern, &lru, &mut socket).await?
+                Command::GEOPOS { key, members } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::geopos_cmd(key, members, &_db, &mut _heap, &mut socket).await?
                 }
-                Command::ConfigSet { key, value } => {
-                    controllers::config_set_cmd(key, value, &lru, &mut socket).await?
+                Command::GEOHASH { key, members } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::geohash_cmd(key, members, &_db, &mut _heap, &mut socket).await?
                 }
-                Command::HELLO { version } => controllers::hello_cmd(version, &mut socket).await?,
-                Command::COMMAND => controllers::command_cmd(&mut socket).await?,
-                Command::ClientSetinfo => socket.write_all(b"+OK\r\n").await?,
-                Command::LPUSH { key, values } => {
-                    lru.record_access(&mut access_buffer, &key);
-                    controllers::lpush_cmd(key, values, &_db, &mut _heap, &lru, &mut socket).await?
+                Command::GEODIST {
+                    key,
+                    member1,
+                    member2,
+                    unit,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::geodist_cmd(
+                        key,
+                        member1,
+                        member2,
+                        unit,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
                 }
-                Command::RPUSH { key, values } => {
-                    lru.record_access(&mut access_buffer, &key);
-                    controllers::rpush_cmd(key, values, &_db, &mut _heap, &lru, &mut socket).await?
+                Command::GEOSEARCH { key, search } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::geosearch_cmd(key, search, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::GEOSEARCHSTORE {
+                    destination,
+                    source,
+                    search,
+                    store_dist,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    lru.record_access(&mut access_buffer, selected, &source);
+                    controllers::geosearchstore_cmd(
+                        destination,
+                        source,
+                        search,
+                        store_dist,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::JsonSet {
+                    key,
+                    path,
+                    value,
+                    nx,
+                    xx,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::json_set_cmd(
+                        key,
+                        path,
+                        value,
+                        nx,
+                        xx,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::JsonGet { key, paths } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::json_get_cmd(key, paths, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::JsonDel { key, path } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::json_del_cmd(key, path, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::JsonType { key, path } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::json_type_cmd(key, path, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::JsonNumIncrBy { key, path, by } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::json_numincrby_cmd(
+                        key,
+                        path,
+                        by,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::JsonArrAppend { key, path, values } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::json_arrappend_cmd(
+                        key,
+                        path,
+                        values,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::JsonStrLen { key, path } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::json_strlen_cmd(key, path, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::JsonObjKeys { key, path } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::json_objkeys_cmd(key, path, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::BfReserve {
+                    key,
+                    error_rate,
+                    capacity,
+                    expansion,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::bf_reserve_cmd(
+                        key,
+                        error_rate,
+                        capacity,
+                        expansion,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::BfAdd { key, item } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::bf_add_cmd(key, item, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::BfMAdd { key, items } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::bf_madd_cmd(key, items, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::BfExists { key, item } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::bf_exists_cmd(key, item, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::BfMExists { key, items } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::bf_mexists_cmd(key, items, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::BfInfo { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::bf_info_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::CfAdd { key, item } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::cf_add_cmd(key, item, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::CfDel { key, item } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::cf_del_cmd(key, item, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::CfExists { key, item } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::cf_exists_cmd(key, item, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::CmsInitByDim { key, width, depth } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::cms_initbydim_cmd(
+                        key,
+                        width,
+                        depth,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::CmsInitByProb {
+                    key,
+                    error,
+                    probability,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::cms_initbyprob_cmd(
+                        key,
+                        error,
+                        probability,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::CmsIncrBy { key, increments } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::cms_incrby_cmd(
+                        key,
+                        increments,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::CmsQuery { key, items } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::cms_query_cmd(key, items, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::CmsMerge {
+                    destination,
+                    sources,
+                    weights,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    for key in &sources {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::cms_merge_cmd(
+                        destination,
+                        sources,
+                        weights,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::TopKReserve {
+                    key,
+                    k,
+                    width,
+                    depth,
+                    decay,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::topk_reserve_cmd(
+                        key,
+                        k,
+                        width,
+                        depth,
+                        decay,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::TopKAdd { key, items } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::topk_add_cmd(key, items, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::TopKQuery { key, items } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::topk_query_cmd(key, items, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::TopKCount { key, items } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::topk_count_cmd(key, items, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::TopKList { key, with_count } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::topk_list_cmd(key, with_count, &_db, &mut _heap, &mut socket)
+                        .await?
+                }
+                Command::TsCreate { key, series } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::ts_create_cmd(key, series, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::TsAdd {
+                    key,
+                    timestamp,
+                    value,
+                    on_duplicate,
+                    series,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::ts_add_cmd(
+                        key,
+                        timestamp,
+                        value,
+                        on_duplicate,
+                        series,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::TsMAdd { samples } => {
+                    for (key, _, _) in &samples {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::ts_madd_cmd(samples, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::TsRange { key, range } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::ts_range_cmd(key, range, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::TsMRange {
+                    range,
+                    with_labels,
+                    filters,
+                } => {
+                    controllers::ts_mrange_cmd(
+                        range,
+                        with_labels,
+                        filters,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::HEXPIRE {
+                    key,
+                    expiry,
+                    condition,
+                    fields,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hexpire_cmd(
+                        key,
+                        expiry,
+                        condition,
+                        fields,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
                 }
-                Command::LPOP { key } => {
-                    lru.record_access(&mut access_buffer, &key);
-                    controllers::lpop_cmd(key, &_db, &mut _heap, &lru, &mut socket).await?
+                Command::HTTL {
+                    key,
+                    fields,
+                    millis,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::httl_cmd(key, fields, millis, &_db, &mut _heap, &mut socket)
+                        .await?
+                }
+                Command::HPERSIST { key, fields } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hpersist_cmd(key, fields, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
                 }
-                Command::RPOP { key } => {
-                    lru.record_access(&mut access_buffer, &key);
-                    controllers::rpop_cmd(key, &_db, &mut _heap, &lru, &mut socket).await?
+                Command::SCAN {
+                    cursor,
+                    pattern,
+                    count,
+                    type_name,
+                } => {
+                    controllers::scan_cmd(
+                        cursor,
+                        pattern,
+                        count,
+                        type_name,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::SSCAN {
+                    key,
+                    cursor,
+                    pattern,
+                    count,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::sscan_cmd(
+                        key,
+                        cursor,
+                        pattern,
+                        count,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::ZSCAN {
+                    key,
+                    cursor,
+                    pattern,
+                    count,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zscan_cmd(
+                        key,
+                        cursor,
+                        pattern,
+                        count,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::KEYS { pattern } => {
+                    controllers::keys_cmd(pattern, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::RENAME {
+                    source,
+                    destination,
+                    nx,
+                } => {
+                    controllers::rename_cmd(
+                        source,
+                        destination,
+                        nx,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::COPY {
+                    source,
+                    destination,
+                    replace,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &source);
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    controllers::copy_cmd(
+                        source,
+                        destination,
+                        replace,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::RANDOMKEY => {
+                    controllers::randomkey_cmd(&_db, &mut _heap, &mut socket).await?
+                }
+                Command::DBSIZE => controllers::dbsize_cmd(&_db, &mut socket).await?,
+                Command::TOUCH { keys } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::touch_cmd(keys, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::SELECT { index } => {
+                    if let Some(database) =
+                        controllers::select_cmd(index, &databases, &mut socket).await?
+                    {
+                        selected = index as usize;
+                        _db = database.db;
+                        _heap = database.heap;
+                        blocking = database.blocking;
+                    }
+                }
+                Command::MOVE { key, db } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::move_cmd(key, db, selected, &databases, &lru, &mut socket).await?
+                }
+                Command::SWAPDB { first, second } => {
+                    controllers::swapdb_cmd(first, second, &databases, &lru, &mut socket).await?
+                }
+                Command::FLUSHDB { lazy } => {
+                    controllers::flushdb_cmd(lazy, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::FLUSHALL { lazy } => {
+                    controllers::flushall_cmd(lazy, &databases, &lru, &mut socket).await?
+                }
+                Command::DEL { keys } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::del_cmd(keys, false, &_db, &lru, &mut socket).await?
+                }
+                Command::UNLINK { keys } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::del_cmd(keys, true, &_db, &lru, &mut socket).await?
+                }
+                Command::EXISTS { keys } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::exists_cmd(keys, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::EXPIRE {
+                    key,
+                    expiry,
+                    conditions,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::expire_cmd(
+                        key,
+                        expiry,
+                        conditions,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::PERSIST { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::persist_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::TTL { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::ttl_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::PTTL { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::pttl_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::EXPIRETIME { key, millis } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::expiretime_cmd(key, millis, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::TYPE { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::type_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::INFO { section } => {
+                    controllers::info_cmd(section, &_db, &databases, &lru, &mut socket).await?
+                }
+                Command::ConfigGet { pattern } => {
+                    controllers::config_get_cmd(pattern, databases.count(), &lru, &mut socket)
+                        .await?
+                }
+                Command::ConfigSet { key, value } => {
+                    controllers::config_set_cmd(key, value, &lru, &mut socket).await?
+                }
+                Command::HELLO { version } => controllers::hello_cmd(version, &mut socket).await?,
+                Command::COMMAND => controllers::command_cmd(&mut socket).await?,
+                Command::ClientSetinfo => socket.write_all(b"+OK\r\n").await?,
+                Command::LPUSH { key, values } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::lpush_cmd(
+                        key,
+                        values,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::RPUSH { key, values } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::rpush_cmd(
+                        key,
+                        values,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::LPOP { key, count } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::lpop_cmd(key, count, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::RPOP { key, count } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::rpop_cmd(key, count, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::LPUSHX { key, values } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::lpushx_cmd(key, values, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::RPUSHX { key, values } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::rpushx_cmd(key, values, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::LRANGE { key, start, stop } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::lrange_cmd(key, start, stop, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::LINDEX { key, index } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::lindex_cmd(key, index, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::LLEN { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::llen_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::LSET { key, index, value } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::lset_cmd(key, index, value, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::LINSERT {
+                    key,
+                    before,
+                    pivot,
+                    value,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::linsert_cmd(
+                        key,
+                        before,
+                        pivot,
+                        value,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::LREM { key, count, value } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::lrem_cmd(key, count, value, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::LTRIM { key, start, stop } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::ltrim_cmd(key, start, stop, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::LPOS {
+                    key,
+                    element,
+                    rank,
+                    count,
+                    maxlen,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::lpos_cmd(
+                        key,
+                        element,
+                        rank,
+                        count,
+                        maxlen,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::LMOVE {
+                    source,
+                    destination,
+                    from_left,
+                    to_left,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &source);
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    controllers::lmove_cmd(
+                        source,
+                        destination,
+                        from_left,
+                        to_left,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::RPOPLPUSH {
+                    source,
+                    destination,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &source);
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    controllers::rpoplpush_cmd(
+                        source,
+                        destination,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::LMPOP { keys, left, count } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::lmpop_cmd(
+                        keys,
+                        left,
+                        count,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::BLPOP { keys, timeout } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::blpop_cmd(
+                        keys,
+                        timeout,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::BRPOP { keys, timeout } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::brpop_cmd(
+                        keys,
+                        timeout,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::BLMOVE {
+                    source,
+                    destination,
+                    from_left,
+                    to_left,
+                    timeout,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &source);
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    controllers::blmove_cmd(
+                        source,
+                        destination,
+                        from_left,
+                        to_left,
+                        timeout,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::BLMPOP {
+                    keys,
+                    left,
+                    count,
+                    timeout,
+                } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::blmpop_cmd(
+                        keys,
+                        left,
+                        count,
+                        timeout,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::HSET { key, pairs } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hset_cmd(key, pairs, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::HSETNX { key, field, value } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hsetnx_cmd(key, field, value, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::HGET { key, field } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hget_cmd(key, field, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::HMGET { key, fields } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hmget_cmd(key, fields, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::HDEL { key, fields } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hdel_cmd(key, fields, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::HEXISTS { key, field } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hexists_cmd(key, field, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::HLEN { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hlen_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::HSTRLEN { key, field } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hstrlen_cmd(key, field, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::HKEYS { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hkeys_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::HVALS { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hvals_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::HGETALL { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hgetall_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::HINCRBY {
+                    key,
+                    field,
+                    increment,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hincrby_cmd(
+                        key,
+                        field,
+                        increment,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::HINCRBYFLOAT {
+                    key,
+                    field,
+                    increment,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hincrbyfloat_cmd(
+                        key,
+                        field,
+                        increment,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::HRANDFIELD {
+                    key,
+                    count,
+                    with_values,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hrandfield_cmd(
+                        key,
+                        count,
+                        with_values,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::HSCAN {
+                    key,
+                    cursor,
+                    pattern,
+                    count,
+                    no_values,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::hscan_cmd(
+                        key,
+                        cursor,
+                        pattern,
+                        count,
+                        no_values,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::SADD { key, members } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::sadd_cmd(key, members, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::SREM { key, members } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::srem_cmd(key, members, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::SISMEMBER { key, member } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::sismember_cmd(key, member, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::SMISMEMBER { key, members } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::smismember_cmd(key, members, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::SMEMBERS { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::smembers_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::SCARD { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::scard_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::SPOP { key, count } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::spop_cmd(key, count, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::SRANDMEMBER { key, count } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::srandmember_cmd(key, count, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::SMOVE {
+                    source,
+                    destination,
+                    member,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &source);
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    controllers::smove_cmd(
+                        source,
+                        destination,
+                        member,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::SINTER { keys } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::sinter_cmd(keys, &_db, &mut socket).await?
+                }
+                Command::SUNION { keys } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::sunion_cmd(keys, &_db, &mut socket).await?
+                }
+                Command::SDIFF { keys } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::sdiff_cmd(keys, &_db, &mut socket).await?
+                }
+                Command::SINTERSTORE { destination, keys } => {
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::sinterstore_cmd(
+                        destination,
+                        keys,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::SUNIONSTORE { destination, keys } => {
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::sunionstore_cmd(
+                        destination,
+                        keys,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::SDIFFSTORE { destination, keys } => {
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::sdiffstore_cmd(
+                        destination,
+                        keys,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::SINTERCARD { keys, limit } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::sintercard_cmd(keys, limit, &_db, &mut socket).await?
+                }
+                Command::ZADD { key, flags, pairs } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zadd_cmd(key, flags, pairs, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::ZREM { key, members } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zrem_cmd(key, members, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::ZSCORE { key, member } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zscore_cmd(key, member, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::ZINCRBY {
+                    key,
+                    increment,
+                    member,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zincrby_cmd(
+                        key,
+                        increment,
+                        member,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::ZCARD { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zcard_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::ZCOUNT { key, min, max } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zcount_cmd(key, min, max, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::ZRANK {
+                    key,
+                    member,
+                    with_score,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zrank_cmd(key, member, with_score, &_db, &mut _heap, &mut socket)
+                        .await?
+                }
+                Command::ZREVRANK {
+                    key,
+                    member,
+                    with_score,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zrevrank_cmd(
+                        key,
+                        member,
+                        with_score,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::ZRANGE { key, spec } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zrange_cmd(key, spec, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::ZRANGESTORE {
+                    destination,
+                    source,
+                    spec,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    lru.record_access(&mut access_buffer, selected, &source);
+                    controllers::zrangestore_cmd(
+                        destination,
+                        source,
+                        spec,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::ZPOPMIN { key, count } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zpopmin_cmd(key, count, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::ZPOPMAX { key, count } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::zpopmax_cmd(key, count, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::ZUNIONSTORE {
+                    destination,
+                    keys,
+                    weights,
+                    aggregate,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::zunionstore_cmd(
+                        destination,
+                        keys,
+                        weights,
+                        aggregate,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::ZINTERSTORE {
+                    destination,
+                    keys,
+                    weights,
+                    aggregate,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &destination);
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::zinterstore_cmd(
+                        destination,
+                        keys,
+                        weights,
+                        aggregate,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XADD {
+                    key,
+                    nomkstream,
+                    trim,
+                    id,
+                    fields,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xadd_cmd(
+                        key,
+                        nomkstream,
+                        trim,
+                        id,
+                        fields,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XRANGE {
+                    key,
+                    start,
+                    end,
+                    count,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xrange_cmd(key, start, end, count, &_db, &mut _heap, &mut socket)
+                        .await?
+                }
+                Command::XREVRANGE {
+                    key,
+                    start,
+                    end,
+                    count,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xrevrange_cmd(
+                        key,
+                        start,
+                        end,
+                        count,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XLEN { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xlen_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::XDEL { key, ids } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xdel_cmd(key, ids, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::XTRIM { key, trim } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xtrim_cmd(key, trim, &_db, &mut _heap, &lru, &mut socket).await?
+                }
+                Command::XREAD {
+                    keys,
+                    ids,
+                    count,
+                    block,
+                } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::xread_cmd(
+                        keys,
+                        ids,
+                        count,
+                        block,
+                        &_db,
+                        &mut _heap,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XREADGROUP {
+                    group,
+                    consumer,
+                    keys,
+                    ids,
+                    count,
+                    block,
+                    noack,
+                } => {
+                    for key in &keys {
+                        lru.record_access(&mut access_buffer, selected, key);
+                    }
+                    controllers::xreadgroup_cmd(
+                        group,
+                        consumer,
+                        keys,
+                        ids,
+                        count,
+                        block,
+                        noack,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XGroupCreate {
+                    key,
+                    group,
+                    id,
+                    mkstream,
+                    entries_read,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xgroup_create_cmd(
+                        key,
+                        group,
+                        id,
+                        mkstream,
+                        entries_read,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XGroupSetId {
+                    key,
+                    group,
+                    id,
+                    entries_read,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xgroup_setid_cmd(
+                        key,
+                        group,
+                        id,
+                        entries_read,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XGroupDestroy { key, group } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xgroup_destroy_cmd(
+                        key,
+                        group,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &blocking,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XGroupCreateConsumer {
+                    key,
+                    group,
+                    consumer,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xgroup_createconsumer_cmd(
+                        key,
+                        group,
+                        consumer,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XGroupDelConsumer {
+                    key,
+                    group,
+                    consumer,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xgroup_delconsumer_cmd(
+                        key,
+                        group,
+                        consumer,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XACK { key, group, ids } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xack_cmd(key, group, ids, &_db, &mut _heap, &lru, &mut socket)
+                        .await?
+                }
+                Command::XPENDING {
+                    key,
+                    group,
+                    range,
+                    idle,
+                    consumer,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xpending_cmd(
+                        key,
+                        group,
+                        range,
+                        idle,
+                        consumer,
+                        &_db,
+                        &mut _heap,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XCLAIM {
+                    key,
+                    group,
+                    consumer,
+                    min_idle,
+                    ids,
+                    options,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xclaim_cmd(
+                        key,
+                        group,
+                        consumer,
+                        min_idle,
+                        ids,
+                        options,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XAUTOCLAIM {
+                    key,
+                    group,
+                    consumer,
+                    min_idle,
+                    start,
+                    count,
+                    justid,
+                } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xautoclaim_cmd(
+                        key,
+                        group,
+                        consumer,
+                        min_idle,
+                        start,
+                        count,
+                        justid,
+                        &_db,
+                        &mut _heap,
+                        &lru,
+                        &mut socket,
+                    )
+                    .await?
+                }
+                Command::XInfoStream { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xinfo_stream_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::XInfoGroups { key } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xinfo_groups_cmd(key, &_db, &mut _heap, &mut socket).await?
+                }
+                Command::XInfoConsumers { key, group } => {
+                    lru.record_access(&mut access_buffer, selected, &key);
+                    controllers::xinfo_consumers_cmd(key, group, &_db, &mut _heap, &mut socket)
+                        .await?
                 }
             }
             lru.flush_accesses(&mut access_buffer);
@@ -0,0 +1,118 @@
+use crate::model::{Json, Value};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+use tokio::sync::mpsc;
+
+/// Values with more elements than this are handed to the background task
+/// when lazy freeing applies; smaller ones are cheaper to drop inline.
+const LAZYFREE_THRESHOLD: usize = 64;
+
+/// The situations in which removed values may be freed in the background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LazyFreeOption {
+    /// Keys evicted to stay under `maxmemory`.
+    Eviction,
+    /// Keys removed by the background expiry task.
+    Expire,
+    /// Keys removed by DEL. UNLINK always frees lazily.
+    UserDel,
+}
+
+impl LazyFreeOption {
+    pub const ALL: [Self; 3] = [Self::Eviction, Self::Expire, Self::UserDel];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|option| option.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Eviction => "lazyfree-lazy-eviction",
+            Self::Expire => "lazyfree-lazy-expire",
+            Self::UserDel => "lazyfree-lazy-user-del",
+        }
+    }
+}
+
+/// Frees large removed values off the request path. Batches go through a
+/// channel to a task that drops them on a blocking thread, so neither the
+/// DB lock nor the runtime's workers wait on the deallocation.
+#[derive(Clone)]
+pub struct LazyFree {
+    tx: mpsc::UnboundedSender<Vec<Value>>,
+    options: Arc<[AtomicBool; 3]>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl LazyFree {
+    pub fn new() -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Vec<Value>>();
+        let pending = Arc::new(AtomicUsize::new(0));
+
+        let pending_task = pending.clone();
+        tokio::spawn(async move {
+            while let Some(batch) = rx.recv().await {
+                let count = batch.len();
+                let _ = tokio::task::spawn_blocking(move || drop(batch)).await;
+                pending_task.fetch_sub(count, Ordering::Relaxed);
+            }
+        });
+
+        Self {
+            tx,
+            options: Arc::new(Default::default()),
+            pending,
+        }
+    }
+
+    pub fn enabled(&self, option: LazyFreeOption) -> bool {
+        self.options[option as usize].load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, option: LazyFreeOption, enabled: bool) {
+        self.options[option as usize].store(enabled, Ordering::Relaxed);
+    }
+
+    /// Values queued for the background task that it has not freed yet.
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Drops `values`, sending the large ones to the background task when
+    /// `lazy` is set. Call it after releasing the DB lock.
+    pub fn free(&self, values: Vec<Value>, lazy: bool) {
+        if !lazy {
+            return;
+        }
+        let large: Vec<Value> = values
+            .into_iter()
+            .filter(|value| free_effort(value) > LAZYFREE_THRESHOLD)
+            .collect();
+        if large.is_empty() {
+            return;
+        }
+        self.pending.fetch_add(large.len(), Ordering::Relaxed);
+        if let Err(mpsc::error::SendError(batch)) = self.tx.send(large) {
+            self.pending.fetch_sub(batch.len(), Ordering::Relaxed);
+        }
+    }
+}
+
+/// Roughly how many allocations dropping `value` releases.
+fn free_effort(value: &Value) -> usize {
+    match value {
+        Value::List(list) => list.len(),
+        Value::Hash(hash) => hash.len(),
+        Value::Set(set) => set.len(),
+        Value::ZSet(zset) => zset.len(),
+        Value::Stream(stream) => stream.len(),
+        Value::Json(Json::Array(items)) => items.len(),
+        Value::Json(Json::Object(fields)) => fields.len(),
+        Value::TimeSeries(ts) => ts.len(),
+        _ => 1,
+    }
+}
@@ -1,7 +1,9 @@
-use crate::model::{DB, Entry, Value};
+use crate::databases::Databases;
+use crate::lazyfree::{LazyFree, LazyFreeOption};
+use crate::model::{DB, Entry, Json, PendingEntry, Stream, StreamId, Value, ZSet};
 use anyhow::Result;
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     mem::{self, size_of},
     sync::{
         Arc,
@@ -21,19 +23,27 @@ pub enum EvictionPolicy {
     VolatileTtl,
 }
 
+/// Access recency for one database, by key.
+type Recency = HashMap<String, u64>;
+
 #[derive(Clone)]
 pub struct LruManager {
-    access_tx: mpsc::Sender<Vec<String>>,
-    last_access: Arc<Mutex<HashMap<String, u64>>>,
+    access_tx: mpsc::Sender<Vec<(usize, String)>>,
+    /// Indexed like `databases`, so equal names in two databases keep
+    /// separate recency.
+    last_access: Arc<Mutex<Vec<Recency>>>,
+    databases: Databases,
     maxmemory: Arc<AtomicUsize>,
     policy: Arc<AtomicU8>,
     used_bytes: Arc<AtomicUsize>,
+    lazyfree: LazyFree,
 }
 
 impl LruManager {
-    pub fn new(maxmemory: usize, policy: EvictionPolicy) -> Self {
-        let (access_tx, mut access_rx) = mpsc::channel::<Vec<String>>(ACCESS_CHANNEL_CAPACITY);
-        let last_access = Arc::new(Mutex::new(HashMap::<String, u64>::new()));
+    pub fn new(maxmemory: usize, policy: EvictionPolicy, databases: Databases) -> Self {
+        let (access_tx, mut access_rx) =
+            mpsc::channel::<Vec<(usize, String)>>(ACCESS_CHANNEL_CAPACITY);
+        let last_access = Arc::new(Mutex::new(vec![Recency::new(); databases.count()]));
         let counter = Arc::new(AtomicU64::new(0));
         let maxmemory = Arc::new(AtomicUsize::new(maxmemory));
         let policy = Arc::new(AtomicU8::new(policy_to_u8(policy)));
@@ -56,10 +66,10 @@ impl LruManager {
                     continue;
                 }
 
-                let mut map = last_access_task.lock().await;
-                for key in batch {
+                let mut recency = last_access_task.lock().await;
+                for (db, key) in batch {
                     let tick = counter_task.fetch_add(1, Ordering::Relaxed) + 1;
-                    map.insert(key, tick);
+                    recency[db].insert(key, tick);
                 }
             }
         });
@@ -67,23 +77,29 @@ impl LruManager {
         Self {
             access_tx,
             last_access,
+            databases,
             maxmemory,
             policy,
             used_bytes,
+            lazyfree: LazyFree::new(),
         }
     }
 
-    pub fn record_access(&self, buffer: &mut Vec<String>, key: &str) {
+    pub fn lazyfree(&self) -> &LazyFree {
+        &self.lazyfree
+    }
+
+    pub fn record_access(&self, buffer: &mut Vec<(usize, String)>, db: usize, key: &str) {
         if self.policy() != EvictionPolicy::AllKeysLru || self.maxmemory() == 0 {
             return;
         }
-        buffer.push(key.to_string());
+        buffer.push((db, key.to_string()));
         if buffer.len() >= ACCESS_BATCH_SIZE {
             self.flush_accesses(buffer);
         }
     }
 
-    pub fn flush_accesses(&self, buffer: &mut Vec<String>) {
+    pub fn flush_accesses(&self, buffer: &mut Vec<(usize, String)>) {
         if self.policy() != EvictionPolicy::AllKeysLru || self.maxmemory() == 0 {
             buffer.clear();
             return;
@@ -95,28 +111,75 @@ impl LruManager {
         let _ = self.access_tx.try_send(batch);
     }
 
-    pub async fn remove_key(&self, key: &str) {
+    /// Index of `db` among the databases, for keying its recency.
+    fn db_index(&self, db: &DB) -> usize {
+        self.databases
+            .iter()
+            .position(|database| Arc::ptr_eq(&database.db, db))
+            .unwrap_or(0)
+    }
+
+    pub async fn remove_key(&self, db: &DB, key: &str) {
         if self.policy() != EvictionPolicy::AllKeysLru {
             return;
         }
-        let mut map = self.last_access.lock().await;
-        map.remove(key);
+        let index = self.db_index(db);
+        let mut recency = self.last_access.lock().await;
+        recency[index].remove(key);
     }
 
-    pub async fn remove_keys(&self, keys: &[String]) {
+    /// Carries `from`'s recency over to `to` when a key is renamed.
+    pub async fn rename_key(&self, db: &DB, from: &str, to: &str) {
+        if self.policy() != EvictionPolicy::AllKeysLru {
+            return;
+        }
+        let index = self.db_index(db);
+        let mut recency = self.last_access.lock().await;
+        let map = &mut recency[index];
+        match map.remove(from) {
+            Some(tick) => map.insert(to.to_string(), tick),
+            None => map.remove(to),
+        };
+    }
+
+    pub async fn remove_keys(&self, db: &DB, keys: &[String]) {
         if self.policy() != EvictionPolicy::AllKeysLru {
             return;
         }
         if keys.is_empty() {
             return;
         }
-        let mut map = self.last_access.lock().await;
+        let index = self.db_index(db);
+        let mut recency = self.last_access.lock().await;
         for key in keys {
-            map.remove(key);
+            recency[index].remove(key);
+        }
+    }
+
+    /// Empties `db`'s recency for FLUSHDB, handing back the old map so the
+    /// caller decides where it is dropped.
+    pub async fn take_recency(&self, db: &DB) -> HashMap<String, u64> {
+        let index = self.db_index(db);
+        mem::take(&mut self.last_access.lock().await[index])
+    }
+
+    /// Carries `key`'s recency along when MOVE hands it to another database.
+    pub async fn move_key(&self, from: usize, to: usize, key: &str) {
+        let mut recency = self.last_access.lock().await;
+        if let Some(tick) = recency[from].remove(key) {
+            recency[to].insert(key.to_string(), tick);
         }
     }
 
-    pub async fn evict_if_needed(&self, db: &DB, heap: &crate::model::Heap) -> Result<bool> {
+    /// Follows SWAPDB, which exchanges the keyspaces behind two indexes.
+    pub async fn swap_dbs(&self, a: usize, b: usize) {
+        self.last_access.lock().await.swap(a, b);
+    }
+
+    /// Evicts under the configured policy until usage is back under
+    /// `maxmemory`. Candidates are drawn from every database, since the limit
+    /// covers them all.
+    pub async fn evict_if_needed(&self) -> Result<bool> {
         let maxmemory = self.maxmemory();
         if maxmemory == 0 {
             return Ok(true);
@@ -130,105 +193,170 @@ impl LruManager {
             EvictionPolicy::NoEviction => return Ok(false),
             EvictionPolicy::AllKeysLru => {
                 while used > maxmemory {
-                    let sample_keys = {
-                        let db_read = db.read().await;
-                        db_read
-                            .keys()
-                            .take(SAMPLE_SIZE)
-                            .cloned()
-                            .collect::<Vec<_>>()
-                    };
+                    let mut sample_keys = Vec::new();
+                    for (index, database) in self.databases.iter().enumerate() {
+                        let db_read = database.db.read().await;
+                        sample_keys.extend(
+                            db_read
+                                .keys()
+                                .take(SAMPLE_SIZE)
+                                .map(|key| (index, key.clone())),
+                        );
+                    }
 
                     if sample_keys.is_empty() {
                         break;
                     }
 
                     let candidate = {
-                        let map = self.last_access.lock().await;
-                        let mut best: Option<(String, u64)> = None;
-
-                        for key in &sample_keys {
-                            let tick = map.get(key).copied().unwrap_or(0);
-                            match &best {
-                                None => best = Some((key.clone(), tick)),
-                                Some((_, best_tick)) if tick < *best_tick => {
-                                    best = Some((key.clone(), tick))
-                                }
-                                _ => {}
-                            }
-                        }
-                        best.map(|(key, _)| key)
+                        let recency = self.last_access.lock().await;
+                        // A key without a tick was written by a command whose
+                        // access has not been flushed yet, so it is the newest.
+                        sample_keys.into_iter().min_by_key(|(index, key)| {
+                            recency[*index].get(key).copied().unwrap_or(u64::MAX)
+                        })
                     };
 
-                    let Some(key) = candidate else {
+                    let Some((index, key)) = candidate else {
+                        break;
+                    };
+                    let Some(database) = self.databases.get(index) else {
                         break;
                     };
 
                     let removed = {
-                        let mut db_write = db.write().await;
+                        let mut db_write = database.db.write().await;
                         db_write.remove_entry(&key)
                     };
 
                     if let Some((stored_key, entry)) = removed {
                         let bytes = estimate_entry_bytes(&stored_key, &entry);
                         used = self.adjust_used_bytes(-(bytes as isize));
-                        self.remove_key(&stored_key).await;
+                        self.remove_key(&database.db, &stored_key).await;
+                        self.free_evicted(entry);
                     } else {
                         used = self.used_bytes.load(Ordering::Relaxed);
                     }
                 }
             }
             EvictionPolicy::VolatileTtl => {
+                // Hash field deadlines share the heaps with key TTLs. They are
+                // not eviction candidates but the expiry task still needs
+                // them, so they go back once the loop is done.
+                let mut field_records = Vec::new();
                 while used > maxmemory {
+                    // The soonest deadline across the databases' heaps.
+                    let mut soonest: Option<(u64, usize)> = None;
+                    for (index, database) in self.databases.iter().enumerate() {
+                        let heap_guard = database.heap.lock().await;
+                        if let Some(top) = heap_guard.peek()
+                            && soonest.is_none_or(|(at, _)| top.expires_at < at)
+                        {
+                            soonest = Some((top.expires_at, index));
+                        }
+                    }
+
+                    let Some(database) = soonest.and_then(|(_, index)| self.databases.get(index))
+                    else {
+                        break;
+                    };
+
                     let candidate = {
-                        let mut heap_guard = heap.lock().await;
+                        let mut heap_guard = database.heap.lock().await;
                         heap_guard.pop()
                     };
 
                     let Some(min) = candidate else {
-                        break;
+                        continue;
                     };
 
-                    let should_remove = {
-                        let db_read = db.read().await;
-                        matches!(
-                            db_read.get(&min.key),
-                            Some(entry) if entry.expires_at == Some(min.expires_at)
-                        )
+                    let (should_remove, field_deadline) = {
+                        let db_read = database.db.read().await;
+                        match db_read.get(&min.key) {
+                            Some(entry) if entry.expires_at == Some(min.expires_at) => {
+                                (true, false)
+                            }
+                            Some(entry) => (
+                                false,
+                                entry.value.as_hash().is_some_and(|hash| {
+                                    hash.deadlines().any(|at| at == min.expires_at)
+                                }),
+                            ),
+                            None => (false, false),
+                        }
                     };
 
+                    if field_deadline {
+                        field_records.push((database, min));
+                        continue;
+                    }
                     if !should_remove {
                         continue;
                     }
 
                     let removed = {
-                        let mut db_write = db.write().await;
+                        let mut db_write = database.db.write().await;
                         db_write.remove_entry(&min.key)
                     };
 
                     if let Some((stored_key, entry)) = removed {
                         let bytes = estimate_entry_bytes(&stored_key, &entry);
                         used = self.adjust_used_bytes(-(bytes as isize));
-                        self.remove_key(&stored_key).await;
+                        self.remove_key(&database.db, &stored_key).await;
+                        self.free_evicted(entry);
                     }
                 }
+
+                for (database, record) in field_records {
+                    database.heap.lock().await.push(record);
+                }
             }
         }
 
         Ok(used <= maxmemory)
     }
+
+    fn free_evicted(&self, entry: Entry) {
+        let lazy = self.lazyfree.enabled(LazyFreeOption::Eviction);
+        self.lazyfree.free(vec![entry.value], lazy);
+    }
+
+    /// Charges `delta` bytes against `maxmemory`, evicting if the limit is
+    /// exceeded. Returns `false` when the write must be rejected; the caller
+    /// then undoes its change and releases `delta` again.
+    pub async fn try_reserve(&self, delta: isize) -> Result<bool> {
+        let new_used = self.adjust_used_bytes(delta);
+        let maxmemory = self.maxmemory();
+        if maxmemory > 0 && new_used > maxmemory {
+            return self.evict_if_needed().await;
+        }
+        Ok(true)
+    }
 }
 
 pub fn estimate_entry_bytes(key: &String, entry: &Entry) -> usize {
     let key_bytes = key.capacity();
     let value_bytes = value_heap_bytes(&entry.value);
-    size_of::<Entry>() + size_of::<String>() + key_bytes + value_bytes
+    // The keyspace's SCAN index holds a second copy of the key.
+    let index_bytes = size_of::<(u64, String)>() + key.len();
+    size_of::<Entry>() + size_of::<String>() + key_bytes + index_bytes + value_bytes
 }
 
 fn value_heap_bytes(value: &Value) -> usize {
     match value {
         Value::String(bytes) => bytes.capacity(),
+        Value::Int(_) => 0,
         Value::List(list) => list_heap_bytes(list),
+        Value::Hash(hash) => hash.bytes(),
+        Value::Set(set) => set_heap_bytes(set),
+        Value::ZSet(zset) => zset_heap_bytes(zset),
+        Value::Stream(stream) => stream_heap_bytes(stream),
+        Value::Json(json) => json_heap_bytes(json),
+        Value::Bloom(bloom) => bloom.bytes(),
+        Value::Cuckoo(cuckoo) => cuckoo.bytes(),
+        Value::Cms(cms) => cms.bytes(),
+        Value::TopK(topk) => topk.bytes(),
+        Value::TimeSeries(ts) => ts.bytes(),
     }
 }
 
@@ -241,6 +369,67 @@ fn list_heap_bytes(list: &VecDeque<Vec<u8>>) -> usize {
     total
 }
 
+fn set_heap_bytes(set: &HashSet<Vec<u8>>) -> usize {
+    let slots = set.capacity();
+    let mut total = slots * (size_of::<Vec<u8>>() + 1);
+    for member in set {
+        total += member.capacity();
+    }
+    total
+}
+
+fn zset_heap_bytes(zset: &ZSet) -> usize {
+    let mut total = zset.scores_capacity() * (size_of::<(Vec<u8>, f64)>() + 1);
+    total += zset.len() * size_of::<(f64, Vec<u8>)>();
+    for member in zset.members() {
+        total += member.capacity() * 2;
+    }
+    total
+}
+
+fn stream_heap_bytes(stream: &Stream) -> usize {
+    let mut total = 0;
+    for (_, fields) in stream.entries() {
+        total += size_of::<(StreamId, Vec<(Vec<u8>, Vec<u8>)>)>();
+        total += fields.capacity() * size_of::<(Vec<u8>, Vec<u8>)>();
+        for (field, value) in fields {
+            total += field.capacity() + value.capacity();
+        }
+    }
+    for (name, group) in stream.groups() {
+        total += name.capacity();
+        total += group.pending.len() * size_of::<(StreamId, PendingEntry)>();
+        for pending in group.pending.values() {
+            total += pending.consumer.capacity();
+        }
+        for name in group.consumers.keys() {
+            total += name.capacity() * 2;
+        }
+    }
+    total
+}
+
+fn json_heap_bytes(json: &Json) -> usize {
+    match json {
+        Json::String(s) => s.capacity(),
+        Json::Array(items) => {
+            let mut total = items.capacity() * size_of::<Json>();
+            for item in items {
+                total += json_heap_bytes(item);
+            }
+            total
+        }
+        Json::Object(fields) => {
+            let mut total = fields.capacity() * size_of::<(String, Json)>();
+            for (key, value) in fields {
+                total += key.capacity() + json_heap_bytes(value);
+            }
+            total
+        }
+        _ => 0,
+    }
+}
+
 impl LruManager {
     pub fn maxmemory(&self) -> usize {
         self.maxmemory.load(Ordering::Relaxed)
@@ -1,24 +1,24 @@
 mod async_heap_delete;
+mod blocking;
 mod controllers;
+mod databases;
 mod handle_client;
+mod lazyfree;
 mod lru;
 mod model;
 mod parser;
 mod util;
 
 use crate::handle_client::process_client;
-use crate::model::{DB, Entry, Heap, MinHeap};
 use crate::{
     async_heap_delete::async_clean_db_heap,
+    databases::Databases,
+    lazyfree::LazyFreeOption,
     lru::{EvictionPolicy, LruManager},
 };
 use anyhow::Result;
-use std::{
-    collections::{BinaryHeap, HashMap},
-    env,
-    sync::Arc,
-};
-use tokio::{net::TcpListener, sync::Mutex, sync::RwLock};
+use std::env;
+use tokio::net::TcpListener;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -31,6 +31,8 @@ async fn main() -> Result<()> {
     let mut policy = env::var("MINIREDIS_MAXMEMORY_POLICY")
         .unwrap_or_else(|_| "noeviction".to_string())
         .to_lowercase();
+    let mut databases: usize = 16;
+    let mut lazyfree: Vec<(LazyFreeOption, bool)> = Vec::new();
 
     let mut args = env::args().skip(1);
     while let Some(arg) = args.next() {
@@ -41,17 +43,17 @@ async fn main() -> Result<()> {
                 }
             }
             "--port" => {
-                if let Some(v) = args.next() {
-                    if let Ok(p) = v.parse::<u16>() {
-                        port = p;
-                    }
+                if let Some(v) = args.next()
+                    && let Ok(p) = v.parse::<u16>()
+                {
+                    port = p;
                 }
             }
             "--maxmemory" => {
-                if let Some(v) = args.next() {
-                    if let Ok(m) = v.parse::<usize>() {
-                        maxmemory = m;
-                    }
+                if let Some(v) = args.next()
+                    && let Ok(m) = v.parse::<usize>()
+                {
+                    maxmemory = m;
                 }
             }
             "--maxmemory-policy" => {
@@ -59,9 +61,24 @@ async fn main() -> Result<()> {
                     policy = v.to_lowercase();
                 }
             }
+            "--databases" => {
+                if let Some(v) = args.next()
+                    && let Ok(n) = v.parse::<usize>()
+                    && n > 0
+                {
+                    databases = n;
+                }
+            }
+            "--lazyfree-lazy-eviction" | "--lazyfree-lazy-expire" | "--lazyfree-lazy-user-del" => {
+                if let Some(option) = LazyFreeOption::parse(&arg[2..])
+                    && let Some(v) = args.next()
+                {
+                    lazyfree.push((option, v.eq_ignore_ascii_case("yes")));
+                }
+            }
             "--help" | "-h" => {
                 println!(
-                    "miniRedis options:\n  --bind <ip>\n  --port <port>\n  --maxmemory <bytes>\n  --maxmemory-policy <noeviction|allkeys-lru|volatile-ttl>"
+                    "miniRedis options:\n  --bind <ip>\n  --port <port>\n  --maxmemory <bytes>\n  --maxmemory-policy <noeviction|allkeys-lru|volatile-ttl>\n  --databases <count>\n  --lazyfree-lazy-eviction|--lazyfree-lazy-expire|--lazyfree-lazy-user-del <yes|no>"
                 );
                 return Ok(());
             }
@@ -79,22 +96,24 @@ async fn main() -> Result<()> {
     let bind = format!("{}:{}", bind_addr, port);
     let listener = TcpListener::bind(&bind).await?;
     println!("miniRedis listening on {}", bind);
-    let db: DB = Arc::new(RwLock::new(HashMap::<String, Entry>::new()));
-    let heap: Heap = Arc::new(Mutex::new(BinaryHeap::<MinHeap>::new()));
-    let lru = LruManager::new(maxmemory, policy);
+    let databases = Databases::new(databases);
+    let lru = LruManager::new(maxmemory, policy, databases.clone());
+    for (option, enabled) in lazyfree {
+        lru.lazyfree().set_enabled(option, enabled);
+    }
 
-    async_clean_db_heap(db.clone(), heap.clone(), lru.clone());
+    for database in databases.iter() {
+        async_clean_db_heap(database.db.clone(), database.heap.clone(), lru.clone());
+    }
 
     loop {
         let (socket, _) = listener.accept().await.unwrap();
 
-        let db = db.clone();
-        let heap = heap.clone();
-
+        let databases = databases.clone();
         let lru = lru.clone();
 
         tokio::spawn(async move {
-            if let Err(e) = process_client(socket, db, heap, lru).await {
+            if let Err(e) = process_client(socket, databases, lru).await {
                 eprintln!("Error: {:?}", e);
             }
         });
@@ -0,0 +1,135 @@
+/// Operator for BITOP.
+#[derive(Debug, Clone, Copy)]
+pub enum BitOp {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+/// Integer encoding of a BITFIELD field, e.g. `i8` or `u16`.
+#[derive(Debug, Clone, Copy)]
+pub struct BitFieldType {
+    pub signed: bool,
+    pub bits: u8,
+}
+
+/// How BITFIELD SET/INCRBY handle values that do not fit the field.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum BitOverflow {
+    #[default]
+    Wrap,
+    Sat,
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum BitFieldOp {
+    Get {
+        ty: BitFieldType,
+        offset: u64,
+    },
+    Set {
+        ty: BitFieldType,
+        offset: u64,
+        value: i64,
+    },
+    IncrBy {
+        ty: BitFieldType,
+        offset: u64,
+        increment: i64,
+    },
+    Overflow(BitOverflow),
+}
+
+impl BitFieldType {
+    fn min(&self) -> i128 {
+        if self.signed {
+            -(1i128 << (self.bits - 1))
+        } else {
+            0
+        }
+    }
+
+    fn max(&self) -> i128 {
+        if self.signed {
+            (1i128 << (self.bits - 1)) - 1
+        } else {
+            (1i128 << self.bits) - 1
+        }
+    }
+
+    fn mask(&self) -> u64 {
+        if self.bits == 64 {
+            u64::MAX
+        } else {
+            (1u64 << self.bits) - 1
+        }
+    }
+
+    /// Interprets the raw field bits as this type, sign-extending `i` fields.
+    pub fn decode(&self, raw: u64) -> i64 {
+        if self.signed && self.bits < 64 && raw & (1 << (self.bits - 1)) != 0 {
+            (raw | !self.mask()) as i64
+        } else {
+            raw as i64
+        }
+    }
+
+    /// Fits `value` into the field according to `overflow`. Returns `None`
+    /// when it does not fit and the mode is `Fail`.
+    pub fn fit(&self, value: i128, overflow: BitOverflow) -> Option<i64> {
+        if (self.min()..=self.max()).contains(&value) {
+            return Some(value as i64);
+        }
+        match overflow {
+            BitOverflow::Wrap => Some(self.decode(value as u64 & self.mask())),
+            BitOverflow::Sat => Some(value.clamp(self.min(), self.max()) as i64),
+            BitOverflow::Fail => None,
+        }
+    }
+
+    /// Reads the field at bit `offset`; bits past the end of `bytes` are 0.
+    pub fn read(&self, bytes: &[u8], offset: u64) -> i64 {
+        let mut raw = 0u64;
+        for i in 0..self.bits as u64 {
+            raw = (raw << 1) | get_bit(bytes, offset + i) as u64;
+        }
+        self.decode(raw)
+    }
+
+    /// Writes `value` at bit `offset`, growing `bytes` as needed.
+    pub fn write(&self, bytes: &mut Vec<u8>, offset: u64, value: i64) {
+        let raw = value as u64 & self.mask();
+        for i in 0..self.bits as u64 {
+            let on = (raw >> (self.bits as u64 - 1 - i)) & 1 == 1;
+            set_bit(bytes, offset + i, on);
+        }
+    }
+}
+
+/// Returns the bit at `offset`, counting from the most significant bit of
+/// the first byte. Bits past the end read as 0.
+pub fn get_bit(bytes: &[u8], offset: u64) -> bool {
+    let Some(byte) = bytes.get((offset / 8) as usize) else {
+        return false;
+    };
+    byte & (0x80 >> (offset % 8)) != 0
+}
+
+/// Sets the bit at `offset`, zero-padding `bytes` up to it, and returns the
+/// previous bit.
+pub fn set_bit(bytes: &mut Vec<u8>, offset: u64, on: bool) -> bool {
+    let idx = (offset / 8) as usize;
+    if bytes.len() <= idx {
+        bytes.resize(idx + 1, 0);
+    }
+    let mask = 0x80 >> (offset % 8);
+    let previous = bytes[idx] & mask != 0;
+    if on {
+        bytes[idx] |= mask;
+    } else {
+        bytes[idx] &= !mask;
+    }
+    previous
+}
@@ -0,0 +1,183 @@
+use super::hyperloglog::murmur_hash64a;
+
+const BF_DEFAULT_ERROR_RATE: f64 = 0.01;
+const BF_DEFAULT_CAPACITY: u64 = 100;
+pub const BF_DEFAULT_EXPANSION: u32 = 2;
+/// Sub-filter `i` is sized for `error_rate * TIGHTENING_RATIO^(i + 1)`, so
+/// the rates of the whole chain sum to at most the configured one.
+const TIGHTENING_RATIO: f64 = 0.5;
+const HASH_SEED: u64 = 0x9747_b28c;
+/// Largest sub-filter, in bits (512 MiB). Reservations and growth past it
+/// are refused rather than left to abort the process on allocation.
+const BF_MAX_BITS: u64 = 1 << 32;
+
+/// A scalable Bloom filter: a chain of plain filters, each larger and
+/// stricter than the last. A new one is added when the newest is full,
+/// unless the filter was reserved as non-scaling.
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    filters: Vec<SubFilter>,
+    error_rate: f64,
+    /// `None` for NONSCALING filters.
+    expansion: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+struct SubFilter {
+    bits: Vec<u64>,
+    nbits: u64,
+    hashes: u32,
+    capacity: u64,
+    count: u64,
+}
+
+impl Default for BloomFilter {
+    fn default() -> Self {
+        BloomFilter::new(
+            BF_DEFAULT_ERROR_RATE,
+            BF_DEFAULT_CAPACITY,
+            Some(BF_DEFAULT_EXPANSION),
+        )
+        .expect("default filter is within BF_MAX_BITS")
+    }
+}
+
+impl BloomFilter {
+    /// A filter for `capacity` items at `error_rate`, or `None` if its
+    /// first sub-filter would exceed `BF_MAX_BITS`.
+    pub fn new(error_rate: f64, capacity: u64, expansion: Option<u32>) -> Option<Self> {
+        Some(BloomFilter {
+            filters: vec![SubFilter::new(error_rate * TIGHTENING_RATIO, capacity)?],
+            error_rate,
+            expansion,
+        })
+    }
+
+    /// Heap bytes `new` would allocate, so the caller can check them
+    /// against `maxmemory` first. `None` if the filter is over the limit.
+    pub fn footprint(error_rate: f64, capacity: u64) -> Option<usize> {
+        let nbits = filter_bits(error_rate * TIGHTENING_RATIO, capacity)?;
+        Some(nbits.div_ceil(64) as usize * size_of::<u64>() + size_of::<SubFilter>())
+    }
+
+    pub fn contains(&self, item: &[u8]) -> bool {
+        let hashes = item_hashes(item);
+        self.filters.iter().any(|filter| filter.contains(hashes))
+    }
+
+    /// Adds an item, reporting whether it was new. Returns `None` when a
+    /// non-scaling filter is full, or the next sub-filter would exceed
+    /// `BF_MAX_BITS`.
+    pub fn add(&mut self, item: &[u8]) -> Option<bool> {
+        let hashes = item_hashes(item);
+        if self.filters.iter().any(|filter| filter.contains(hashes)) {
+            return Some(false);
+        }
+
+        let newest = self.filters.last()?;
+        if newest.count >= newest.capacity {
+            let expansion = self.expansion?;
+            let level = self.filters.len() as i32 + 1;
+            let capacity = newest.capacity.saturating_mul(expansion as u64);
+            let error_rate = self.error_rate * TIGHTENING_RATIO.powi(level);
+            self.filters.push(SubFilter::new(error_rate, capacity)?);
+        }
+        self.filters.last_mut()?.insert(hashes);
+        Some(true)
+    }
+
+    /// Number of chained filters, for undoing growth with `truncate`.
+    pub fn filter_count(&self) -> usize {
+        self.filters.len()
+    }
+
+    /// Drops the filters added after the first `len`, along with the items
+    /// they hold.
+    pub fn truncate(&mut self, len: usize) {
+        self.filters.truncate(len.max(1));
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.filters.iter().map(|filter| filter.capacity).sum()
+    }
+
+    pub fn items(&self) -> u64 {
+        self.filters.iter().map(|filter| filter.count).sum()
+    }
+
+    pub fn expansion(&self) -> Option<u32> {
+        self.expansion
+    }
+
+    /// Heap bytes held by the chained filters.
+    pub fn bytes(&self) -> usize {
+        self.filters
+            .iter()
+            .map(|filter| filter.bits.capacity() * size_of::<u64>())
+            .sum::<usize>()
+            + self.filters.capacity() * size_of::<SubFilter>()
+    }
+}
+
+impl SubFilter {
+    /// Sizes the filter for `capacity` items at `error_rate` with
+    /// `k = -log2(p)` hash functions; see `filter_bits`.
+    fn new(error_rate: f64, capacity: u64) -> Option<Self> {
+        let nbits = filter_bits(error_rate, capacity)?;
+        let hashes = (-error_rate.log2()).ceil().max(1.0) as u32;
+        Some(SubFilter {
+            bits: vec![0; nbits.div_ceil(64) as usize],
+            nbits,
+            hashes,
+            capacity,
+            count: 0,
+        })
+    }
+
+    fn positions(&self, (a, b): (u64, u64)) -> impl Iterator<Item = u64> + '_ {
+        (0..self.hashes as u64).map(move |i| a.wrapping_add(i.wrapping_mul(b)) % self.nbits)
+    }
+
+    fn contains(&self, hashes: (u64, u64)) -> bool {
+        self.positions(hashes)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    fn insert(&mut self, hashes: (u64, u64)) {
+        let positions: Vec<u64> = self.positions(hashes).collect();
+        for bit in positions {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.count += 1;
+    }
+}
+
+/// `m = -n ln(p) / ln(2)^2` bits for `capacity` items at `error_rate`, or
+/// `None` past `BF_MAX_BITS`.
+fn filter_bits(error_rate: f64, capacity: u64) -> Option<u64> {
+    let ln2 = std::f64::consts::LN_2;
+    let nbits = (-(capacity as f64) * error_rate.ln() / (ln2 * ln2)).ceil();
+    (nbits <= BF_MAX_BITS as f64).then(|| (nbits as u64).max(64))
+}
+
+/// The two base hashes combined as `a + i * b` for the i-th probe.
+fn item_hashes(item: &[u8]) -> (u64, u64) {
+    let a = murmur_hash64a(item, HASH_SEED);
+    let b = murmur_hash64a(item, a);
+    (a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_filters_over_the_size_limit() {
+        assert!(BloomFilter::footprint(0.0000001, 100_000_000_000).is_none());
+        assert!(BloomFilter::new(0.0000001, 100_000_000_000, None).is_none());
+
+        let footprint = BloomFilter::footprint(0.01, 1000).unwrap();
+        let bloom = BloomFilter::new(0.01, 1000, None).unwrap();
+        assert_eq!(bloom.bytes(), footprint);
+    }
+}
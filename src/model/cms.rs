@@ -0,0 +1,87 @@
+use super::hyperloglog::murmur_hash64a;
+
+/// A Count-Min Sketch: `depth` rows of `width` counters, one hash per row.
+/// An item's estimate is its smallest counter, which never undercounts and
+/// overcounts by at most `2 / width` of the total with probability
+/// `1 - 0.5^depth`.
+#[derive(Debug, Clone)]
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    counters: Vec<u64>,
+    count: u64,
+}
+
+impl CountMinSketch {
+    pub fn new(width: usize, depth: usize) -> Self {
+        CountMinSketch {
+            width,
+            depth,
+            counters: vec![0; width * depth],
+            count: 0,
+        }
+    }
+
+    /// Dimensions for an overestimate of at most `error` of the total with
+    /// probability `1 - probability`, as CMS.INITBYPROB takes them.
+    pub fn dims_for(error: f64, probability: f64) -> (usize, usize) {
+        let width = (2.0 / error).ceil() as usize;
+        let depth = (probability.ln() / 0.5f64.ln()).ceil() as usize;
+        (width.max(1), depth.max(1))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Adds `by` to the item's counters and returns its new estimate.
+    pub fn incr_by(&mut self, item: &[u8], by: u64) -> u64 {
+        let mut estimate = u64::MAX;
+        for row in 0..self.depth {
+            let slot = self.slot(item, row);
+            self.counters[slot] = self.counters[slot].saturating_add(by);
+            estimate = estimate.min(self.counters[slot]);
+        }
+        self.count = self.count.saturating_add(by);
+        estimate
+    }
+
+    pub fn query(&self, item: &[u8]) -> u64 {
+        (0..self.depth)
+            .map(|row| self.counters[self.slot(item, row)])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Replaces the counters with the weighted sum of `sources`, which
+    /// must all have the same dimensions as this sketch.
+    pub fn merge(&mut self, sources: &[(&CountMinSketch, u64)]) {
+        let mut counters = vec![0u64; self.counters.len()];
+        let mut count = 0u64;
+        for (source, weight) in sources {
+            for (sum, counter) in counters.iter_mut().zip(&source.counters) {
+                *sum = sum.saturating_add(counter.saturating_mul(*weight));
+            }
+            count = count.saturating_add(source.count.saturating_mul(*weight));
+        }
+        self.counters = counters;
+        self.count = count;
+    }
+
+    /// Heap bytes `new` allocates for these dimensions.
+    pub fn footprint(width: usize, depth: usize) -> usize {
+        width * depth * size_of::<u64>()
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.counters.capacity() * size_of::<u64>()
+    }
+
+    fn slot(&self, item: &[u8], row: usize) -> usize {
+        row * self.width + (murmur_hash64a(item, row as u64) % self.width as u64) as usize
+    }
+}
@@ -1,18 +1,92 @@
+use super::{
+    Aggregate, BitFieldOp, BitFieldType, BitOp, DuplicatePolicy, ExpireCondition, GeoSearch,
+    GeoUnit, Json, JsonPath, LabelFilter, ScoreBound, SetExpiry, SetOptions, StreamFields,
+    StreamId, StreamIdSpec, StreamReadId, StreamTrim, TimeSeries, TsRange, XClaimOptions,
+    ZAddFlags, ZRangeSpec,
+};
+
 #[derive(Debug)]
 #[rustfmt::skip]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Command {
     PING,
     QUIT,
-    SET { key: String, value: Vec<u8> },
+    SET { key: String, value: Vec<u8>, options: SetOptions },
     SETEX { key: String, value:  Vec<u8>, seconds: u64 },
     PSETEX { key: String, value: Vec<u8>, seconds: u64 },
     GET { key: String },
+    INCR { key: String },
+    DECR { key: String },
+    INCRBY { key: String, increment: i64 },
+    DECRBY { key: String, decrement: i64 },
+    INCRBYFLOAT { key: String, increment: f64 },
+    APPEND { key: String, value: Vec<u8> },
+    STRLEN { key: String },
+    GETRANGE { key: String, start: i64, end: i64 },
+    SETRANGE { key: String, offset: usize, value: Vec<u8> },
+    GETDEL { key: String },
+    GETEX { key: String, expiry: Option<SetExpiry>, persist: bool },
+    GETSET { key: String, value: Vec<u8> },
+    SETNX { key: String, value: Vec<u8> },
+    MGET { keys: Vec<String> },
+    MSET { pairs: Vec<(String, Vec<u8>)> },
+    MSETNX { pairs: Vec<(String, Vec<u8>)> },
+    SETBIT { key: String, offset: u64, value: bool },
+    GETBIT { key: String, offset: u64 },
+    BITCOUNT { key: String, range: Option<(i64, i64)>, bit_unit: bool },
+    BITPOS { key: String, bit: bool, start: i64, end: Option<i64>, bit_unit: bool },
+    BITOP { op: BitOp, destination: String, keys: Vec<String> },
+    BITFIELD { key: String, ops: Vec<BitFieldOp> },
+    #[allow(non_camel_case_types)]
+    BITFIELD_RO { key: String, gets: Vec<(BitFieldType, u64)> },
+    PFADD { key: String, elements: Vec<Vec<u8>> },
+    PFCOUNT { keys: Vec<String> },
+    PFMERGE { destination: String, keys: Vec<String> },
+    GEOADD { key: String, flags: ZAddFlags, points: Vec<(f64, f64, Vec<u8>)> },
+    GEOPOS { key: String, members: Vec<Vec<u8>> },
+    GEOHASH { key: String, members: Vec<Vec<u8>> },
+    GEODIST { key: String, member1: Vec<u8>, member2: Vec<u8>, unit: GeoUnit },
+    GEOSEARCH { key: String, search: GeoSearch },
+    GEOSEARCHSTORE { destination: String, source: String, search: GeoSearch, store_dist: bool },
+    JsonSet { key: String, path: JsonPath, value: Json, nx: bool, xx: bool },
+    JsonGet { key: String, paths: Vec<JsonPath> },
+    JsonDel { key: String, path: JsonPath },
+    JsonType { key: String, path: JsonPath },
+    JsonNumIncrBy { key: String, path: JsonPath, by: Json },
+    JsonArrAppend { key: String, path: JsonPath, values: Vec<Json> },
+    JsonStrLen { key: String, path: JsonPath },
+    JsonObjKeys { key: String, path: JsonPath },
+    BfReserve { key: String, error_rate: f64, capacity: u64, expansion: Option<u32> },
+    BfAdd { key: String, item: Vec<u8> },
+    BfMAdd { key: String, items: Vec<Vec<u8>> },
+    BfExists { key: String, item: Vec<u8> },
+    BfMExists { key: String, items: Vec<Vec<u8>> },
+    BfInfo { key: String },
+    CfAdd { key: String, item: Vec<u8> },
+    CfDel { key: String, item: Vec<u8> },
+    CfExists { key: String, item: Vec<u8> },
+    CmsInitByDim { key: String, width: usize, depth: usize },
+    CmsInitByProb { key: String, error: f64, probability: f64 },
+    CmsIncrBy { key: String, increments: Vec<(Vec<u8>, u64)> },
+    CmsQuery { key: String, items: Vec<Vec<u8>> },
+    CmsMerge { destination: String, sources: Vec<String>, weights: Vec<u64> },
+    TopKReserve { key: String, k: usize, width: usize, depth: usize, decay: f64 },
+    TopKAdd { key: String, items: Vec<Vec<u8>> },
+    TopKQuery { key: String, items: Vec<Vec<u8>> },
+    TopKCount { key: String, items: Vec<Vec<u8>> },
+    TopKList { key: String, with_count: bool },
+    TsCreate { key: String, series: TimeSeries },
+    TsAdd { key: String, timestamp: Option<u64>, value: f64, on_duplicate: Option<DuplicatePolicy>, series: TimeSeries },
+    TsMAdd { samples: Vec<(String, Option<u64>, f64)> },
+    TsRange { key: String, range: TsRange },
+    TsMRange { range: TsRange, with_labels: bool, filters: Vec<LabelFilter> },
     DEL { keys: Vec<String> },
     EXISTS { keys: Vec<String> },
-    EXPIRE { key: String, seconds: u64 },
+    EXPIRE { key: String, expiry: SetExpiry, conditions: Vec<ExpireCondition> },
     PERSIST { key: String },
     TTL { key: String },
     PTTL { key: String },
+    EXPIRETIME { key: String, millis: bool },
     TYPE { key: String },
     INFO { section: Option<String> },
     HELLO { version: Option<u8> },
@@ -22,8 +96,108 @@ pub enum Command {
     ConfigSet { key: String, value: String },
     LPUSH { key: String, values: Vec<Vec<u8>> },
     RPUSH { key: String, values: Vec<Vec<u8>> },
-    LPOP { key: String },
-    RPOP { key: String },
+    LPOP { key: String, count: Option<usize> },
+    RPOP { key: String, count: Option<usize> },
+    LPUSHX { key: String, values: Vec<Vec<u8>> },
+    RPUSHX { key: String, values: Vec<Vec<u8>> },
+    LRANGE { key: String, start: i64, stop: i64 },
+    LINDEX { key: String, index: i64 },
+    LLEN { key: String },
+    LSET { key: String, index: i64, value: Vec<u8> },
+    LINSERT { key: String, before: bool, pivot: Vec<u8>, value: Vec<u8> },
+    LREM { key: String, count: i64, value: Vec<u8> },
+    LTRIM { key: String, start: i64, stop: i64 },
+    LPOS { key: String, element: Vec<u8>, rank: i64, count: Option<usize>, maxlen: usize },
+    LMOVE { source: String, destination: String, from_left: bool, to_left: bool },
+    RPOPLPUSH { source: String, destination: String },
+    LMPOP { keys: Vec<String>, left: bool, count: usize },
+    BLPOP { keys: Vec<String>, timeout: u64 },
+    BRPOP { keys: Vec<String>, timeout: u64 },
+    BLMOVE { source: String, destination: String, from_left: bool, to_left: bool, timeout: u64 },
+    BLMPOP { keys: Vec<String>, left: bool, count: usize, timeout: u64 },
+    HSET { key: String, pairs: Vec<(Vec<u8>, Vec<u8>)> },
+    HSETNX { key: String, field: Vec<u8>, value: Vec<u8> },
+    HGET { key: String, field: Vec<u8> },
+    HMGET { key: String, fields: Vec<Vec<u8>> },
+    HDEL { key: String, fields: Vec<Vec<u8>> },
+    HEXISTS { key: String, field: Vec<u8> },
+    HLEN { key: String },
+    HSTRLEN { key: String, field: Vec<u8> },
+    HKEYS { key: String },
+    HVALS { key: String },
+    HGETALL { key: String },
+    HINCRBY { key: String, field: Vec<u8>, increment: i64 },
+    HINCRBYFLOAT { key: String, field: Vec<u8>, increment: f64 },
+    HRANDFIELD { key: String, count: Option<i64>, with_values: bool },
+    HSCAN { key: String, cursor: u64, pattern: Option<Vec<u8>>, count: usize, no_values: bool },
+    HEXPIRE { key: String, expiry: SetExpiry, condition: Option<ExpireCondition>, fields: Vec<Vec<u8>> },
+    HTTL { key: String, fields: Vec<Vec<u8>>, millis: bool },
+    HPERSIST { key: String, fields: Vec<Vec<u8>> },
+    SCAN { cursor: u64, pattern: Option<Vec<u8>>, count: usize, type_name: Option<String> },
+    SSCAN { key: String, cursor: u64, pattern: Option<Vec<u8>>, count: usize },
+    ZSCAN { key: String, cursor: u64, pattern: Option<Vec<u8>>, count: usize },
+    KEYS { pattern: Vec<u8> },
+    RENAME { source: String, destination: String, nx: bool },
+    COPY { source: String, destination: String, replace: bool },
+    RANDOMKEY,
+    DBSIZE,
+    TOUCH { keys: Vec<String> },
+    SELECT { index: i64 },
+    MOVE { key: String, db: i64 },
+    SWAPDB { first: i64, second: i64 },
+    FLUSHDB { lazy: bool },
+    FLUSHALL { lazy: bool },
+    UNLINK { keys: Vec<String> },
+    SADD { key: String, members: Vec<Vec<u8>> },
+    SREM { key: String, members: Vec<Vec<u8>> },
+    SISMEMBER { key: String, member: Vec<u8> },
+    SMISMEMBER { key: String, members: Vec<Vec<u8>> },
+    SMEMBERS { key: String },
+    SCARD { key: String },
+    SPOP { key: String, count: Option<usize> },
+    SRANDMEMBER { key: String, count: Option<i64> },
+    SMOVE { source: String, destination: String, member: Vec<u8> },
+    SINTER { keys: Vec<String> },
+    SUNION { keys: Vec<String> },
+    SDIFF { keys: Vec<String> },
+    SINTERSTORE { destination: String, keys: Vec<String> },
+    SUNIONSTORE { destination: String, keys: Vec<String> },
+    SDIFFSTORE { destination: String, keys: Vec<String> },
+    SINTERCARD { keys: Vec<String>, limit: usize },
+    ZADD { key: String, flags: ZAddFlags, pairs: Vec<(f64, Vec<u8>)> },
+    ZREM { key: String, members: Vec<Vec<u8>> },
+    ZSCORE { key: String, member: Vec<u8> },
+    ZINCRBY { key: String, increment: f64, member: Vec<u8> },
+    ZCARD { key: String },
+    ZCOUNT { key: String, min: ScoreBound, max: ScoreBound },
+    ZRANK { key: String, member: Vec<u8>, with_score: bool },
+    ZREVRANK { key: String, member: Vec<u8>, with_score: bool },
+    ZRANGE { key: String, spec: ZRangeSpec },
+    ZRANGESTORE { destination: String, source: String, spec: ZRangeSpec },
+    ZPOPMIN { key: String, count: Option<usize> },
+    ZPOPMAX { key: String, count: Option<usize> },
+    ZUNIONSTORE { destination: String, keys: Vec<String>, weights: Vec<f64>, aggregate: Aggregate },
+    ZINTERSTORE { destination: String, keys: Vec<String>, weights: Vec<f64>, aggregate: Aggregate },
+    XADD { key: String, nomkstream: bool, trim: Option<StreamTrim>, id: StreamIdSpec, fields: StreamFields },
+    XRANGE { key: String, start: StreamId, end: StreamId, count: Option<usize> },
+    XREVRANGE { key: String, start: StreamId, end: StreamId, count: Option<usize> },
+    XLEN { key: String },
+    XDEL { key: String, ids: Vec<StreamId> },
+    XTRIM { key: String, trim: StreamTrim },
+    XREAD { keys: Vec<String>, ids: Vec<StreamReadId>, count: Option<usize>, block: Option<u64> },
+    XREADGROUP { group: Vec<u8>, consumer: Vec<u8>, keys: Vec<String>, ids: Vec<StreamReadId>, count: Option<usize>, block: Option<u64>, noack: bool },
+    XGroupCreate { key: String, group: Vec<u8>, id: StreamReadId, mkstream: bool, entries_read: Option<u64> },
+    XGroupSetId { key: String, group: Vec<u8>, id: StreamReadId, entries_read: Option<u64> },
+    XGroupDestroy { key: String, group: Vec<u8> },
+    XGroupCreateConsumer { key: String, group: Vec<u8>, consumer: Vec<u8> },
+    XGroupDelConsumer { key: String, group: Vec<u8>, consumer: Vec<u8> },
+    XACK { key: String, group: Vec<u8>, ids: Vec<StreamId> },
+    XPENDING { key: String, group: Vec<u8>, range: Option<(StreamId, StreamId, usize)>, idle: Option<u64>, consumer: Option<Vec<u8>> },
+    XCLAIM { key: String, group: Vec<u8>, consumer: Vec<u8>, min_idle: u64, ids: Vec<StreamId>, options: XClaimOptions },
+    XAUTOCLAIM { key: String, group: Vec<u8>, consumer: Vec<u8>, min_idle: u64, start: StreamId, count: usize, justid: bool },
+    XInfoStream { key: String },
+    XInfoGroups { key: String },
+    XInfoConsumers { key: String, group: Vec<u8> },
 }
 
 pub struct CommandInfo<'a> {
@@ -0,0 +1,146 @@
+use super::hyperloglog::murmur_hash64a;
+use crate::util::random_index;
+
+const CF_DEFAULT_CAPACITY: u64 = 1024;
+const BUCKET_SIZE: usize = 2;
+const MAX_ITERATIONS: usize = 20;
+const HASH_SEED: u64 = 0x5bd1_e995;
+/// Fingerprint value marking an empty slot.
+const EMPTY: u8 = 0;
+
+/// A cuckoo filter of 8-bit fingerprints in buckets of two. Unlike a Bloom
+/// filter it supports deletion, and the same item may be added more than
+/// once. When a fingerprint cannot be placed in the newest table, another
+/// table of the same size is chained on.
+#[derive(Debug, Clone)]
+pub struct CuckooFilter {
+    tables: Vec<Vec<[u8; BUCKET_SIZE]>>,
+    buckets: usize,
+}
+
+impl Default for CuckooFilter {
+    fn default() -> Self {
+        CuckooFilter::new(CF_DEFAULT_CAPACITY)
+    }
+}
+
+impl CuckooFilter {
+    pub fn new(capacity: u64) -> Self {
+        let buckets = (capacity as usize)
+            .div_ceil(BUCKET_SIZE)
+            .next_power_of_two();
+        CuckooFilter {
+            tables: vec![vec![[EMPTY; BUCKET_SIZE]; buckets]],
+            buckets,
+        }
+    }
+
+    pub fn add(&mut self, item: &[u8]) {
+        let (fingerprint, i1) = self.locate(item);
+        let i2 = alt_index(i1, fingerprint, self.buckets);
+        if let Some(table) = self.tables.last_mut()
+            && cuckoo_insert(table, fingerprint, i1, i2, self.buckets)
+        {
+            return;
+        }
+
+        let mut table = vec![[EMPTY; BUCKET_SIZE]; self.buckets];
+        table[i1][0] = fingerprint;
+        self.tables.push(table);
+    }
+
+    pub fn contains(&self, item: &[u8]) -> bool {
+        let (fingerprint, i1) = self.locate(item);
+        let i2 = alt_index(i1, fingerprint, self.buckets);
+        self.tables
+            .iter()
+            .any(|table| table[i1].contains(&fingerprint) || table[i2].contains(&fingerprint))
+    }
+
+    /// Removes one copy of the item's fingerprint, newest table first.
+    pub fn delete(&mut self, item: &[u8]) -> bool {
+        let (fingerprint, i1) = self.locate(item);
+        let i2 = alt_index(i1, fingerprint, self.buckets);
+        for table in self.tables.iter_mut().rev() {
+            for index in [i1, i2] {
+                if let Some(slot) = table[index].iter_mut().find(|slot| **slot == fingerprint) {
+                    *slot = EMPTY;
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Number of chained tables, for undoing growth with `truncate`.
+    pub fn table_count(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Drops the tables added after the first `len`.
+    pub fn truncate(&mut self, len: usize) {
+        self.tables.truncate(len.max(1));
+    }
+
+    /// Heap bytes held by the tables.
+    pub fn bytes(&self) -> usize {
+        self.tables.capacity() * size_of::<Vec<[u8; BUCKET_SIZE]>>()
+            + self
+                .tables
+                .iter()
+                .map(|table| table.capacity() * BUCKET_SIZE)
+                .sum::<usize>()
+    }
+
+    /// The item's non-zero fingerprint and primary bucket.
+    fn locate(&self, item: &[u8]) -> (u8, usize) {
+        let hash = murmur_hash64a(item, HASH_SEED);
+        let fingerprint = ((hash >> 32) % 255 + 1) as u8;
+        (fingerprint, hash as usize & (self.buckets - 1))
+    }
+}
+
+/// The other bucket a fingerprint may live in. Applying it twice gives back
+/// the original bucket, which is what lets entries be relocated without
+/// knowing the item.
+fn alt_index(index: usize, fingerprint: u8, buckets: usize) -> usize {
+    (index ^ murmur_hash64a(&[fingerprint], HASH_SEED) as usize) & (buckets - 1)
+}
+
+/// Places a fingerprint in one of its buckets, evicting residents to their
+/// alternate buckets when both are full. Every eviction is undone if no
+/// free slot turns up within `MAX_ITERATIONS`, so a failed insert leaves
+/// the table unchanged.
+fn cuckoo_insert(
+    table: &mut [[u8; BUCKET_SIZE]],
+    fingerprint: u8,
+    i1: usize,
+    i2: usize,
+    buckets: usize,
+) -> bool {
+    for index in [i1, i2] {
+        if let Some(slot) = table[index].iter_mut().find(|slot| **slot == EMPTY) {
+            *slot = fingerprint;
+            return true;
+        }
+    }
+
+    let mut swaps: Vec<(usize, usize)> = Vec::new();
+    let mut index = if random_index(2) == 0 { i1 } else { i2 };
+    let mut homeless = fingerprint;
+    for _ in 0..MAX_ITERATIONS {
+        let slot = random_index(BUCKET_SIZE);
+        std::mem::swap(&mut homeless, &mut table[index][slot]);
+        swaps.push((index, slot));
+        index = alt_index(index, homeless, buckets);
+        if let Some(free) = table[index].iter_mut().find(|slot| **slot == EMPTY) {
+            *free = homeless;
+            return true;
+        }
+    }
+
+    for (index, slot) in swaps.into_iter().rev() {
+        std::mem::swap(&mut homeless, &mut table[index][slot]);
+    }
+    false
+}
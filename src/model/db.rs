@@ -1,25 +1,71 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    borrow::Cow,
+    collections::{HashSet, VecDeque},
     sync::Arc,
-    time::Instant,
 };
 use tokio::sync::RwLock;
 
-#[derive(Debug)]
+use super::{
+    BloomFilter, CountMinSketch, CuckooFilter, Hash, Json, Keyspace, Stream, TimeSeries, TopK, ZSet,
+};
+
+#[derive(Debug, Clone)]
 pub enum Value {
     String(Vec<u8>),
+    /// A string holding a canonical 64-bit integer, as left behind by INCR
+    /// and friends so counters skip re-parsing.
+    Int(i64),
     List(VecDeque<Vec<u8>>),
+    Hash(Hash),
+    Set(HashSet<Vec<u8>>),
+    ZSet(ZSet),
+    Stream(Stream),
+    Json(Json),
+    Bloom(BloomFilter),
+    Cuckoo(CuckooFilter),
+    Cms(CountMinSketch),
+    TopK(TopK),
+    TimeSeries(TimeSeries),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Entry {
     pub value: Value,
-    pub expires_at: Option<Instant>,
+    /// When the key expires, as a Unix time in milliseconds.
+    pub expires_at: Option<u64>,
 }
 
-pub type DB = Arc<RwLock<HashMap<String, Entry>>>;
+pub type DB = Arc<RwLock<Keyspace>>;
 
 impl Value {
+    /// String contents; integer-encoded values are rendered as decimal text.
+    pub fn as_string_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        match self {
+            Value::String(bytes) => Some(Cow::Borrowed(bytes)),
+            Value::Int(n) => Some(Cow::Owned(n.to_string().into_bytes())),
+            _ => None,
+        }
+    }
+
+    /// Mutable string contents. An integer-encoded value is turned back into
+    /// raw bytes first, since the edit may no longer be a number.
+    pub fn as_string_mut(&mut self) -> Option<&mut Vec<u8>> {
+        if let Value::Int(n) = self {
+            *self = Value::String(n.to_string().into_bytes());
+        }
+        match self {
+            Value::String(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&VecDeque<Vec<u8>>> {
+        match self {
+            Value::List(l) => Some(l),
+            _ => None,
+        }
+    }
+
     pub fn as_list_mut(&mut self) -> Option<&mut VecDeque<Vec<u8>>> {
         match self {
             Value::List(l) => Some(l),
@@ -27,6 +73,164 @@ impl Value {
         }
     }
 
+    pub fn as_hash(&self) -> Option<&Hash> {
+        match self {
+            Value::Hash(h) => Some(h),
+            _ => None,
+        }
+    }
+
+    pub fn as_hash_mut(&mut self) -> Option<&mut Hash> {
+        match self {
+            Value::Hash(h) => Some(h),
+            _ => None,
+        }
+    }
+
+    pub fn as_set(&self) -> Option<&HashSet<Vec<u8>>> {
+        match self {
+            Value::Set(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_set_mut(&mut self) -> Option<&mut HashSet<Vec<u8>>> {
+        match self {
+            Value::Set(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_zset(&self) -> Option<&ZSet> {
+        match self {
+            Value::ZSet(z) => Some(z),
+            _ => None,
+        }
+    }
+
+    pub fn as_zset_mut(&mut self) -> Option<&mut ZSet> {
+        match self {
+            Value::ZSet(z) => Some(z),
+            _ => None,
+        }
+    }
+
+    pub fn as_stream(&self) -> Option<&Stream> {
+        match self {
+            Value::Stream(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_stream_mut(&mut self) -> Option<&mut Stream> {
+        match self {
+            Value::Stream(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_json(&self) -> Option<&Json> {
+        match self {
+            Value::Json(j) => Some(j),
+            _ => None,
+        }
+    }
+
+    pub fn as_json_mut(&mut self) -> Option<&mut Json> {
+        match self {
+            Value::Json(j) => Some(j),
+            _ => None,
+        }
+    }
+
+    pub fn as_bloom(&self) -> Option<&BloomFilter> {
+        match self {
+            Value::Bloom(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_bloom_mut(&mut self) -> Option<&mut BloomFilter> {
+        match self {
+            Value::Bloom(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_cuckoo(&self) -> Option<&CuckooFilter> {
+        match self {
+            Value::Cuckoo(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    pub fn as_cuckoo_mut(&mut self) -> Option<&mut CuckooFilter> {
+        match self {
+            Value::Cuckoo(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    pub fn as_cms(&self) -> Option<&CountMinSketch> {
+        match self {
+            Value::Cms(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    pub fn as_cms_mut(&mut self) -> Option<&mut CountMinSketch> {
+        match self {
+            Value::Cms(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    pub fn as_topk(&self) -> Option<&TopK> {
+        match self {
+            Value::TopK(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    pub fn as_topk_mut(&mut self) -> Option<&mut TopK> {
+        match self {
+            Value::TopK(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    pub fn as_timeseries(&self) -> Option<&TimeSeries> {
+        match self {
+            Value::TimeSeries(ts) => Some(ts),
+            _ => None,
+        }
+    }
+
+    pub fn as_timeseries_mut(&mut self) -> Option<&mut TimeSeries> {
+        match self {
+            Value::TimeSeries(ts) => Some(ts),
+            _ => None,
+        }
+    }
+
+    /// The name TYPE reports and SCAN's TYPE option matches against.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) | Value::Int(_) => "string",
+            Value::List(_) => "list",
+            Value::Hash(_) => "hash",
+            Value::Set(_) => "set",
+            Value::ZSet(_) => "zset",
+            Value::Stream(_) => "stream",
+            Value::Json(_) => "ReJSON-RL",
+            Value::Bloom(_) => "MBbloom--",
+            Value::Cuckoo(_) => "MBbloomCF",
+            Value::Cms(_) => "CMSk-TYPE",
+            Value::TopK(_) => "TopK-TYPE",
+            Value::TimeSeries(_) => "TSDB-TYPE",
+        }
+    }
+
     pub fn to_resp_bytes(&self) -> Vec<u8> {
         match self {
             Value::String(bytes) => {
@@ -39,7 +243,20 @@ impl Value {
                 resp
             }
 
-            Value::List(_) => b"*0\r\n".to_vec(),
+            Value::Int(n) => crate::util::bulk_str(&n.to_string()),
+
+            // Types with no plain RESP rendering answer GET with WRONGTYPE.
+            Value::List(_)
+            | Value::Hash(_)
+            | Value::Set(_)
+            | Value::ZSet(_)
+            | Value::Stream(_)
+            | Value::Json(_)
+            | Value::Bloom(_)
+            | Value::Cuckoo(_)
+            | Value::Cms(_)
+            | Value::TopK(_)
+            | Value::TimeSeries(_) => crate::util::WRONGTYPE_ERR.to_vec(),
         }
     }
 }
@@ -0,0 +1,162 @@
+/// Precision of the geohash stored as a sorted set score: 26 bits per axis,
+/// 52 bits in total, which a `f64` holds exactly.
+const GEO_STEP: u32 = 26;
+const GEO_LON_MIN: f64 = -180.0;
+const GEO_LON_MAX: f64 = 180.0;
+const GEO_LAT_MIN: f64 = -85.051_128_78;
+const GEO_LAT_MAX: f64 = 85.051_128_78;
+const EARTH_RADIUS_M: f64 = 6_372_797.560_856;
+const GEOHASH_ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+#[derive(Debug, Clone, Copy)]
+pub enum GeoUnit {
+    M,
+    Km,
+    Ft,
+    Mi,
+}
+
+impl GeoUnit {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.to_lowercase().as_str() {
+            "m" => Some(GeoUnit::M),
+            "km" => Some(GeoUnit::Km),
+            "ft" => Some(GeoUnit::Ft),
+            "mi" => Some(GeoUnit::Mi),
+            _ => None,
+        }
+    }
+
+    pub fn meters(&self) -> f64 {
+        match self {
+            GeoUnit::M => 1.0,
+            GeoUnit::Km => 1000.0,
+            GeoUnit::Ft => 0.3048,
+            GeoUnit::Mi => 1609.34,
+        }
+    }
+}
+
+/// Search centre for GEOSEARCH.
+#[derive(Debug, Clone)]
+pub enum GeoFrom {
+    Member(Vec<u8>),
+    LonLat(f64, f64),
+}
+
+/// Search area for GEOSEARCH, in meters.
+#[derive(Debug, Clone, Copy)]
+pub enum GeoShape {
+    Radius(f64),
+    Box { width: f64, height: f64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct GeoSearch {
+    pub from: GeoFrom,
+    pub shape: GeoShape,
+    pub unit: GeoUnit,
+    /// `Some(true)` for ASC, `Some(false)` for DESC.
+    pub asc: Option<bool>,
+    pub count: Option<usize>,
+    pub any: bool,
+    pub with_coord: bool,
+    pub with_dist: bool,
+    pub with_hash: bool,
+}
+
+impl GeoShape {
+    /// Returns the distance from `center` to `point` if the point lies
+    /// within the shape.
+    pub fn distance_within(&self, center: (f64, f64), point: (f64, f64)) -> Option<f64> {
+        match *self {
+            GeoShape::Radius(radius) => {
+                let distance = geo_distance(center, point);
+                (distance <= radius).then_some(distance)
+            }
+            GeoShape::Box { width, height } => {
+                let lat_distance =
+                    EARTH_RADIUS_M * (point.1.to_radians() - center.1.to_radians()).abs();
+                if lat_distance > height / 2.0 {
+                    return None;
+                }
+                // Measured along the point's latitude, as Redis does.
+                let lon_distance = geo_distance((center.0, point.1), point);
+                if lon_distance > width / 2.0 {
+                    return None;
+                }
+                Some(geo_distance(center, point))
+            }
+        }
+    }
+}
+
+/// Whether the pair is within the range a geohash score can encode.
+pub fn geo_valid(lon: f64, lat: f64) -> bool {
+    (GEO_LON_MIN..=GEO_LON_MAX).contains(&lon) && (GEO_LAT_MIN..=GEO_LAT_MAX).contains(&lat)
+}
+
+/// Encodes a position as the 52-bit interleaved geohash used as the score.
+pub fn geohash_encode(lon: f64, lat: f64) -> u64 {
+    encode_bits(lon, lat, (GEO_LAT_MIN, GEO_LAT_MAX))
+}
+
+/// Decodes a score back to the centre of its geohash cell.
+pub fn geohash_decode(bits: u64) -> (f64, f64) {
+    let lat_cells = squash(bits);
+    let lon_cells = squash(bits >> 1);
+    let cells = (1u64 << GEO_STEP) as f64;
+    let cell_center = |cell: u64, min: f64, max: f64| {
+        let low = min + (cell as f64 / cells) * (max - min);
+        let high = min + ((cell + 1) as f64 / cells) * (max - min);
+        ((low + high) / 2.0).clamp(min, max)
+    };
+    (
+        cell_center(lon_cells, GEO_LON_MIN, GEO_LON_MAX),
+        cell_center(lat_cells, GEO_LAT_MIN, GEO_LAT_MAX),
+    )
+}
+
+/// The standard 11 character base32 geohash. It is computed over the usual
+/// -90..90 latitude range rather than the Mercator-limited one the scores use.
+pub fn geohash_string(lon: f64, lat: f64) -> String {
+    let bits = encode_bits(lon, lat, (-90.0, 90.0));
+    (0..11)
+        .map(|i| {
+            // Only 52 bits exist, so the last character is padded with zeros.
+            let idx = if i == 10 {
+                0
+            } else {
+                (bits >> (52 - (i + 1) * 5)) & 0x1f
+            };
+            GEOHASH_ALPHABET[idx as usize] as char
+        })
+        .collect()
+}
+
+/// Great-circle distance in meters between two `(lon, lat)` points.
+pub fn geo_distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lon1, lat1) = (from.0.to_radians(), from.1.to_radians());
+    let (lon2, lat2) = (to.0.to_radians(), to.1.to_radians());
+    let u = ((lat2 - lat1) / 2.0).sin();
+    let v = ((lon2 - lon1) / 2.0).sin();
+    2.0 * EARTH_RADIUS_M * (u * u + lat1.cos() * lat2.cos() * v * v).sqrt().asin()
+}
+
+/// Interleaves the latitude (even bits) and longitude (odd bits) cells.
+fn encode_bits(lon: f64, lat: f64, (lat_min, lat_max): (f64, f64)) -> u64 {
+    let cells = (1u64 << GEO_STEP) as f64;
+    let lat_cell = ((lat - lat_min) / (lat_max - lat_min) * cells) as u64;
+    let lon_cell = ((lon - GEO_LON_MIN) / (GEO_LON_MAX - GEO_LON_MIN) * cells) as u64;
+    spread(lat_cell.min((1 << GEO_STEP) - 1)) | spread(lon_cell.min((1 << GEO_STEP) - 1)) << 1
+}
+
+/// Moves bit `i` of `x` to bit `2 * i`.
+fn spread(x: u64) -> u64 {
+    (0..GEO_STEP).fold(0, |acc, i| acc | ((x >> i) & 1) << (2 * i))
+}
+
+/// Inverse of `spread`: collects the even bits of `x`.
+fn squash(x: u64) -> u64 {
+    (0..GEO_STEP).fold(0, |acc, i| acc | ((x >> (2 * i)) & 1) << i)
+}
@@ -0,0 +1,141 @@
+use super::unix_millis;
+use std::collections::HashMap;
+
+/// A hash whose fields may carry their own deadlines, as set by HEXPIRE,
+/// held as Unix times in milliseconds.
+/// Fields past their deadline are hidden from every accessor straight away
+/// and physically dropped by `purge_expired`, which the background cleaner
+/// runs when the field's heap record comes due.
+#[derive(Debug, Clone, Default)]
+pub struct Hash {
+    fields: HashMap<Vec<u8>, Vec<u8>>,
+    expires: HashMap<Vec<u8>, u64>,
+}
+
+impl Hash {
+    fn is_live(&self, field: &[u8], now: u64) -> bool {
+        self.expires.get(field).is_none_or(|t| *t > now)
+    }
+
+    pub fn get(&self, field: &[u8]) -> Option<&Vec<u8>> {
+        let value = self.fields.get(field)?;
+        self.is_live(field, unix_millis()).then_some(value)
+    }
+
+    pub fn contains_key(&self, field: &[u8]) -> bool {
+        self.get(field).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        let now = unix_millis();
+        self.fields.len() - self.expires.values().filter(|t| **t <= now).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Vec<u8>, &Vec<u8>)> {
+        let now = unix_millis();
+        self.fields
+            .iter()
+            .filter(move |(field, _)| self.is_live(field, now))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Vec<u8>> {
+        self.iter().map(|(field, _)| field)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Vec<u8>> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Sets `field`, clearing any deadline it had. Returns the previous
+    /// value if the field was live.
+    pub fn insert(&mut self, field: Vec<u8>, value: Vec<u8>) -> Option<Vec<u8>> {
+        let live = self.is_live(&field, unix_millis());
+        self.expires.remove(&field);
+        let old = self.fields.insert(field, value);
+        old.filter(|_| live)
+    }
+
+    /// Overwrites `field` in place, keeping its deadline if it is live, as
+    /// the increment commands do. Returns the previous value if the field
+    /// was live.
+    pub fn update(&mut self, field: Vec<u8>, value: Vec<u8>) -> Option<Vec<u8>> {
+        if !self.is_live(&field, unix_millis()) {
+            return self.insert(field, value);
+        }
+        self.fields.insert(field, value)
+    }
+
+    /// Removes `field`, returning its value if it was live.
+    pub fn remove(&mut self, field: &[u8]) -> Option<Vec<u8>> {
+        let live = self.is_live(field, unix_millis());
+        self.expires.remove(field);
+        let old = self.fields.remove(field);
+        old.filter(|_| live)
+    }
+
+    /// The deadline of a live field: `None` if there is no such field,
+    /// `Some(None)` if it never expires.
+    pub fn expires_at(&self, field: &[u8]) -> Option<Option<u64>> {
+        self.get(field)?;
+        Some(self.expires.get(field).copied())
+    }
+
+    /// Gives a live field a deadline. Returns false if there is no such
+    /// field.
+    pub fn set_expires_at(&mut self, field: &[u8], expires_at: u64) -> bool {
+        if !self.contains_key(field) {
+            return false;
+        }
+        self.expires.insert(field.to_vec(), expires_at);
+        true
+    }
+
+    /// Clears a live field's deadline. Returns false if it had none.
+    pub fn persist(&mut self, field: &[u8]) -> bool {
+        self.contains_key(field) && self.expires.remove(field).is_some()
+    }
+
+    /// Whether the hash has fields but every one of them has expired, in
+    /// which case the key as a whole counts as expired.
+    pub fn all_expired(&self) -> bool {
+        !self.fields.is_empty() && self.expires.len() == self.fields.len() && self.is_empty()
+    }
+
+    /// Every field deadline, expired or not, for re-registering the fields
+    /// with the heap when the hash moves to another key.
+    pub fn deadlines(&self) -> impl Iterator<Item = u64> {
+        self.expires.values().copied()
+    }
+
+    /// Drops expired fields and returns how many went.
+    pub fn purge_expired(&mut self) -> usize {
+        let now = unix_millis();
+        let expired: Vec<Vec<u8>> = self
+            .expires
+            .iter()
+            .filter(|(_, t)| **t <= now)
+            .map(|(field, _)| field.clone())
+            .collect();
+        for field in &expired {
+            self.expires.remove(field);
+            self.fields.remove(field);
+        }
+        expired.len()
+    }
+
+    pub fn bytes(&self) -> usize {
+        let mut total = self.fields.capacity() * (size_of::<(Vec<u8>, Vec<u8>)>() + 1);
+        for (field, value) in &self.fields {
+            total += field.capacity() + value.capacity();
+        }
+        total += self.expires.capacity() * (size_of::<(Vec<u8>, u64)>() + 1);
+        for field in self.expires.keys() {
+            total += field.capacity();
+        }
+        total
+    }
+}
@@ -0,0 +1,290 @@
+const P: u32 = 14;
+const Q: u32 = 64 - P;
+const REGISTERS: usize = 1 << P;
+const HEADER_LEN: usize = 16;
+const DENSE_LEN: usize = HEADER_LEN + (REGISTERS * 6).div_ceil(8);
+const MAGIC: &[u8] = b"HYLL";
+const DENSE: u8 = 0;
+const SPARSE: u8 = 1;
+/// Largest value a sparse VAL opcode can hold.
+const SPARSE_MAX_VALUE: u8 = 32;
+/// Sparse representations growing past this switch to dense, matching
+/// Redis' default `hll-sparse-max-bytes`.
+const SPARSE_MAX_BYTES: usize = 3000;
+const HASH_SEED: u64 = 0xadc8_3b19;
+const ALPHA_INF: f64 = 0.721_347_520_444_481_7;
+
+/// Decoded registers of one HyperLogLog. It is stored as a Redis-compatible
+/// string: a 16 byte `HYLL` header followed by either the sparse run-length
+/// encoding or 16384 packed 6-bit registers. The standard error is
+/// 1.04 / sqrt(16384), about 0.81%.
+#[derive(Debug, Clone)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+    dense: bool,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        HyperLogLog {
+            registers: vec![0; REGISTERS],
+            dense: false,
+        }
+    }
+}
+
+impl HyperLogLog {
+    /// Decodes a stored string, or returns `None` if it is not a valid HLL.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+            return None;
+        }
+        let body = &bytes[HEADER_LEN..];
+        match bytes[4] {
+            DENSE if bytes.len() == DENSE_LEN => {
+                let registers = (0..REGISTERS).map(|i| dense_get(body, i)).collect();
+                Some(HyperLogLog {
+                    registers,
+                    dense: true,
+                })
+            }
+            SPARSE => Some(HyperLogLog {
+                registers: sparse_decode(body)?,
+                dense: false,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Encodes the registers with an invalidated cardinality cache. Stays
+    /// sparse while every register fits a VAL opcode and the encoding is
+    /// within `SPARSE_MAX_BYTES`; once dense, it never goes back.
+    pub fn encode(&self) -> Vec<u8> {
+        if !self.dense
+            && let Some(body) = sparse_encode(&self.registers)
+        {
+            return with_header(SPARSE, &body);
+        }
+
+        let mut body = vec![0u8; DENSE_LEN - HEADER_LEN];
+        for (i, value) in self.registers.iter().enumerate() {
+            dense_set(&mut body, i, *value);
+        }
+        with_header(DENSE, &body)
+    }
+
+    /// Adds an element and reports whether any register changed.
+    pub fn add(&mut self, element: &[u8]) -> bool {
+        let hash = murmur_hash64a(element, HASH_SEED);
+        let index = (hash & (REGISTERS as u64 - 1)) as usize;
+        let rank = ((hash >> P) | (1 << Q)).trailing_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Takes the register-wise maximum with `other`.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        for (mine, theirs) in self.registers.iter_mut().zip(&other.registers) {
+            *mine = (*mine).max(*theirs);
+        }
+        self.dense |= other.dense;
+    }
+
+    /// Estimates the cardinality with the improved estimator from Ertl's
+    /// "New cardinality estimation algorithms for HyperLogLog sketches", the
+    /// same one Redis uses.
+    pub fn count(&self) -> u64 {
+        let m = REGISTERS as f64;
+        let mut histogram = [0u32; Q as usize + 2];
+        for value in &self.registers {
+            histogram[*value as usize] += 1;
+        }
+
+        let mut z = m * tau((m - histogram[Q as usize + 1] as f64) / m);
+        for j in (1..=Q as usize).rev() {
+            z += histogram[j] as f64;
+            z *= 0.5;
+        }
+        z += m * sigma(histogram[0] as f64 / m);
+        (ALPHA_INF * m * m / z).round() as u64
+    }
+}
+
+/// Returns the cached cardinality from a stored HLL's header, if valid.
+pub fn hll_cached_count(bytes: &[u8]) -> Option<u64> {
+    let valid = bytes.len() >= HEADER_LEN
+        && &bytes[..4] == MAGIC
+        && (bytes[4] == SPARSE || (bytes[4] == DENSE && bytes.len() == DENSE_LEN));
+    if !valid {
+        return None;
+    }
+    let card: [u8; 8] = bytes.get(8..HEADER_LEN)?.try_into().ok()?;
+    (card[7] & 0x80 == 0).then(|| u64::from_le_bytes(card))
+}
+
+/// Stores `count` in the header's cardinality cache.
+pub fn hll_set_cached_count(bytes: &mut [u8], count: u64) {
+    bytes[8..HEADER_LEN].copy_from_slice(&count.to_le_bytes());
+}
+
+fn with_header(encoding: u8, body: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + body.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&[encoding, 0, 0, 0]);
+    bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0x80]);
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+/// Registers are packed 6 bits each, least significant bits first.
+fn dense_get(body: &[u8], index: usize) -> u8 {
+    let bit = index * 6;
+    let (byte, shift) = (bit / 8, bit % 8);
+    let low = body[byte] as u16 >> shift;
+    let high = body.get(byte + 1).copied().unwrap_or(0) as u16;
+    ((low | high << (8 - shift)) & 0x3f) as u8
+}
+
+fn dense_set(body: &mut [u8], index: usize, value: u8) {
+    let bit = index * 6;
+    let (byte, shift) = (bit / 8, bit % 8);
+    let packed = (value as u16 & 0x3f) << shift;
+    body[byte] = (body[byte] & !(0x3fu16 << shift) as u8) | packed as u8;
+    if let Some(next) = body.get_mut(byte + 1) {
+        *next = (*next & !(0x3fu16 << shift >> 8) as u8) | (packed >> 8) as u8;
+    }
+}
+
+/// Sparse opcodes: `00xxxxxx` is a run of up to 64 zero registers,
+/// `01xxxxxx yyyyyyyy` a run of up to 16384, and `1vvvvvxx` a run of up to
+/// 4 registers holding value `vvvvv + 1`.
+fn sparse_decode(body: &[u8]) -> Option<Vec<u8>> {
+    let mut registers = Vec::with_capacity(REGISTERS);
+    let mut i = 0;
+    while i < body.len() {
+        let op = body[i];
+        if op & 0x80 != 0 {
+            let value = ((op >> 2) & 0x1f) + 1;
+            let run = (op & 0x03) as usize + 1;
+            registers.extend(std::iter::repeat_n(value, run));
+            i += 1;
+        } else if op & 0x40 != 0 {
+            let next = *body.get(i + 1)? as usize;
+            let run = (((op & 0x3f) as usize) << 8 | next) + 1;
+            registers.extend(std::iter::repeat_n(0, run));
+            i += 2;
+        } else {
+            registers.extend(std::iter::repeat_n(0, (op & 0x3f) as usize + 1));
+            i += 1;
+        }
+        if registers.len() > REGISTERS {
+            return None;
+        }
+    }
+    (registers.len() == REGISTERS).then_some(registers)
+}
+
+/// Returns `None` when the registers cannot be represented sparsely.
+fn sparse_encode(registers: &[u8]) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut i = 0;
+    while i < registers.len() {
+        let value = registers[i];
+        let run = registers[i..].iter().take_while(|v| **v == value).count();
+        i += run;
+        if value == 0 {
+            let mut left = run;
+            while left > 0 {
+                let len = left.min(REGISTERS);
+                if len <= 64 {
+                    body.push((len - 1) as u8);
+                } else {
+                    body.push(0x40 | ((len - 1) >> 8) as u8);
+                    body.push(((len - 1) & 0xff) as u8);
+                }
+                left -= len;
+            }
+        } else {
+            if value > SPARSE_MAX_VALUE {
+                return None;
+            }
+            let mut left = run;
+            while left > 0 {
+                let len = left.min(4);
+                body.push(0x80 | (value - 1) << 2 | (len - 1) as u8);
+                left -= len;
+            }
+        }
+        if HEADER_LEN + body.len() > SPARSE_MAX_BYTES {
+            return None;
+        }
+    }
+    Some(body)
+}
+
+fn sigma(mut x: f64) -> f64 {
+    if x == 1.0 {
+        return f64::INFINITY;
+    }
+    let mut y = 1.0;
+    let mut z = x;
+    loop {
+        x *= x;
+        let previous = z;
+        z += x * y;
+        y += y;
+        if z == previous {
+            return z;
+        }
+    }
+}
+
+fn tau(mut x: f64) -> f64 {
+    if x == 0.0 || x == 1.0 {
+        return 0.0;
+    }
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+    loop {
+        x = x.sqrt();
+        let previous = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+        if z == previous {
+            return z / 3.0;
+        }
+    }
+}
+
+/// MurmurHash64A, as used by Redis to place HLL elements.
+pub fn murmur_hash64a(key: &[u8], seed: u64) -> u64 {
+    const M: u64 = 0xc6a4_a793_5bd1_e995;
+    const R: u32 = 47;
+
+    let mut h = seed ^ (key.len() as u64).wrapping_mul(M);
+    let chunks = key.chunks_exact(8);
+    let tail = chunks.remainder();
+    for chunk in chunks {
+        let mut k = u64::from_le_bytes(chunk.try_into().unwrap_or_default());
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h ^= k;
+        h = h.wrapping_mul(M);
+    }
+    if !tail.is_empty() {
+        for (i, byte) in tail.iter().enumerate() {
+            h ^= (*byte as u64) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^= h >> R;
+    h
+}
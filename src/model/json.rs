@@ -0,0 +1,636 @@
+use std::fmt;
+
+/// A JSON document. Objects keep their keys in insertion order, and integers
+/// are kept apart from floats so they round-trip unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+/// One step of a path: an object key, an array index (negative counts from
+/// the end) or a wildcard over every child.
+#[derive(Debug, Clone)]
+pub enum PathStep {
+    Key(String),
+    Index(i64),
+    Wildcard,
+}
+
+/// A parsed path. Paths starting with `$` are JSONPath and answer with every
+/// match; anything else is a legacy path (`.a.b`, `a[0]`) that answers with
+/// a single value.
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    pub raw: String,
+    pub steps: Vec<PathStep>,
+    pub legacy: bool,
+}
+
+impl Json {
+    /// Parses a JSON text, reporting the position of the first error.
+    pub fn parse(text: &[u8]) -> Result<Json, String> {
+        let mut parser = Parser { text, pos: 0 };
+        parser.skip_whitespace();
+        let value = parser.value(0)?;
+        parser.skip_whitespace();
+        if parser.pos < text.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(value)
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Json::Null => "null",
+            Json::Bool(_) => "boolean",
+            Json::Int(_) => "integer",
+            Json::Float(_) => "number",
+            Json::String(_) => "string",
+            Json::Array(_) => "array",
+            Json::Object(_) => "object",
+        }
+    }
+
+    /// Whether `self` can be written `level` containers below the root
+    /// without nesting the document past the limit `parse` enforces.
+    pub fn fits_at(&self, level: usize) -> bool {
+        level + self.depth() <= MAX_DEPTH
+    }
+
+    /// How many containers deep the innermost value sits; scalars and
+    /// empty containers are 0.
+    fn depth(&self) -> usize {
+        let children = match self {
+            Json::Array(items) => items.iter().map(Json::depth).max(),
+            Json::Object(fields) => fields.iter().map(|(_, v)| v.depth()).max(),
+            _ => None,
+        };
+        children.map_or(0, |depth| depth + 1)
+    }
+
+    /// Every value matched by `path`, in document order.
+    pub fn resolve(&self, path: &JsonPath) -> Vec<&Json> {
+        let mut matches = vec![self];
+        for step in &path.steps {
+            let mut next = Vec::new();
+            for node in matches {
+                match (step, node) {
+                    (PathStep::Key(key), Json::Object(fields)) => {
+                        next.extend(fields.iter().filter(|(k, _)| k == key).map(|(_, v)| v));
+                    }
+                    (PathStep::Index(index), Json::Array(items)) => {
+                        next.extend(array_index(items.len(), *index).map(|i| &items[i]));
+                    }
+                    (PathStep::Wildcard, Json::Object(fields)) => {
+                        next.extend(fields.iter().map(|(_, v)| v));
+                    }
+                    (PathStep::Wildcard, Json::Array(items)) => next.extend(items.iter()),
+                    _ => {}
+                }
+            }
+            matches = next;
+        }
+        matches
+    }
+
+    /// Calls `f` on every value matched by `path`, in the same order as
+    /// `resolve`.
+    pub fn visit_mut(&mut self, steps: &[PathStep], f: &mut dyn FnMut(&mut Json)) {
+        let Some((step, rest)) = steps.split_first() else {
+            f(self);
+            return;
+        };
+        match (step, self) {
+            (PathStep::Key(key), Json::Object(fields)) => {
+                if let Some((_, value)) = fields.iter_mut().find(|(k, _)| k == key) {
+                    value.visit_mut(rest, f);
+                }
+            }
+            (PathStep::Index(index), Json::Array(items)) => {
+                if let Some(i) = array_index(items.len(), *index) {
+                    items[i].visit_mut(rest, f);
+                }
+            }
+            (PathStep::Wildcard, Json::Object(fields)) => {
+                for (_, value) in fields {
+                    value.visit_mut(rest, f);
+                }
+            }
+            (PathStep::Wildcard, Json::Array(items)) => {
+                for item in items {
+                    item.visit_mut(rest, f);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Writes `value` at every location `steps` names below this value,
+    /// adding the key when the last step names a missing object key.
+    /// Returns what each write displaced, in visiting order: the old value,
+    /// or `None` where a key was added. Empty means nothing was written.
+    pub fn set(&mut self, steps: &[PathStep], value: &Json) -> Vec<Option<Json>> {
+        let Some((last, parent)) = steps.split_last() else {
+            return vec![Some(std::mem::replace(self, value.clone()))];
+        };
+        let mut displaced = Vec::new();
+        let mut replace = |old: &mut Json| Some(std::mem::replace(old, value.clone()));
+        self.visit_mut(parent, &mut |node| match (last, node) {
+            (PathStep::Key(key), Json::Object(fields)) => {
+                match fields.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, old)) => displaced.push(replace(old)),
+                    None => {
+                        fields.push((key.clone(), value.clone()));
+                        displaced.push(None);
+                    }
+                }
+            }
+            (PathStep::Index(index), Json::Array(items)) => {
+                if let Some(i) = array_index(items.len(), *index) {
+                    displaced.push(replace(&mut items[i]));
+                }
+            }
+            (PathStep::Wildcard, Json::Object(fields)) => {
+                displaced.extend(fields.iter_mut().map(|(_, old)| replace(old)));
+            }
+            (PathStep::Wildcard, Json::Array(items)) => {
+                displaced.extend(items.iter_mut().map(&mut replace));
+            }
+            _ => {}
+        });
+        displaced
+    }
+
+    /// Undoes `set` along the same path, putting back what it displaced and
+    /// dropping the keys it added.
+    pub fn restore(&mut self, steps: &[PathStep], displaced: Vec<Option<Json>>) {
+        let mut displaced = displaced.into_iter();
+        let Some((last, parent)) = steps.split_last() else {
+            if let Some(Some(old)) = displaced.next() {
+                *self = old;
+            }
+            return;
+        };
+        self.visit_mut(parent, &mut |node| match (last, node) {
+            (PathStep::Key(key), Json::Object(fields)) => {
+                let slot = fields.iter().position(|(k, _)| k == key);
+                match (slot, displaced.next()) {
+                    (Some(i), Some(Some(old))) => fields[i].1 = old,
+                    (Some(i), Some(None)) => {
+                        fields.remove(i);
+                    }
+                    _ => {}
+                }
+            }
+            (PathStep::Index(index), Json::Array(items)) => {
+                if let Some(i) = array_index(items.len(), *index)
+                    && let Some(Some(old)) = displaced.next()
+                {
+                    items[i] = old;
+                }
+            }
+            (PathStep::Wildcard, Json::Object(fields)) => {
+                for (_, slot) in fields.iter_mut() {
+                    if let Some(Some(old)) = displaced.next() {
+                        *slot = old;
+                    }
+                }
+            }
+            (PathStep::Wildcard, Json::Array(items)) => {
+                for slot in items.iter_mut() {
+                    if let Some(Some(old)) = displaced.next() {
+                        *slot = old;
+                    }
+                }
+            }
+            _ => {}
+        });
+    }
+
+    /// Removes every value matched by a non-root path and returns how many
+    /// were removed.
+    pub fn delete(&mut self, steps: &[PathStep]) -> usize {
+        let Some((last, parent)) = steps.split_last() else {
+            return 0;
+        };
+        let mut removed = 0;
+        self.visit_mut(parent, &mut |node| match (last, node) {
+            (PathStep::Key(key), Json::Object(fields)) => {
+                let before = fields.len();
+                fields.retain(|(k, _)| k != key);
+                removed += before - fields.len();
+            }
+            (PathStep::Index(index), Json::Array(items)) => {
+                if let Some(i) = array_index(items.len(), *index) {
+                    items.remove(i);
+                    removed += 1;
+                }
+            }
+            (PathStep::Wildcard, Json::Object(fields)) => {
+                removed += fields.len();
+                fields.clear();
+            }
+            (PathStep::Wildcard, Json::Array(items)) => {
+                removed += items.len();
+                items.clear();
+            }
+            _ => {}
+        });
+        removed
+    }
+}
+
+impl JsonPath {
+    /// The legacy root path used when a command's path is omitted.
+    pub fn root() -> Self {
+        JsonPath {
+            raw: ".".to_string(),
+            steps: Vec::new(),
+            legacy: true,
+        }
+    }
+
+    /// Parses `$`, `$.a.b[0]`, `$.*`, `$['a']`, `$.a[*]` and the legacy
+    /// forms `.`, `.a.b` and `a[0]`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (legacy, rest) = match raw.strip_prefix('$') {
+            Some(rest) => (false, rest),
+            None if raw == "." => (true, ""),
+            None => (true, raw),
+        };
+        let rest = if legacy && !rest.starts_with(['.', '[']) && !rest.is_empty() {
+            // `a.b` is shorthand for `.a.b`.
+            format!(".{}", rest)
+        } else {
+            rest.to_string()
+        };
+
+        let bytes = rest.as_bytes();
+        let mut steps = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'.' => {
+                    let start = i + 1;
+                    let end = bytes[start..]
+                        .iter()
+                        .position(|b| matches!(b, b'.' | b'['))
+                        .map_or(bytes.len(), |p| start + p);
+                    let name = &rest[start..end];
+                    match name {
+                        "" => return None,
+                        "*" => steps.push(PathStep::Wildcard),
+                        _ => steps.push(PathStep::Key(name.to_string())),
+                    }
+                    i = end;
+                }
+                b'[' => {
+                    let close = i + rest[i..].find(']')?;
+                    let inner = rest[i + 1..close].trim();
+                    let step = if inner == "*" {
+                        PathStep::Wildcard
+                    } else if let Some(name) = quoted(inner) {
+                        PathStep::Key(name.to_string())
+                    } else {
+                        PathStep::Index(inner.parse().ok()?)
+                    };
+                    steps.push(step);
+                    i = close + 1;
+                }
+                _ => return None,
+            }
+        }
+
+        Some(JsonPath {
+            raw: raw.to_string(),
+            steps,
+            legacy,
+        })
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Compact serialization, as JSON.GET returns it.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Int(n) => write!(f, "{}", n),
+            // Debug keeps the `.0` and switches to exponents for large values.
+            Json::Float(x) => write!(f, "{:?}", x),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str("]")
+            }
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\u{8}' => f.write_str("\\b")?,
+            '\u{c}' => f.write_str("\\f")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+fn quoted(inner: &str) -> Option<&str> {
+    inner
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+}
+
+fn array_index(len: usize, index: i64) -> Option<usize> {
+    let len = len as i64;
+    let index = if index < 0 { len + index } else { index };
+    (0..len).contains(&index).then_some(index as usize)
+}
+
+/// Nesting limit for a whole document, so hostile input cannot overflow the
+/// stack in the recursive walks, `Clone` or `Drop`.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, what: &str) -> String {
+        format!("{} at position {}", what, self.pos)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.text.len()
+            && matches!(self.text[self.pos], b' ' | b'\t' | b'\n' | b'\r')
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn expect_literal(&mut self, literal: &[u8], value: Json) -> Result<Json, String> {
+        if self.text[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("expected value"))
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Json, String> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("recursion limit exceeded"));
+        }
+        match self.peek() {
+            Some(b'n') => self.expect_literal(b"null", Json::Null),
+            Some(b't') => self.expect_literal(b"true", Json::Bool(true)),
+            Some(b'f') => self.expect_literal(b"false", Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => self.array(depth),
+            Some(b'{') => self.object(depth),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => Err(self.error("expected value")),
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Json, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            self.skip_whitespace();
+            items.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Json, String> {
+        self.pos += 1;
+        let mut fields: Vec<(String, Json)> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("key must be a string"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                return Err(self.error("expected `:`"));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let value = self.value(depth + 1)?;
+            // A repeated key keeps the last value, in the first key's place.
+            match fields.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, old)) => *old = value,
+                None => fields.push((key, value)),
+            }
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let mut out: Vec<u8> = Vec::new();
+        loop {
+            let Some(byte) = self.peek() else {
+                return Err(self.error("EOF while parsing a string"));
+            };
+            self.pos += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let Some(escape) = self.peek() else {
+                        return Err(self.error("EOF while parsing a string"));
+                    };
+                    self.pos += 1;
+                    match escape {
+                        b'"' => out.push(b'"'),
+                        b'\\' => out.push(b'\\'),
+                        b'/' => out.push(b'/'),
+                        b'b' => out.push(0x08),
+                        b'f' => out.push(0x0c),
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'u' => {
+                            let c = self.unicode_escape()?;
+                            let mut buf = [0u8; 4];
+                            out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                }
+                0x00..=0x1f => return Err(self.error("control character in string")),
+                _ => out.push(byte),
+            }
+        }
+        String::from_utf8(out).map_err(|_| self.error("invalid UTF-8 in string"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let digits = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+            .ok_or_else(|| self.error("invalid \\u escape"))?;
+        self.pos += 4;
+        Ok(digits)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if !self.text[self.pos..].starts_with(b"\\u") {
+                return Err(self.error("lone leading surrogate"));
+            }
+            self.pos += 2;
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("invalid surrogate pair"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode code point"))
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        let digits = |p: &mut Self| {
+            let from = p.pos;
+            while p.peek().is_some_and(|b| b.is_ascii_digit()) {
+                p.pos += 1;
+            }
+            p.pos > from
+        };
+        if !digits(self) {
+            return Err(self.error("invalid number"));
+        }
+        let mut float = false;
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            float = true;
+            if !digits(self) {
+                return Err(self.error("invalid number"));
+            }
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            float = true;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if !digits(self) {
+                return Err(self.error("invalid number"));
+            }
+        }
+
+        let text = std::str::from_utf8(&self.text[start..self.pos]).unwrap_or_default();
+        if !float && let Ok(n) = text.parse::<i64>() {
+            return Ok(Json::Int(n));
+        }
+        text.parse::<f64>()
+            .ok()
+            .filter(|x| x.is_finite())
+            .map(Json::Float)
+            .ok_or_else(|| self.error("number out of range"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_cannot_nest_past_the_limit() {
+        let text = [b"[".repeat(MAX_DEPTH), b"]".repeat(MAX_DEPTH)].concat();
+        let value = Json::parse(&text).unwrap();
+        assert!(value.fits_at(1));
+        assert!(!value.fits_at(2));
+
+        // Writing one level deeper each time stops at the limit.
+        let mut doc = Json::Array(vec![Json::Null]);
+        let mut path = vec![PathStep::Index(0)];
+        let leaf = Json::Array(vec![Json::Null]);
+        while leaf.fits_at(path.len()) {
+            assert_eq!(doc.set(&path, &leaf).len(), 1);
+            path.push(PathStep::Index(0));
+        }
+        assert_eq!(path.len(), MAX_DEPTH);
+        assert_eq!(doc.depth(), MAX_DEPTH);
+    }
+}
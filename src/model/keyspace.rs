@@ -0,0 +1,79 @@
+use crate::util::scan_hash;
+use std::{
+    collections::{BTreeSet, HashMap, hash_map},
+    ops::Deref,
+};
+
+use super::Entry;
+
+/// The keys of one database. Reads go straight to the map through `Deref`;
+/// writes that add or drop keys go through the methods below so the SCAN
+/// index stays in step with the map.
+#[derive(Debug, Default)]
+pub struct Keyspace {
+    map: HashMap<String, Entry>,
+    /// Every key under its `scan_hash`, the order SCAN walks the keyspace in.
+    scan_index: BTreeSet<(u64, String)>,
+}
+
+impl Keyspace {
+    pub fn insert(&mut self, key: String, entry: Entry) -> Option<Entry> {
+        match self.map.entry(key) {
+            hash_map::Entry::Occupied(mut slot) => Some(slot.insert(entry)),
+            hash_map::Entry::Vacant(slot) => {
+                self.scan_index
+                    .insert((scan_hash(slot.key().as_bytes()), slot.key().clone()));
+                slot.insert(entry);
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Entry> {
+        self.remove_entry(key).map(|(_, entry)| entry)
+    }
+
+    pub fn remove_entry(&mut self, key: &str) -> Option<(String, Entry)> {
+        let (key, entry) = self.map.remove_entry(key)?;
+        self.scan_index
+            .remove(&(scan_hash(key.as_bytes()), key.clone()));
+        Some((key, entry))
+    }
+
+    /// The entry at `key`, inserting the one built by `make` if there is none.
+    pub fn get_or_insert_with(&mut self, key: String, make: impl FnOnce() -> Entry) -> &mut Entry {
+        if !self.map.contains_key(&key) {
+            self.scan_index
+                .insert((scan_hash(key.as_bytes()), key.clone()));
+        }
+        self.map.entry(key).or_insert_with(make)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Entry> {
+        self.map.get_mut(key)
+    }
+
+    /// Keys from `cursor` on in SCAN order, with their hashes.
+    pub fn scan_from(&self, cursor: u64) -> impl Iterator<Item = (u64, &String, &Entry)> {
+        self.scan_index
+            .range((cursor, String::new())..)
+            .map(|(hash, key)| (*hash, key, &self.map[key]))
+    }
+}
+
+impl Deref for Keyspace {
+    type Target = HashMap<String, Entry>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl IntoIterator for Keyspace {
+    type Item = (String, Entry);
+    type IntoIter = hash_map::IntoIter<String, Entry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
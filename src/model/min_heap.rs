@@ -1,11 +1,12 @@
-use std::{collections::BinaryHeap, sync::Arc, time::Instant};
+use std::{collections::BinaryHeap, sync::Arc};
 use tokio::sync::Mutex;
 
 pub type Heap = Arc<Mutex<BinaryHeap<MinHeap>>>;
 
 #[derive(Clone, PartialEq, Eq)]
 pub struct MinHeap {
-    pub expires_at: Instant,
+    /// Unix time in milliseconds, matching `Entry::expires_at`.
+    pub expires_at: u64,
     pub key: String,
 }
 
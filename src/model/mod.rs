@@ -1,9 +1,43 @@
+mod bitmap;
+mod bloom;
+mod cms;
 mod command;
+mod cuckoo;
 mod db;
+mod geo;
+mod hash;
+mod hyperloglog;
+mod json;
+mod keyspace;
 mod min_heap;
 mod resp;
+mod stream;
+mod string;
+mod timeseries;
+mod topk;
+mod zset;
 
+pub use bitmap::{BitFieldOp, BitFieldType, BitOp, BitOverflow, get_bit, set_bit};
+pub use bloom::{BF_DEFAULT_EXPANSION, BloomFilter};
+pub use cms::CountMinSketch;
+pub use cuckoo::CuckooFilter;
+pub use geo::{
+    GeoFrom, GeoSearch, GeoShape, GeoUnit, geo_distance, geo_valid, geohash_decode, geohash_encode,
+    geohash_string,
+};
+pub use hash::Hash;
+pub use hyperloglog::{HyperLogLog, hll_cached_count, hll_set_cached_count};
+pub use json::{Json, JsonPath};
+pub use keyspace::Keyspace;
 pub use resp::RESP;
+pub use stream::{
+    ConsumerGroup, PendingEntry, Stream, StreamFields, StreamId, StreamIdSpec, StreamReadId,
+    StreamTrim, TrimStrategy, XClaimOptions, stream_entry_resp, unix_millis,
+};
+pub use string::{ExpireCondition, SetExpiry, SetOptions};
+pub use timeseries::{Aggregator, DuplicatePolicy, LabelFilter, TimeSeries, TsAddError, TsRange};
+pub use topk::{TOPK_DEFAULT_DECAY, TOPK_DEFAULT_DEPTH, TOPK_DEFAULT_WIDTH, TopK};
+pub use zset::{Aggregate, LexBound, ScoreBound, ZAddFlags, ZRangeBy, ZRangeSpec, ZSet};
 pub use {command::Command, command::CommandInfo};
 pub use {db::DB, db::Entry, db::Value};
 pub use {min_heap::Heap, min_heap::MinHeap};
@@ -0,0 +1,460 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub type StreamFields = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// Stream entry ID: milliseconds plus a sequence number, ordered as a pair.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+/// How XADD picks the ID of the new entry.
+#[derive(Debug, Clone, Copy)]
+pub enum StreamIdSpec {
+    Auto,
+    AutoSeq(u64),
+    Explicit(StreamId),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum TrimStrategy {
+    MaxLen(u64),
+    MinId(StreamId),
+}
+
+/// Parsed `MAXLEN|MINID [=|~] threshold [LIMIT count]` arguments.
+/// Approximate trims are carried out exactly, bounded by `limit`.
+#[derive(Debug, Clone, Copy)]
+pub struct StreamTrim {
+    pub strategy: TrimStrategy,
+    pub limit: Option<u64>,
+}
+
+/// Where XREAD / XREADGROUP start reading a stream.
+#[derive(Debug, Clone, Copy)]
+pub enum StreamReadId {
+    /// `$`: only entries added after the call.
+    Last,
+    /// `+`: the last entry currently in the stream.
+    LastEntry,
+    /// `>`: entries never delivered to the group.
+    Undelivered,
+    After(StreamId),
+}
+
+/// Optional arguments of XCLAIM.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct XClaimOptions {
+    pub idle: Option<u64>,
+    pub time: Option<u64>,
+    pub retry_count: Option<u64>,
+    pub force: bool,
+    pub justid: bool,
+    pub last_id: Option<StreamId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingEntry {
+    pub consumer: Vec<u8>,
+    pub delivered_at: u64,
+    pub delivery_count: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Consumer {
+    pub seen_time: u64,
+    pub active_time: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConsumerGroup {
+    pub last_id: StreamId,
+    pub entries_read: Option<u64>,
+    pub pending: BTreeMap<StreamId, PendingEntry>,
+    pub consumers: BTreeMap<Vec<u8>, Consumer>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Stream {
+    entries: BTreeMap<StreamId, StreamFields>,
+    last_id: StreamId,
+    max_deleted_id: StreamId,
+    entries_added: u64,
+    groups: BTreeMap<Vec<u8>, ConsumerGroup>,
+}
+
+/// Current Unix time in milliseconds, used for IDs and pending-entry idle times.
+pub fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl StreamId {
+    pub const MIN: StreamId = StreamId { ms: 0, seq: 0 };
+    pub const MAX: StreamId = StreamId {
+        ms: u64::MAX,
+        seq: u64::MAX,
+    };
+
+    /// Parses `ms-seq`, or a bare `ms` with `seq` filled in from `default_seq`.
+    pub fn parse(raw: &[u8], default_seq: u64) -> Option<Self> {
+        let s = std::str::from_utf8(raw).ok()?;
+        match s.split_once('-') {
+            Some((ms, seq)) => Some(StreamId {
+                ms: ms.parse().ok()?,
+                seq: seq.parse().ok()?,
+            }),
+            None => Some(StreamId {
+                ms: s.parse().ok()?,
+                seq: default_seq,
+            }),
+        }
+    }
+
+    pub fn next(self) -> Option<Self> {
+        if self.seq < u64::MAX {
+            Some(StreamId {
+                ms: self.ms,
+                seq: self.seq + 1,
+            })
+        } else if self.ms < u64::MAX {
+            Some(StreamId {
+                ms: self.ms + 1,
+                seq: 0,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn prev(self) -> Option<Self> {
+        if self.seq > 0 {
+            Some(StreamId {
+                ms: self.ms,
+                seq: self.seq - 1,
+            })
+        } else if self.ms > 0 {
+            Some(StreamId {
+                ms: self.ms - 1,
+                seq: u64::MAX,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+impl Stream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    pub fn max_deleted_id(&self) -> StreamId {
+        self.max_deleted_id
+    }
+
+    pub fn entries_added(&self) -> u64 {
+        self.entries_added
+    }
+
+    pub fn first_entry(&self) -> Option<(&StreamId, &StreamFields)> {
+        self.entries.first_key_value()
+    }
+
+    pub fn last_entry(&self) -> Option<(&StreamId, &StreamFields)> {
+        self.entries.last_key_value()
+    }
+
+    pub fn get(&self, id: &StreamId) -> Option<&StreamFields> {
+        self.entries.get(id)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&StreamId, &StreamFields)> {
+        self.entries.iter()
+    }
+
+    /// Resolves the ID for a new entry. Returns `None` when the result would
+    /// not be greater than the current top item.
+    pub fn next_id(&self, spec: StreamIdSpec) -> Option<StreamId> {
+        let id = match spec {
+            StreamIdSpec::Auto => {
+                let now = unix_millis();
+                if now > self.last_id.ms {
+                    StreamId { ms: now, seq: 0 }
+                } else {
+                    self.last_id.next()?
+                }
+            }
+            StreamIdSpec::AutoSeq(ms) => {
+                if ms == self.last_id.ms {
+                    self.last_id.next()?
+                } else {
+                    StreamId { ms, seq: 0 }
+                }
+            }
+            StreamIdSpec::Explicit(id) => id,
+        };
+        (id > self.last_id).then_some(id)
+    }
+
+    pub fn insert(&mut self, id: StreamId, fields: StreamFields) {
+        self.entries.insert(id, fields);
+        self.last_id = id;
+        self.entries_added += 1;
+    }
+
+    /// Reverts an `insert` followed by a trim, putting the trimmed entries back.
+    pub fn undo_insert(
+        &mut self,
+        id: StreamId,
+        previous_last_id: StreamId,
+        previous_max_deleted_id: StreamId,
+        trimmed: Vec<(StreamId, StreamFields)>,
+    ) {
+        self.entries.remove(&id);
+        self.entries.extend(trimmed);
+        self.last_id = previous_last_id;
+        self.max_deleted_id = previous_max_deleted_id;
+        self.entries_added -= 1;
+    }
+
+    pub fn delete(&mut self, id: &StreamId) -> bool {
+        if self.entries.remove(id).is_none() {
+            return false;
+        }
+        if *id > self.max_deleted_id {
+            self.max_deleted_id = *id;
+        }
+        true
+    }
+
+    /// Evicts entries from the head according to `trim`, returning them.
+    pub fn trim(&mut self, trim: &StreamTrim) -> Vec<(StreamId, StreamFields)> {
+        let mut removed = Vec::new();
+        let limit = trim.limit.unwrap_or(0);
+        loop {
+            if limit > 0 && removed.len() as u64 >= limit {
+                break;
+            }
+            let Some(first) = self.entries.first_key_value().map(|(id, _)| *id) else {
+                break;
+            };
+            let evict = match trim.strategy {
+                TrimStrategy::MaxLen(max) => self.entries.len() as u64 > max,
+                TrimStrategy::MinId(min) => first < min,
+            };
+            if !evict {
+                break;
+            }
+            if let Some(entry) = self.entries.pop_first() {
+                removed.push(entry);
+            }
+        }
+        // Trimmed entries are the oldest, so only the last one can raise it.
+        if let Some((id, _)) = removed.last()
+            && *id > self.max_deleted_id
+        {
+            self.max_deleted_id = *id;
+        }
+        removed
+    }
+
+    /// Entries with IDs in `start..=end`, newest first when `rev`.
+    pub fn range(
+        &self,
+        start: StreamId,
+        end: StreamId,
+        rev: bool,
+        count: Option<usize>,
+    ) -> Vec<(&StreamId, &StreamFields)> {
+        if start > end {
+            return Vec::new();
+        }
+        let count = count.unwrap_or(usize::MAX);
+        let range = self.entries.range(start..=end);
+        if rev {
+            range.rev().take(count).collect()
+        } else {
+            range.take(count).collect()
+        }
+    }
+
+    /// Estimates how many entries were ever added up to and including `id`,
+    /// or `None` when deletions make that unknowable.
+    pub fn entries_read_at(&self, id: StreamId) -> Option<u64> {
+        if id >= self.last_id {
+            return Some(self.entries_added);
+        }
+        let Some((first, _)) = self.entries.first_key_value() else {
+            return Some(self.entries_added);
+        };
+        if self.max_deleted_id >= *first {
+            return None;
+        }
+        let trimmed = self.entries_added - self.entries.len() as u64;
+        Some(trimmed + self.entries.range(..=id).count() as u64)
+    }
+
+    /// Number of entries the group has yet to be delivered, if known.
+    pub fn lag(&self, group: &ConsumerGroup) -> Option<u64> {
+        if self.entries.is_empty() || group.last_id >= self.last_id {
+            return Some(0);
+        }
+        let read = group.entries_read?;
+        let tombstone_pending = self
+            .entries
+            .first_key_value()
+            .is_some_and(|(first, _)| self.max_deleted_id >= *first);
+        if tombstone_pending && self.max_deleted_id >= group.last_id {
+            return None;
+        }
+        Some(self.entries_added.saturating_sub(read))
+    }
+
+    pub fn groups(&self) -> &BTreeMap<Vec<u8>, ConsumerGroup> {
+        &self.groups
+    }
+
+    pub fn group(&self, name: &[u8]) -> Option<&ConsumerGroup> {
+        self.groups.get(name)
+    }
+
+    pub fn group_mut(&mut self, name: &[u8]) -> Option<&mut ConsumerGroup> {
+        self.groups.get_mut(name)
+    }
+
+    /// Adds a group starting after `last_id`. Returns `false` if it exists.
+    pub fn create_group(
+        &mut self,
+        name: Vec<u8>,
+        last_id: StreamId,
+        entries_read: Option<u64>,
+    ) -> bool {
+        if self.groups.contains_key(&name) {
+            return false;
+        }
+        let entries_read = entries_read.or_else(|| self.entries_read_at(last_id));
+        self.groups.insert(
+            name,
+            ConsumerGroup {
+                last_id,
+                entries_read,
+                ..ConsumerGroup::default()
+            },
+        );
+        true
+    }
+
+    pub fn destroy_group(&mut self, name: &[u8]) -> bool {
+        self.groups.remove(name).is_some()
+    }
+}
+
+impl ConsumerGroup {
+    /// Looks up `name`, creating it if needed. Returns whether it was created.
+    pub fn touch_consumer(&mut self, name: &[u8], now: u64) -> bool {
+        match self.consumers.get_mut(name) {
+            Some(consumer) => {
+                consumer.seen_time = now;
+                false
+            }
+            None => {
+                self.consumers.insert(
+                    name.to_vec(),
+                    Consumer {
+                        seen_time: now,
+                        active_time: None,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    pub fn pending_for<'a>(
+        &'a self,
+        consumer: &'a [u8],
+    ) -> impl Iterator<Item = (&'a StreamId, &'a PendingEntry)> + 'a {
+        self.pending
+            .iter()
+            .filter(move |(_, pending)| pending.consumer == consumer)
+    }
+
+    /// Removes a consumer and its pending entries, returning how many it held.
+    pub fn delete_consumer(&mut self, name: &[u8]) -> Option<usize> {
+        self.consumers.remove(name)?;
+        let before = self.pending.len();
+        self.pending.retain(|_, pending| pending.consumer != name);
+        Some(before - self.pending.len())
+    }
+}
+
+/// Encodes one entry as `[id, [field, value, ...]]`; deleted entries that are
+/// still pending are sent with a null field list.
+pub fn stream_entry_resp(id: &StreamId, fields: Option<&StreamFields>) -> Vec<u8> {
+    let mut resp = crate::util::array_len(2);
+    resp.extend_from_slice(&crate::util::bulk_str(&id.to_string()));
+    match fields {
+        Some(fields) => {
+            resp.extend_from_slice(&crate::util::array_len(fields.len() * 2));
+            for (field, value) in fields {
+                resp.extend_from_slice(&crate::util::bulk_bytes(field));
+                resp.extend_from_slice(&crate::util::bulk_bytes(value));
+            }
+        }
+        None => resp.extend_from_slice(&crate::util::null_array()),
+    }
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(ms: u64) -> StreamId {
+        StreamId { ms, seq: 0 }
+    }
+
+    #[test]
+    fn deletions_raise_max_deleted_id() {
+        let mut stream = Stream::new();
+        for ms in 1..=5 {
+            stream.insert(id(ms), vec![(b"f".to_vec(), b"v".to_vec())]);
+        }
+        assert_eq!(stream.max_deleted_id(), StreamId::default());
+
+        assert!(stream.delete(&id(3)));
+        assert_eq!(stream.max_deleted_id(), id(3));
+        // Deleting an older entry keeps the maximum.
+        assert!(stream.delete(&id(1)));
+        assert_eq!(stream.max_deleted_id(), id(3));
+
+        let trim = StreamTrim {
+            strategy: TrimStrategy::MaxLen(1),
+            limit: None,
+        };
+        assert_eq!(stream.trim(&trim).len(), 2);
+        assert_eq!(stream.max_deleted_id(), id(4));
+    }
+}
@@ -0,0 +1,68 @@
+use super::unix_millis;
+
+/// Expiry requested by SET. `Ex`/`Px` count from now, `ExAt`/`PxAt` are Unix
+/// timestamps and `KeepTtl` leaves the current TTL in place.
+#[derive(Debug, Clone, Copy)]
+pub enum SetExpiry {
+    Ex(u64),
+    Px(u64),
+    ExAt(u64),
+    PxAt(u64),
+    KeepTtl,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SetOptions {
+    pub nx: bool,
+    pub xx: bool,
+    pub get: bool,
+    pub expiry: Option<SetExpiry>,
+}
+
+impl SetExpiry {
+    /// Resolves the expiry to a Unix time in milliseconds, or `None` for
+    /// `KeepTtl`. The parser has already checked that the result fits an
+    /// i64. Timestamps already in the past are kept as given, so the key is
+    /// written already expired and goes through the usual lazy cleanup.
+    pub fn deadline(&self) -> Option<u64> {
+        let now = unix_millis();
+        Some(match *self {
+            SetExpiry::Ex(secs) => now + secs * 1000,
+            SetExpiry::Px(ms) => now + ms,
+            SetExpiry::ExAt(secs) => secs * 1000,
+            SetExpiry::PxAt(ms) => ms,
+            SetExpiry::KeepTtl => return None,
+        })
+    }
+}
+
+/// The NX/XX/GT/LT guard on an expiry update. A missing TTL counts as
+/// infinite, so GT never replaces it and LT always does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpireCondition {
+    Nx,
+    Xx,
+    Gt,
+    Lt,
+}
+
+impl ExpireCondition {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.to_uppercase().as_str() {
+            "NX" => Some(ExpireCondition::Nx),
+            "XX" => Some(ExpireCondition::Xx),
+            "GT" => Some(ExpireCondition::Gt),
+            "LT" => Some(ExpireCondition::Lt),
+            _ => None,
+        }
+    }
+
+    pub fn allows(&self, current: Option<u64>, deadline: u64) -> bool {
+        match self {
+            ExpireCondition::Nx => current.is_none(),
+            ExpireCondition::Xx => current.is_some(),
+            ExpireCondition::Gt => current.is_some_and(|t| deadline > t),
+            ExpireCondition::Lt => current.is_none_or(|t| deadline < t),
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+
+/// What TS.ADD does when a sample already exists at the timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DuplicatePolicy {
+    #[default]
+    Block,
+    First,
+    Last,
+    Min,
+    Max,
+    Sum,
+}
+
+impl DuplicatePolicy {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.to_uppercase().as_str() {
+            "BLOCK" => Some(DuplicatePolicy::Block),
+            "FIRST" => Some(DuplicatePolicy::First),
+            "LAST" => Some(DuplicatePolicy::Last),
+            "MIN" => Some(DuplicatePolicy::Min),
+            "MAX" => Some(DuplicatePolicy::Max),
+            "SUM" => Some(DuplicatePolicy::Sum),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Aggregator {
+    Avg,
+    Sum,
+    Min,
+    Max,
+    Count,
+}
+
+impl Aggregator {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.to_uppercase().as_str() {
+            "AVG" => Some(Aggregator::Avg),
+            "SUM" => Some(Aggregator::Sum),
+            "MIN" => Some(Aggregator::Min),
+            "MAX" => Some(Aggregator::Max),
+            "COUNT" => Some(Aggregator::Count),
+            _ => None,
+        }
+    }
+
+    fn reduce(&self, values: &[f64]) -> f64 {
+        match self {
+            Aggregator::Avg => values.iter().sum::<f64>() / values.len() as f64,
+            Aggregator::Sum => values.iter().sum(),
+            Aggregator::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregator::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Aggregator::Count => values.len() as f64,
+        }
+    }
+}
+
+/// Options shared by TS.RANGE, TS.REVRANGE and TS.MRANGE.
+#[derive(Debug, Clone)]
+pub struct TsRange {
+    pub from: u64,
+    pub to: u64,
+    pub rev: bool,
+    pub count: Option<usize>,
+    /// Aggregator and bucket duration in milliseconds.
+    pub aggregation: Option<(Aggregator, u64)>,
+}
+
+/// A TS.MRANGE label matcher. An empty value list stands for "no value",
+/// so `label=` matches series without the label and `label!=` those with it.
+#[derive(Debug, Clone)]
+pub enum LabelFilter {
+    Equals(String, Vec<String>),
+    NotEquals(String, Vec<String>),
+}
+
+impl LabelFilter {
+    /// Parses `l=v`, `l!=v`, `l=`, `l!=`, `l=(v1,v2)` and `l!=(v1,v2)`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (label, values, equals) = match raw.split_once("!=") {
+            Some((label, values)) => (label, values, false),
+            None => {
+                let (label, values) = raw.split_once('=')?;
+                (label, values, true)
+            }
+        };
+        if label.is_empty() {
+            return None;
+        }
+        let values: Vec<String> = match values.strip_prefix('(') {
+            Some(list) => list
+                .strip_suffix(')')?
+                .split(',')
+                .map(|v| v.trim().to_string())
+                .collect(),
+            None if values.is_empty() => Vec::new(),
+            None => vec![values.to_string()],
+        };
+        Some(if equals {
+            LabelFilter::Equals(label.to_string(), values)
+        } else {
+            LabelFilter::NotEquals(label.to_string(), values)
+        })
+    }
+
+    pub fn is_equals(&self) -> bool {
+        matches!(self, LabelFilter::Equals(..))
+    }
+
+    fn matches(&self, labels: &[(String, String)]) -> bool {
+        let value_of = |label: &str| labels.iter().find(|(l, _)| l == label).map(|(_, v)| v);
+        match self {
+            LabelFilter::Equals(label, values) => match value_of(label) {
+                Some(value) => values.contains(value),
+                None => values.is_empty(),
+            },
+            LabelFilter::NotEquals(label, values) => match value_of(label) {
+                Some(value) => !values.is_empty() && !values.contains(value),
+                None => !values.is_empty(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TsAddError {
+    /// The timestamp falls before the retention window.
+    TooOld,
+    /// A sample exists at the timestamp and the policy is BLOCK.
+    Blocked,
+}
+
+/// Samples ordered by millisecond timestamp. Retention is measured back from
+/// the newest sample rather than from the wall clock, so an idle series
+/// keeps its last window.
+#[derive(Debug, Clone, Default)]
+pub struct TimeSeries {
+    samples: BTreeMap<u64, f64>,
+    /// Milliseconds of history to keep; 0 keeps everything.
+    pub retention: u64,
+    pub duplicate_policy: DuplicatePolicy,
+    pub labels: Vec<(String, String)>,
+}
+
+impl TimeSeries {
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn last_timestamp(&self) -> Option<u64> {
+        self.samples.keys().next_back().copied()
+    }
+
+    /// Adds a sample, resolving a clash with `on_duplicate` or the series'
+    /// own policy. Returns the value previously stored at the timestamp so
+    /// the add can be undone.
+    pub fn add(
+        &mut self,
+        timestamp: u64,
+        value: f64,
+        on_duplicate: Option<DuplicatePolicy>,
+    ) -> Result<Option<f64>, TsAddError> {
+        if let Some(last) = self.last_timestamp()
+            && self.retention > 0
+            && timestamp < last.saturating_sub(self.retention)
+        {
+            return Err(TsAddError::TooOld);
+        }
+
+        let Some(&old) = self.samples.get(&timestamp) else {
+            self.samples.insert(timestamp, value);
+            return Ok(None);
+        };
+        let merged = match on_duplicate.unwrap_or(self.duplicate_policy) {
+            DuplicatePolicy::Block => return Err(TsAddError::Blocked),
+            DuplicatePolicy::First => old,
+            DuplicatePolicy::Last => value,
+            DuplicatePolicy::Min => old.min(value),
+            DuplicatePolicy::Max => old.max(value),
+            DuplicatePolicy::Sum => old + value,
+        };
+        self.samples.insert(timestamp, merged);
+        Ok(Some(old))
+    }
+
+    /// Reverts an `add` that returned `previous`.
+    pub fn undo_add(&mut self, timestamp: u64, previous: Option<f64>) {
+        match previous {
+            Some(value) => self.samples.insert(timestamp, value),
+            None => self.samples.remove(&timestamp),
+        };
+    }
+
+    /// Drops samples older than the retention window and returns how many
+    /// went.
+    pub fn trim(&mut self) -> usize {
+        let Some(last) = self.last_timestamp() else {
+            return 0;
+        };
+        if self.retention == 0 || last < self.retention {
+            return 0;
+        }
+        let keep = self.samples.split_off(&(last - self.retention));
+        let removed = self.samples.len();
+        self.samples = keep;
+        removed
+    }
+
+    pub fn matches(&self, filters: &[LabelFilter]) -> bool {
+        filters.iter().all(|filter| filter.matches(&self.labels))
+    }
+
+    /// Samples between `range.from` and `range.to` inclusive, aggregated
+    /// into buckets aligned to multiples of the bucket duration if asked.
+    pub fn range(&self, range: &TsRange) -> Vec<(u64, f64)> {
+        if range.from > range.to {
+            return Vec::new();
+        }
+        let samples = self.samples.range(range.from..=range.to);
+
+        let mut out: Vec<(u64, f64)> = match range.aggregation {
+            None => samples.map(|(ts, value)| (*ts, *value)).collect(),
+            Some((aggregator, bucket)) => {
+                let mut out = Vec::new();
+                let mut current: Option<(u64, Vec<f64>)> = None;
+                for (ts, value) in samples {
+                    let start = ts - ts % bucket;
+                    match &mut current {
+                        Some((bucket_start, values)) if *bucket_start == start => {
+                            values.push(*value)
+                        }
+                        _ => {
+                            if let Some((bucket_start, values)) = current.take() {
+                                out.push((bucket_start, aggregator.reduce(&values)));
+                            }
+                            current = Some((start, vec![*value]));
+                        }
+                    }
+                }
+                if let Some((bucket_start, values)) = current {
+                    out.push((bucket_start, aggregator.reduce(&values)));
+                }
+                out
+            }
+        };
+
+        if range.rev {
+            out.reverse();
+        }
+        if let Some(count) = range.count {
+            out.truncate(count);
+        }
+        out
+    }
+
+    pub fn bytes(&self) -> usize {
+        // A B-tree node slot per sample plus roughly one node header per
+        // eleven samples.
+        self.samples.len() * (size_of::<(u64, f64)>() + 8)
+            + self
+                .labels
+                .iter()
+                .map(|(label, value)| label.capacity() + value.capacity())
+                .sum::<usize>()
+            + self.labels.capacity() * size_of::<(String, String)>()
+    }
+}
@@ -0,0 +1,140 @@
+use super::hyperloglog::murmur_hash64a;
+use crate::util::random_index;
+
+pub const TOPK_DEFAULT_WIDTH: usize = 8;
+pub const TOPK_DEFAULT_DEPTH: usize = 7;
+pub const TOPK_DEFAULT_DECAY: f64 = 0.9;
+const FINGERPRINT_SEED: u64 = 0x2b99_2ddf;
+
+/// Top-K tracking with HeavyKeeper: a grid of fingerprinted counters where
+/// a colliding item decays the resident's count with probability
+/// `decay^count`, so heavy hitters keep their buckets while rare items
+/// fade. The `k` items with the highest estimates are kept alongside.
+#[derive(Debug, Clone)]
+pub struct TopK {
+    k: usize,
+    width: usize,
+    depth: usize,
+    decay: f64,
+    buckets: Vec<Bucket>,
+    /// Tracked items and their counts, unordered; `k` is small.
+    top: Vec<(Vec<u8>, u64)>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Bucket {
+    fingerprint: u32,
+    count: u64,
+}
+
+impl TopK {
+    pub fn new(k: usize, width: usize, depth: usize, decay: f64) -> Self {
+        TopK {
+            k,
+            width,
+            depth,
+            decay,
+            buckets: vec![Bucket::default(); width * depth],
+            top: Vec::with_capacity(k),
+        }
+    }
+
+    /// Counts one occurrence of `item`. Returns the item pushed out of the
+    /// top list to make room for it, if any.
+    pub fn add(&mut self, item: &[u8]) -> Option<Vec<u8>> {
+        let fingerprint = murmur_hash64a(item, FINGERPRINT_SEED) as u32;
+        let mut estimate = 0;
+        for row in 0..self.depth {
+            let slot = self.slot(item, row);
+            let bucket = &mut self.buckets[slot];
+            if bucket.count == 0 {
+                bucket.fingerprint = fingerprint;
+            }
+            if bucket.fingerprint == fingerprint {
+                bucket.count += 1;
+                estimate = estimate.max(bucket.count);
+            } else if decays(self.decay, bucket.count) {
+                bucket.count -= 1;
+                if bucket.count == 0 {
+                    bucket.fingerprint = fingerprint;
+                    bucket.count = 1;
+                    estimate = estimate.max(1);
+                }
+            }
+        }
+
+        if let Some(tracked) = self.top.iter_mut().find(|(name, _)| name == item) {
+            tracked.1 = tracked.1.max(estimate);
+            return None;
+        }
+        if self.top.len() < self.k {
+            if estimate > 0 {
+                self.top.push((item.to_vec(), estimate));
+            }
+            return None;
+        }
+
+        let (min_index, min_count) = self
+            .top
+            .iter()
+            .enumerate()
+            .map(|(i, (_, count))| (i, *count))
+            .min_by_key(|(_, count)| *count)?;
+        if estimate <= min_count {
+            return None;
+        }
+        let expelled = std::mem::replace(&mut self.top[min_index], (item.to_vec(), estimate));
+        Some(expelled.0)
+    }
+
+    pub fn contains(&self, item: &[u8]) -> bool {
+        self.top.iter().any(|(name, _)| name == item)
+    }
+
+    /// The sketch's estimate for `item`, tracked or not.
+    pub fn count(&self, item: &[u8]) -> u64 {
+        let fingerprint = murmur_hash64a(item, FINGERPRINT_SEED) as u32;
+        (0..self.depth)
+            .map(|row| self.buckets[self.slot(item, row)])
+            .filter(|bucket| bucket.fingerprint == fingerprint)
+            .map(|bucket| bucket.count)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Tracked items, highest count first.
+    pub fn list(&self) -> Vec<(&[u8], u64)> {
+        let mut items: Vec<(&[u8], u64)> = self
+            .top
+            .iter()
+            .map(|(name, count)| (name.as_slice(), *count))
+            .collect();
+        items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        items
+    }
+
+    /// Heap bytes `new` allocates for these dimensions.
+    pub fn footprint(k: usize, width: usize, depth: usize) -> usize {
+        width * depth * size_of::<Bucket>() + k * size_of::<(Vec<u8>, u64)>()
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.buckets.capacity() * size_of::<Bucket>()
+            + self.top.capacity() * size_of::<(Vec<u8>, u64)>()
+            + self
+                .top
+                .iter()
+                .map(|(name, _)| name.capacity())
+                .sum::<usize>()
+    }
+
+    fn slot(&self, item: &[u8], row: usize) -> usize {
+        row * self.width + (murmur_hash64a(item, row as u64) % self.width as u64) as usize
+    }
+}
+
+/// Whether a resident with `count` gives way to a colliding item this time.
+fn decays(decay: f64, count: u64) -> bool {
+    let chance = decay.powi(count.min(i32::MAX as u64) as i32);
+    (random_index(1 << 24) as f64) < chance * (1 << 24) as f64
+}
@@ -0,0 +1,427 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    ops::Bound,
+};
+
+/// Score wrapper giving `f64` the total order the index needs.
+#[derive(Clone, Copy, Debug)]
+pub struct Score(pub f64);
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Sorted set: a member -> score map plus a B-tree ordered by (score, member).
+#[derive(Debug, Default, Clone)]
+pub struct ZSet {
+    scores: HashMap<Vec<u8>, f64>,
+    index: BTreeSet<(Score, Vec<u8>)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScoreBound {
+    pub value: f64,
+    pub exclusive: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum LexBound {
+    NegInf,
+    PosInf,
+    Inclusive(Vec<u8>),
+    Exclusive(Vec<u8>),
+}
+
+#[derive(Debug, Clone)]
+pub enum ZRangeBy {
+    Rank(i64, i64),
+    Score(ScoreBound, ScoreBound),
+    Lex(LexBound, LexBound),
+}
+
+/// Parsed form of the unified `ZRANGE` arguments. For `REV` ranges the
+/// bounds are stored as `(min, max)` regardless of the order given.
+#[derive(Debug, Clone)]
+pub struct ZRangeSpec {
+    pub by: ZRangeBy,
+    pub rev: bool,
+    pub limit: Option<(i64, i64)>,
+    pub with_scores: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZAddFlags {
+    pub nx: bool,
+    pub xx: bool,
+    pub gt: bool,
+    pub lt: bool,
+    pub ch: bool,
+    pub incr: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Aggregate {
+    Sum,
+    Min,
+    Max,
+}
+
+impl ScoreBound {
+    fn above_min(&self, score: f64) -> bool {
+        if self.exclusive {
+            score > self.value
+        } else {
+            score >= self.value
+        }
+    }
+
+    fn below_max(&self, score: f64) -> bool {
+        if self.exclusive {
+            score < self.value
+        } else {
+            score <= self.value
+        }
+    }
+}
+
+impl LexBound {
+    fn above_min(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::NegInf => true,
+            LexBound::PosInf => false,
+            LexBound::Inclusive(v) => member >= v.as_slice(),
+            LexBound::Exclusive(v) => member > v.as_slice(),
+        }
+    }
+
+    fn below_max(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::NegInf => false,
+            LexBound::PosInf => true,
+            LexBound::Inclusive(v) => member <= v.as_slice(),
+            LexBound::Exclusive(v) => member < v.as_slice(),
+        }
+    }
+}
+
+impl Aggregate {
+    pub fn apply(&self, acc: f64, score: f64) -> f64 {
+        match self {
+            Aggregate::Sum => {
+                let sum = acc + score;
+                if sum.is_nan() { 0.0 } else { sum }
+            }
+            Aggregate::Min => acc.min(score),
+            Aggregate::Max => acc.max(score),
+        }
+    }
+}
+
+impl ZSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// Sets `member` to `score`, returning the previous score if any.
+    pub fn insert(&mut self, member: Vec<u8>, score: f64) -> Option<f64> {
+        let score = if score == 0.0 { 0.0 } else { score };
+        let old = self.scores.insert(member.clone(), score);
+        if let Some(old) = old {
+            self.index.remove(&(Score(old), member.clone()));
+        }
+        self.index.insert((Score(score), member));
+        old
+    }
+
+    pub fn remove(&mut self, member: &[u8]) -> Option<f64> {
+        let (member, score) = self.scores.remove_entry(member)?;
+        self.index.remove(&(Score(score), member));
+        Some(score)
+    }
+
+    /// Zero-based position of `member`, counted from the highest score when `rev`.
+    ///
+    /// The B-tree keeps no subtree sizes, so this walks the members on both
+    /// sides of `member` in step and stops at the shorter side: O(min(rank,
+    /// len - rank)), which keeps ranks near either end of a leaderboard cheap.
+    pub fn rank(&self, member: &[u8], rev: bool) -> Option<usize> {
+        let key = (Score(self.score(member)?), member.to_vec());
+        let mut below_iter = self.index.range(..key.clone());
+        let mut above_iter = self.index.range((Bound::Excluded(key), Bound::Unbounded));
+        let mut walked = 0;
+        let below = loop {
+            if below_iter.next().is_none() {
+                break walked;
+            }
+            if above_iter.next().is_none() {
+                break self.len() - 1 - walked;
+            }
+            walked += 1;
+        };
+        Some(if rev { self.len() - 1 - below } else { below })
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&Vec<u8>, f64)> {
+        self.index.iter().map(|(score, member)| (member, score.0))
+    }
+
+    pub fn pop_min(&mut self) -> Option<(Vec<u8>, f64)> {
+        let (score, member) = self.index.pop_first()?;
+        self.scores.remove(&member);
+        Some((member, score.0))
+    }
+
+    pub fn pop_max(&mut self) -> Option<(Vec<u8>, f64)> {
+        let (score, member) = self.index.pop_last()?;
+        self.scores.remove(&member);
+        Some((member, score.0))
+    }
+
+    /// Members within the bounds. Without subtree sizes this walks the
+    /// matched range, so it costs O(log n + matches).
+    pub fn count_in_score_range(&self, min: &ScoreBound, max: &ScoreBound) -> usize {
+        self.score_range(min, max).count()
+    }
+
+    fn score_range<'a>(
+        &'a self,
+        min: &'a ScoreBound,
+        max: &'a ScoreBound,
+    ) -> impl DoubleEndedIterator<Item = (&'a Vec<u8>, f64)> + 'a {
+        let start = Bound::Included((Score(min.value), Vec::new()));
+        let end = if min.value > max.value {
+            // An inverted range is empty; `BTreeSet::range` would panic on it.
+            Bound::Excluded((Score(min.value), Vec::new()))
+        } else if max.value == f64::INFINITY {
+            Bound::Unbounded
+        } else {
+            Bound::Excluded((Score(max.value.next_up()), Vec::new()))
+        };
+        self.index
+            .range((start, end))
+            .filter(move |(score, _)| min.above_min(score.0) && max.below_max(score.0))
+            .map(|(score, member)| (member, score.0))
+    }
+
+    /// Members between lexical bounds. Redis only defines BYLEX when every
+    /// member shares one score, and then the index is ordered by member
+    /// alone, so the bounds become a B-tree range. With mixed scores the
+    /// result is unspecified and the whole index is filtered instead.
+    fn lex_range<'a>(
+        &'a self,
+        min: &'a LexBound,
+        max: &'a LexBound,
+    ) -> impl DoubleEndedIterator<Item = (&'a Vec<u8>, f64)> + 'a {
+        let (start, end) = match (self.index.first(), self.index.last()) {
+            (Some((low, _)), Some((high, _))) if low == high => lex_bounds(*low, min, max),
+            _ => (Bound::Unbounded, Bound::Unbounded),
+        };
+        self.index
+            .range((start, end))
+            .filter(move |(_, member)| min.above_min(member) && max.below_max(member))
+            .map(|(score, member)| (member, score.0))
+    }
+
+    /// Evaluates a unified `ZRANGE` request. LIMIT is applied while walking
+    /// the index, so only the skipped and returned members are visited.
+    pub fn range<'a>(&'a self, spec: &'a ZRangeSpec) -> Vec<(&'a Vec<u8>, f64)> {
+        match &spec.by {
+            ZRangeBy::Rank(start, stop) => {
+                let len = self.len() as i64;
+                let start = if *start < 0 {
+                    (len + start).max(0)
+                } else {
+                    *start
+                };
+                let stop = if *stop < 0 {
+                    len + stop
+                } else {
+                    (*stop).min(len - 1)
+                };
+                if start > stop || start >= len {
+                    return Vec::new();
+                }
+                let (skip, take) = (start as usize, (stop - start + 1) as usize);
+                if spec.rev {
+                    apply_limit(self.iter().rev().skip(skip).take(take), spec.limit)
+                } else {
+                    apply_limit(self.iter().skip(skip).take(take), spec.limit)
+                }
+            }
+            ZRangeBy::Score(min, max) => {
+                if spec.rev {
+                    apply_limit(self.score_range(min, max).rev(), spec.limit)
+                } else {
+                    apply_limit(self.score_range(min, max), spec.limit)
+                }
+            }
+            ZRangeBy::Lex(min, max) => {
+                if spec.rev {
+                    apply_limit(self.lex_range(min, max).rev(), spec.limit)
+                } else {
+                    apply_limit(self.lex_range(min, max), spec.limit)
+                }
+            }
+        }
+    }
+
+    pub fn members(&self) -> impl Iterator<Item = &Vec<u8>> {
+        self.scores.keys()
+    }
+
+    pub fn scores_capacity(&self) -> usize {
+        self.scores.capacity()
+    }
+}
+
+/// Skips and takes per `LIMIT offset count` before collecting; a negative
+/// count means everything after the offset.
+fn apply_limit<'a>(
+    items: impl Iterator<Item = (&'a Vec<u8>, f64)>,
+    limit: Option<(i64, i64)>,
+) -> Vec<(&'a Vec<u8>, f64)> {
+    let Some((offset, count)) = limit else {
+        return items.collect();
+    };
+    if offset < 0 {
+        return Vec::new();
+    }
+    let items = items.skip(offset as usize);
+    if count < 0 {
+        items.collect()
+    } else {
+        items.take(count as usize).collect()
+    }
+}
+
+/// An entry of the sorted set's B-tree index.
+type IndexKey = (Score, Vec<u8>);
+
+/// B-tree bounds for a lexical range over members that all have `score`.
+/// Bounds that select nothing become an empty range, since
+/// `BTreeSet::range` panics on inverted ones.
+fn lex_bounds(score: Score, min: &LexBound, max: &LexBound) -> (Bound<IndexKey>, Bound<IndexKey>) {
+    let key = |member: &Vec<u8>| (score, member.clone());
+    let empty = (
+        Bound::Included((score, Vec::new())),
+        Bound::Excluded((score, Vec::new())),
+    );
+    let start = match min {
+        LexBound::NegInf => Bound::Unbounded,
+        LexBound::PosInf => return empty,
+        LexBound::Inclusive(member) => Bound::Included(key(member)),
+        LexBound::Exclusive(member) => Bound::Excluded(key(member)),
+    };
+    let end = match max {
+        LexBound::NegInf => return empty,
+        LexBound::PosInf => Bound::Unbounded,
+        LexBound::Inclusive(member) => Bound::Included(key(member)),
+        LexBound::Exclusive(member) => Bound::Excluded(key(member)),
+    };
+    let inverted = match (&start, &end) {
+        (Bound::Included(low), Bound::Included(high)) => low > high,
+        (
+            Bound::Included(low) | Bound::Excluded(low),
+            Bound::Included(high) | Bound::Excluded(high),
+        ) => low >= high,
+        _ => false,
+    };
+    if inverted { empty } else { (start, end) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inverted_score_range_is_empty() {
+        let mut zset = ZSet::new();
+        zset.insert(b"a".to_vec(), 1.0);
+        zset.insert(b"b".to_vec(), 5.0);
+
+        let bound = |value| ScoreBound {
+            value,
+            exclusive: false,
+        };
+        assert_eq!(zset.count_in_score_range(&bound(5.0), &bound(1.0)), 0);
+        assert_eq!(zset.count_in_score_range(&bound(1.0), &bound(5.0)), 2);
+
+        let spec = ZRangeSpec {
+            by: ZRangeBy::Score(bound(0.0), bound(-1.0)),
+            rev: false,
+            limit: None,
+            with_scores: false,
+        };
+        assert!(zset.range(&spec).is_empty());
+    }
+
+    #[test]
+    fn ranges_walk_only_what_they_return() {
+        let mut zset = ZSet::new();
+        for member in [b"a", b"b", b"c", b"d", b"e"] {
+            zset.insert(member.to_vec(), 0.0);
+        }
+        let names = |items: Vec<(&Vec<u8>, f64)>| -> Vec<Vec<u8>> {
+            items
+                .into_iter()
+                .map(|(member, _)| member.clone())
+                .collect()
+        };
+
+        let spec = ZRangeSpec {
+            by: ZRangeBy::Lex(
+                LexBound::Exclusive(b"a".to_vec()),
+                LexBound::Inclusive(b"d".to_vec()),
+            ),
+            rev: false,
+            limit: Some((1, 2)),
+            with_scores: false,
+        };
+        assert_eq!(names(zset.range(&spec)), vec![b"c".to_vec(), b"d".to_vec()]);
+
+        let spec = ZRangeSpec {
+            by: ZRangeBy::Lex(
+                LexBound::Inclusive(b"d".to_vec()),
+                LexBound::Exclusive(b"b".to_vec()),
+            ),
+            rev: false,
+            limit: None,
+            with_scores: false,
+        };
+        assert!(zset.range(&spec).is_empty());
+
+        for (i, member) in [b"a", b"b", b"c", b"d", b"e"].iter().enumerate() {
+            assert_eq!(zset.rank(*member, false), Some(i));
+            assert_eq!(zset.rank(*member, true), Some(4 - i));
+        }
+    }
+}
@@ -1,9 +1,22 @@
 use crate::{
-    model::{Command, RESP},
-    util::{bulk_to_string, expect_bulk},
+    model::{
+        Aggregate, Aggregator, BF_DEFAULT_EXPANSION, BitFieldOp, BitFieldType, BitOp, BitOverflow,
+        BloomFilter, Command, CountMinSketch, DuplicatePolicy, ExpireCondition, GeoFrom, GeoSearch,
+        GeoShape, GeoUnit, Json, JsonPath, LabelFilter, LexBound, RESP, ScoreBound, SetExpiry,
+        SetOptions, StreamId, StreamIdSpec, StreamReadId, StreamTrim, TOPK_DEFAULT_DECAY,
+        TOPK_DEFAULT_DEPTH, TOPK_DEFAULT_WIDTH, TimeSeries, TrimStrategy, TsRange, XClaimOptions,
+        ZAddFlags, ZRangeBy, ZRangeSpec, geo_valid, unix_millis,
+    },
+    util::{bulk_to_string, expect_bulk, expect_bulk_bytes, expect_float, expect_int, parse_float},
 };
 use anyhow::{Ok, Result};
 
+/// Largest width, depth or k a sketch may be created with.
+const SKETCH_MAX_DIM: usize = 1 << 20;
+/// Largest counter grid (`width * depth`) of a sketch; 256 MiB of CMS
+/// counters.
+const SKETCH_MAX_CELLS: usize = 1 << 25;
+
 pub fn parse_command(items: Vec<RESP>) -> Result<Command> {
     if items.is_empty() {
         return Err(anyhow::anyhow!("empty command"));
@@ -35,19 +48,234 @@ pub fn parse_command(items: Vec<RESP>) -> Result<Command> {
 
             Ok(Command::GET { key })
         }
-        "SET" => {
+        "INCR" | "DECR" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            if cmd == "INCR" {
+                Ok(Command::INCR { key })
+            } else {
+                Ok(Command::DECR { key })
+            }
+        }
+        "INCRBY" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'incrby' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let increment = expect_int(&items, 2, "increment")?;
+
+            Ok(Command::INCRBY { key, increment })
+        }
+        "DECRBY" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'decrby' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let decrement = expect_int(&items, 2, "decrement")?;
+
+            Ok(Command::DECRBY { key, decrement })
+        }
+        "INCRBYFLOAT" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'incrbyfloat' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let increment = expect_float(&items, 2, "increment")?;
+
+            Ok(Command::INCRBYFLOAT { key, increment })
+        }
+        "APPEND" | "GETSET" | "SETNX" => {
             if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let value = expect_bulk_bytes(&items, 2, "value")?;
+
+            match cmd.as_str() {
+                "APPEND" => Ok(Command::APPEND { key, value }),
+                "GETSET" => Ok(Command::GETSET { key, value }),
+                _ => Ok(Command::SETNX { key, value }),
+            }
+        }
+        "STRLEN" | "GETDEL" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            if cmd == "STRLEN" {
+                Ok(Command::STRLEN { key })
+            } else {
+                Ok(Command::GETDEL { key })
+            }
+        }
+        "GETRANGE" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'getrange' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let start = expect_int(&items, 2, "start")?;
+            let end = expect_int(&items, 3, "end")?;
+
+            Ok(Command::GETRANGE { key, start, end })
+        }
+        "SETRANGE" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'setrange' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let offset = expect_int(&items, 2, "offset")?;
+            if offset < 0 {
+                return Err(anyhow::anyhow!("offset is out of range"));
+            }
+            let value = expect_bulk_bytes(&items, 3, "value")?;
+
+            Ok(Command::SETRANGE {
+                key,
+                offset: offset as usize,
+                value,
+            })
+        }
+        "MGET" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'mget' command"
+                ));
+            }
+
+            let mut keys = Vec::new();
+            for i in 1..len {
+                keys.push(expect_bulk(&items, i, "key")?);
+            }
+
+            Ok(Command::MGET { keys })
+        }
+        "MSET" | "MSETNX" => {
+            let len = items.len();
+            if len < 3 || len.is_multiple_of(2) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let mut pairs = Vec::new();
+            for i in (1..len).step_by(2) {
+                let key = expect_bulk(&items, i, "key")?;
+                let value = expect_bulk_bytes(&items, i + 1, "value")?;
+                pairs.push((key, value));
+            }
+
+            if cmd == "MSET" {
+                Ok(Command::MSET { pairs })
+            } else {
+                Ok(Command::MSETNX { pairs })
+            }
+        }
+        "GETEX" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'getex' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let (expiry, persist) = match len {
+                2 => (None, false),
+                3 if expect_bulk(&items, 2, "option")?.eq_ignore_ascii_case("PERSIST") => {
+                    (None, true)
+                }
+                4 => {
+                    let unit = expect_bulk(&items, 2, "option")?.to_uppercase();
+                    if !matches!(unit.as_str(), "EX" | "PX" | "EXAT" | "PXAT") {
+                        return Err(anyhow::anyhow!("syntax error"));
+                    }
+                    let time = expect_int(&items, 3, "time")?;
+                    (Some(parse_set_expiry("getex", &unit, time)?), false)
+                }
+                _ => return Err(anyhow::anyhow!("syntax error")),
+            };
+
+            Ok(Command::GETEX {
+                key,
+                expiry,
+                persist,
+            })
+        }
+        "SET" => {
+            let len = items.len();
+            if len < 3 {
                 return Err(anyhow::anyhow!(
                     "wrong number of arguments for 'set' command"
                 ));
             }
 
             let key = expect_bulk(&items, 1, "key")?;
-            let value = expect_bulk(&items, 2, "value")?;
+            let value = expect_bulk_bytes(&items, 2, "value")?;
+
+            let mut options = SetOptions::default();
+            let mut i = 3;
+            while i < len {
+                let opt = expect_bulk(&items, i, "option")?.to_uppercase();
+                match opt.as_str() {
+                    "NX" if !options.xx => options.nx = true,
+                    "XX" if !options.nx => options.xx = true,
+                    "GET" => options.get = true,
+                    "KEEPTTL" if matches!(options.expiry, None | Some(SetExpiry::KeepTtl)) => {
+                        options.expiry = Some(SetExpiry::KeepTtl);
+                    }
+                    "EX" | "PX" | "EXAT" | "PXAT" if i + 1 < len => {
+                        let time = expect_int(&items, i + 1, "time")?;
+                        let expiry = parse_set_expiry("set", &opt, time)?;
+                        if options.expiry.is_some_and(|current| {
+                            std::mem::discriminant(&current) != std::mem::discriminant(&expiry)
+                        }) {
+                            return Err(anyhow::anyhow!("syntax error"));
+                        }
+                        options.expiry = Some(expiry);
+                        i += 1;
+                    }
+                    _ => return Err(anyhow::anyhow!("syntax error")),
+                }
+                i += 1;
+            }
 
             Ok(Command::SET {
                 key,
-                value: value.into_bytes(),
+                value,
+                options,
             })
         }
         "SETEX" => {
@@ -88,11 +316,12 @@ pub fn parse_command(items: Vec<RESP>) -> Result<Command> {
                 seconds: sec,
             })
         }
-        "DEL" => {
+        "DEL" | "UNLINK" => {
             let len = items.len();
             if len < 2 {
                 return Err(anyhow::anyhow!(
-                    "wrong number of arguments for 'del' command"
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
                 ));
             }
             let mut keys: Vec<String> = Vec::new();
@@ -102,7 +331,11 @@ pub fn parse_command(items: Vec<RESP>) -> Result<Command> {
                 keys.push(key);
             }
 
-            Ok(Command::DEL { keys })
+            Ok(if cmd == "DEL" {
+                Command::DEL { keys }
+            } else {
+                Command::UNLINK { keys }
+            })
         }
         "EXISTS" => {
             let len = items.len();
@@ -120,20 +353,81 @@ pub fn parse_command(items: Vec<RESP>) -> Result<Command> {
 
             Ok(Command::EXISTS { keys })
         }
-        "EXPIRE" => {
-            let len = items.len();
-            if len != 3 {
+        "EXPIRE" | "PEXPIRE" | "EXPIREAT" | "PEXPIREAT" => {
+            if items.len() < 3 {
                 return Err(anyhow::anyhow!(
-                    "wrong number of arguments for 'expire' command"
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
                 ));
             }
 
             let key = expect_bulk(&items, 1, "key")?;
-            let sec_str = expect_bulk(&items, 2, "time")?;
+            let time = expect_int(&items, 2, "time")?;
+            let seconds = matches!(cmd.as_str(), "EXPIRE" | "EXPIREAT");
+            let relative = matches!(cmd.as_str(), "EXPIRE" | "PEXPIRE");
+            if !deadline_fits(time, seconds, relative) {
+                return Err(anyhow::anyhow!(
+                    "invalid expire time in '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+            // A negative time is already in the past, so the key is deleted.
+            let expiry = match u64::try_from(time).ok() {
+                None => SetExpiry::PxAt(0),
+                Some(time) => match cmd.as_str() {
+                    "EXPIRE" => SetExpiry::Ex(time),
+                    "PEXPIRE" => SetExpiry::Px(time),
+                    "EXPIREAT" => SetExpiry::ExAt(time),
+                    _ => SetExpiry::PxAt(time),
+                },
+            };
+
+            let mut conditions = Vec::new();
+            for i in 3..items.len() {
+                let option = expect_bulk(&items, i, "option")?;
+                match ExpireCondition::parse(&option) {
+                    Some(condition) => conditions.push(condition),
+                    None => {
+                        return Err(anyhow::anyhow!("Unsupported option {}", option));
+                    }
+                }
+            }
+            let has = |condition| conditions.contains(&condition);
+            if has(ExpireCondition::Nx)
+                && (has(ExpireCondition::Xx)
+                    || has(ExpireCondition::Gt)
+                    || has(ExpireCondition::Lt))
+            {
+                return Err(anyhow::anyhow!(
+                    "NX and XX, GT or LT options at the same time are not compatible"
+                ));
+            }
+            if has(ExpireCondition::Gt) && has(ExpireCondition::Lt) {
+                return Err(anyhow::anyhow!(
+                    "GT and LT options at the same time are not compatible"
+                ));
+            }
+
+            Ok(Command::EXPIRE {
+                key,
+                expiry,
+                conditions,
+            })
+        }
+        "EXPIRETIME" | "PEXPIRETIME" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
 
-            let sec = sec_str.parse().unwrap();
+            let key = expect_bulk(&items, 1, "key")?;
 
-            Ok(Command::EXPIRE { key, seconds: sec })
+            Ok(Command::EXPIRETIME {
+                key,
+                millis: cmd == "PEXPIRETIME",
+            })
         }
         "PERSIST" => {
             if items.len() != 2 {
@@ -288,28 +582,3493 @@ pub fn parse_command(items: Vec<RESP>) -> Result<Command> {
         }
         "LPOP" => {
             let len = items.len();
-            if len != 2 {
+            if !(2..=3).contains(&len) {
                 return Err(anyhow::anyhow!(
                     "wrong number of arguments for 'lpop' command"
                 ));
             }
 
             let key = expect_bulk(&items, 1, "key")?;
+            let count = if len == 3 {
+                let n = expect_int(&items, 2, "count")?;
+                if n < 0 {
+                    return Err(anyhow::anyhow!("value is out of range, must be positive"));
+                }
+                Some(n as usize)
+            } else {
+                None
+            };
 
-            Ok(Command::LPOP { key })
+            Ok(Command::LPOP { key, count })
         }
         "RPOP" => {
             let len = items.len();
-            if len != 2 {
+            if !(2..=3).contains(&len) {
                 return Err(anyhow::anyhow!(
                     "wrong number of arguments for 'rpop' command"
                 ));
             }
 
             let key = expect_bulk(&items, 1, "key")?;
+            let count = if len == 3 {
+                let n = expect_int(&items, 2, "count")?;
+                if n < 0 {
+                    return Err(anyhow::anyhow!("value is out of range, must be positive"));
+                }
+                Some(n as usize)
+            } else {
+                None
+            };
 
-            Ok(Command::RPOP { key })
+            Ok(Command::RPOP { key, count })
         }
-        _ => Err(anyhow::anyhow!("unknown command")),
+        "HSET" => {
+            let len = items.len();
+            if len < 4 || !len.is_multiple_of(2) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hset' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut pairs: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+
+            for i in (2..len).step_by(2) {
+                let field = expect_bulk_bytes(&items, i, "field")?;
+                let value = expect_bulk_bytes(&items, i + 1, "value")?;
+                pairs.push((field, value));
+            }
+
+            Ok(Command::HSET { key, pairs })
+        }
+        "HSETNX" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hsetnx' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let field = expect_bulk_bytes(&items, 2, "field")?;
+            let value = expect_bulk_bytes(&items, 3, "value")?;
+
+            Ok(Command::HSETNX { key, field, value })
+        }
+        "HGET" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hget' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let field = expect_bulk_bytes(&items, 2, "field")?;
+
+            Ok(Command::HGET { key, field })
+        }
+        "HMGET" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hmget' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut fields: Vec<Vec<u8>> = Vec::new();
+
+            for i in 2..len {
+                fields.push(expect_bulk_bytes(&items, i, "field")?);
+            }
+
+            Ok(Command::HMGET { key, fields })
+        }
+        "HDEL" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hdel' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut fields: Vec<Vec<u8>> = Vec::new();
+
+            for i in 2..len {
+                fields.push(expect_bulk_bytes(&items, i, "field")?);
+            }
+
+            Ok(Command::HDEL { key, fields })
+        }
+        "HEXISTS" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hexists' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let field = expect_bulk_bytes(&items, 2, "field")?;
+
+            Ok(Command::HEXISTS { key, field })
+        }
+        "HLEN" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hlen' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            Ok(Command::HLEN { key })
+        }
+        "HSTRLEN" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hstrlen' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let field = expect_bulk_bytes(&items, 2, "field")?;
+
+            Ok(Command::HSTRLEN { key, field })
+        }
+        "HKEYS" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hkeys' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            Ok(Command::HKEYS { key })
+        }
+        "HVALS" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hvals' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            Ok(Command::HVALS { key })
+        }
+        "HGETALL" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hgetall' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            Ok(Command::HGETALL { key })
+        }
+        "HINCRBY" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hincrby' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let field = expect_bulk_bytes(&items, 2, "field")?;
+            let increment = expect_int(&items, 3, "increment")?;
+
+            Ok(Command::HINCRBY {
+                key,
+                field,
+                increment,
+            })
+        }
+        "HINCRBYFLOAT" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hincrbyfloat' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let field = expect_bulk_bytes(&items, 2, "field")?;
+            let increment = expect_float(&items, 3, "increment")?;
+
+            Ok(Command::HINCRBYFLOAT {
+                key,
+                field,
+                increment,
+            })
+        }
+        "HRANDFIELD" => {
+            let len = items.len();
+            if !(2..=4).contains(&len) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hrandfield' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let count = if len >= 3 {
+                Some(expect_random_count(&items, 2)?)
+            } else {
+                None
+            };
+            let with_values = if len == 4 {
+                if expect_bulk(&items, 3, "option")?.to_uppercase() != "WITHVALUES" {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                true
+            } else {
+                false
+            };
+
+            Ok(Command::HRANDFIELD {
+                key,
+                count,
+                with_values,
+            })
+        }
+        "HSCAN" => {
+            if items.len() < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'hscan' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let cursor = parse_cursor(&items, 2)?;
+            let (pattern, count, no_values, _) = parse_scan_options(&items, 3, "HSCAN")?;
+
+            Ok(Command::HSCAN {
+                key,
+                cursor,
+                pattern,
+                count,
+                no_values,
+            })
+        }
+        "SADD" | "SREM" | "SMISMEMBER" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut members: Vec<Vec<u8>> = Vec::new();
+
+            for i in 2..len {
+                members.push(expect_bulk_bytes(&items, i, "member")?);
+            }
+
+            match cmd.as_str() {
+                "SADD" => Ok(Command::SADD { key, members }),
+                "SREM" => Ok(Command::SREM { key, members }),
+                _ => Ok(Command::SMISMEMBER { key, members }),
+            }
+        }
+        "SISMEMBER" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'sismember' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let member = expect_bulk_bytes(&items, 2, "member")?;
+
+            Ok(Command::SISMEMBER { key, member })
+        }
+        "SMEMBERS" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'smembers' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            Ok(Command::SMEMBERS { key })
+        }
+        "SCARD" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'scard' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            Ok(Command::SCARD { key })
+        }
+        "SPOP" => {
+            let len = items.len();
+            if !(2..=3).contains(&len) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'spop' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let count = if len == 3 {
+                let n = expect_int(&items, 2, "count")?;
+                if n < 0 {
+                    return Err(anyhow::anyhow!("value is out of range, must be positive"));
+                }
+                Some(n as usize)
+            } else {
+                None
+            };
+
+            Ok(Command::SPOP { key, count })
+        }
+        "SRANDMEMBER" => {
+            let len = items.len();
+            if !(2..=3).contains(&len) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'srandmember' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let count = if len == 3 {
+                Some(expect_random_count(&items, 2)?)
+            } else {
+                None
+            };
+
+            Ok(Command::SRANDMEMBER { key, count })
+        }
+        "SMOVE" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'smove' command"
+                ));
+            }
+
+            let source = expect_bulk(&items, 1, "key")?;
+            let destination = expect_bulk(&items, 2, "key")?;
+            let member = expect_bulk_bytes(&items, 3, "member")?;
+
+            Ok(Command::SMOVE {
+                source,
+                destination,
+                member,
+            })
+        }
+        "SINTER" | "SUNION" | "SDIFF" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+            let mut keys: Vec<String> = Vec::new();
+
+            for i in 1..len {
+                keys.push(expect_bulk(&items, i, "key")?);
+            }
+
+            match cmd.as_str() {
+                "SINTER" => Ok(Command::SINTER { keys }),
+                "SUNION" => Ok(Command::SUNION { keys }),
+                _ => Ok(Command::SDIFF { keys }),
+            }
+        }
+        "SINTERSTORE" | "SUNIONSTORE" | "SDIFFSTORE" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let destination = expect_bulk(&items, 1, "key")?;
+            let mut keys: Vec<String> = Vec::new();
+
+            for i in 2..len {
+                keys.push(expect_bulk(&items, i, "key")?);
+            }
+
+            match cmd.as_str() {
+                "SINTERSTORE" => Ok(Command::SINTERSTORE { destination, keys }),
+                "SUNIONSTORE" => Ok(Command::SUNIONSTORE { destination, keys }),
+                _ => Ok(Command::SDIFFSTORE { destination, keys }),
+            }
+        }
+        "SINTERCARD" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'sintercard' command"
+                ));
+            }
+
+            let numkeys = expect_int(&items, 1, "numkeys")?;
+            if numkeys <= 0 {
+                return Err(anyhow::anyhow!("numkeys should be greater than 0"));
+            }
+            let numkeys = numkeys as usize;
+            if numkeys > len - 2 {
+                return Err(anyhow::anyhow!(
+                    "Number of keys can't be greater than number of args"
+                ));
+            }
+
+            let mut keys: Vec<String> = Vec::new();
+            for i in 2..2 + numkeys {
+                keys.push(expect_bulk(&items, i, "key")?);
+            }
+
+            let mut limit = 0usize;
+            let mut i = 2 + numkeys;
+            while i < len {
+                let opt = expect_bulk(&items, i, "option")?.to_uppercase();
+                if opt != "LIMIT" || i + 1 >= len {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                let n = expect_int(&items, i + 1, "limit")?;
+                if n < 0 {
+                    return Err(anyhow::anyhow!("LIMIT can't be negative"));
+                }
+                limit = n as usize;
+                i += 2;
+            }
+
+            Ok(Command::SINTERCARD { keys, limit })
+        }
+        "ZADD" => {
+            let len = items.len();
+            if len < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'zadd' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut flags = ZAddFlags::default();
+            let mut i = 2;
+            while i < len {
+                let Some(RESP::BulkStrings(Some(b))) = items.get(i) else {
+                    break;
+                };
+                match b.to_ascii_uppercase().as_slice() {
+                    b"NX" => flags.nx = true,
+                    b"XX" => flags.xx = true,
+                    b"GT" => flags.gt = true,
+                    b"LT" => flags.lt = true,
+                    b"CH" => flags.ch = true,
+                    b"INCR" => flags.incr = true,
+                    _ => break,
+                }
+                i += 1;
+            }
+
+            if flags.nx && flags.xx {
+                return Err(anyhow::anyhow!(
+                    "XX and NX options at the same time are not compatible"
+                ));
+            }
+            if (flags.gt && flags.lt) || (flags.nx && (flags.gt || flags.lt)) {
+                return Err(anyhow::anyhow!(
+                    "GT, LT, and/or NX options at the same time are not compatible"
+                ));
+            }
+            if i >= len || !(len - i).is_multiple_of(2) {
+                return Err(anyhow::anyhow!("syntax error"));
+            }
+            if flags.incr && len - i != 2 {
+                return Err(anyhow::anyhow!(
+                    "INCR option supports a single increment-element pair"
+                ));
+            }
+
+            let mut pairs: Vec<(f64, Vec<u8>)> = Vec::new();
+            for j in (i..len).step_by(2) {
+                let score = expect_float(&items, j, "score")?;
+                let member = expect_bulk_bytes(&items, j + 1, "member")?;
+                pairs.push((score, member));
+            }
+
+            Ok(Command::ZADD { key, flags, pairs })
+        }
+        "ZREM" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'zrem' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut members: Vec<Vec<u8>> = Vec::new();
+
+            for i in 2..len {
+                members.push(expect_bulk_bytes(&items, i, "member")?);
+            }
+
+            Ok(Command::ZREM { key, members })
+        }
+        "ZSCORE" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'zscore' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let member = expect_bulk_bytes(&items, 2, "member")?;
+
+            Ok(Command::ZSCORE { key, member })
+        }
+        "ZINCRBY" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'zincrby' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let increment = expect_float(&items, 2, "increment")?;
+            let member = expect_bulk_bytes(&items, 3, "member")?;
+
+            Ok(Command::ZINCRBY {
+                key,
+                increment,
+                member,
+            })
+        }
+        "ZCARD" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'zcard' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            Ok(Command::ZCARD { key })
+        }
+        "ZCOUNT" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'zcount' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let min = parse_score_bound(&items, 2)?;
+            let max = parse_score_bound(&items, 3)?;
+
+            Ok(Command::ZCOUNT { key, min, max })
+        }
+        "ZRANK" | "ZREVRANK" => {
+            let len = items.len();
+            if !(3..=4).contains(&len) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let member = expect_bulk_bytes(&items, 2, "member")?;
+            let with_score = if len == 4 {
+                if expect_bulk(&items, 3, "option")?.to_uppercase() != "WITHSCORE" {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                true
+            } else {
+                false
+            };
+
+            if cmd == "ZRANK" {
+                Ok(Command::ZRANK {
+                    key,
+                    member,
+                    with_score,
+                })
+            } else {
+                Ok(Command::ZREVRANK {
+                    key,
+                    member,
+                    with_score,
+                })
+            }
+        }
+        "ZRANGE" => {
+            if items.len() < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'zrange' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let spec = parse_zrange_spec(&items, 2, true)?;
+
+            Ok(Command::ZRANGE { key, spec })
+        }
+        "ZRANGESTORE" => {
+            if items.len() < 5 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'zrangestore' command"
+                ));
+            }
+
+            let destination = expect_bulk(&items, 1, "key")?;
+            let source = expect_bulk(&items, 2, "key")?;
+            let spec = parse_zrange_spec(&items, 3, false)?;
+
+            Ok(Command::ZRANGESTORE {
+                destination,
+                source,
+                spec,
+            })
+        }
+        "ZPOPMIN" | "ZPOPMAX" => {
+            let len = items.len();
+            if !(2..=3).contains(&len) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let count = if len == 3 {
+                let n = expect_int(&items, 2, "count")?;
+                if n < 0 {
+                    return Err(anyhow::anyhow!("value is out of range, must be positive"));
+                }
+                Some(n as usize)
+            } else {
+                None
+            };
+
+            if cmd == "ZPOPMIN" {
+                Ok(Command::ZPOPMIN { key, count })
+            } else {
+                Ok(Command::ZPOPMAX { key, count })
+            }
+        }
+        "ZUNIONSTORE" | "ZINTERSTORE" => {
+            let len = items.len();
+            if len < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let destination = expect_bulk(&items, 1, "key")?;
+            let numkeys = expect_int(&items, 2, "numkeys")?;
+            if numkeys <= 0 {
+                return Err(anyhow::anyhow!(
+                    "at least 1 input key is needed for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+            let numkeys = numkeys as usize;
+            if numkeys > len - 3 {
+                return Err(anyhow::anyhow!("syntax error"));
+            }
+
+            let mut keys: Vec<String> = Vec::new();
+            for i in 3..3 + numkeys {
+                keys.push(expect_bulk(&items, i, "key")?);
+            }
+
+            let mut weights = vec![1.0; numkeys];
+            let mut aggregate = Aggregate::Sum;
+            let mut i = 3 + numkeys;
+            while i < len {
+                let opt = expect_bulk(&items, i, "option")?.to_uppercase();
+                match opt.as_str() {
+                    "WEIGHTS" if i + numkeys < len => {
+                        for (w, weight) in weights.iter_mut().enumerate() {
+                            *weight = expect_bulk(&items, i + 1 + w, "weight")?
+                                .parse::<f64>()
+                                .ok()
+                                .filter(|v| !v.is_nan())
+                                .ok_or_else(|| anyhow::anyhow!("weight value is not a float"))?;
+                        }
+                        i += 1 + numkeys;
+                    }
+                    "AGGREGATE" if i + 1 < len => {
+                        aggregate = match expect_bulk(&items, i + 1, "aggregate")?
+                            .to_uppercase()
+                            .as_str()
+                        {
+                            "SUM" => Aggregate::Sum,
+                            "MIN" => Aggregate::Min,
+                            "MAX" => Aggregate::Max,
+                            _ => return Err(anyhow::anyhow!("syntax error")),
+                        };
+                        i += 2;
+                    }
+                    _ => return Err(anyhow::anyhow!("syntax error")),
+                }
+            }
+
+            if cmd == "ZUNIONSTORE" {
+                Ok(Command::ZUNIONSTORE {
+                    destination,
+                    keys,
+                    weights,
+                    aggregate,
+                })
+            } else {
+                Ok(Command::ZINTERSTORE {
+                    destination,
+                    keys,
+                    weights,
+                    aggregate,
+                })
+            }
+        }
+        "XADD" => {
+            let len = items.len();
+            if len < 5 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xadd' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut nomkstream = false;
+            let mut trim = None;
+            let mut i = 2;
+            while i < len {
+                let opt = expect_bulk_bytes(&items, i, "option")?.to_ascii_uppercase();
+                match opt.as_slice() {
+                    b"NOMKSTREAM" => {
+                        nomkstream = true;
+                        i += 1;
+                    }
+                    b"MAXLEN" | b"MINID" => {
+                        let (parsed, next) = parse_stream_trim(&items, i)?;
+                        trim = Some(parsed);
+                        i = next;
+                    }
+                    _ => break,
+                }
+            }
+
+            if i + 1 >= len || !(len - i - 1).is_multiple_of(2) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xadd' command"
+                ));
+            }
+
+            let raw = expect_bulk_bytes(&items, i, "id")?;
+            let id = if raw == b"*" {
+                StreamIdSpec::Auto
+            } else if let Some(ms) = raw.strip_suffix(b"-*") {
+                match std::str::from_utf8(ms).ok().and_then(|ms| ms.parse().ok()) {
+                    Some(ms) => StreamIdSpec::AutoSeq(ms),
+                    None => return Err(anyhow::anyhow!(INVALID_STREAM_ID)),
+                }
+            } else {
+                let id = parse_stream_id(&items, i, 0)?;
+                if id == StreamId::MIN {
+                    return Err(anyhow::anyhow!(
+                        "The ID specified in XADD must be greater than 0-0"
+                    ));
+                }
+                StreamIdSpec::Explicit(id)
+            };
+
+            let mut fields = Vec::new();
+            for j in (i + 1..len).step_by(2) {
+                let field = expect_bulk_bytes(&items, j, "field")?;
+                let value = expect_bulk_bytes(&items, j + 1, "value")?;
+                fields.push((field, value));
+            }
+
+            Ok(Command::XADD {
+                key,
+                nomkstream,
+                trim,
+                id,
+                fields,
+            })
+        }
+        "XRANGE" | "XREVRANGE" => {
+            let len = items.len();
+            if len != 4 && len != 6 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let rev = cmd == "XREVRANGE";
+            let (low, high) = if rev { (3, 2) } else { (2, 3) };
+            let start = parse_stream_range_bound(&items, low, true)?;
+            let end = parse_stream_range_bound(&items, high, false)?;
+
+            let mut count = None;
+            if len == 6 {
+                let opt = expect_bulk(&items, 4, "option")?.to_uppercase();
+                if opt != "COUNT" {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                count = Some(expect_int(&items, 5, "count")?.max(0) as usize);
+            }
+
+            if rev {
+                Ok(Command::XREVRANGE {
+                    key,
+                    start,
+                    end,
+                    count,
+                })
+            } else {
+                Ok(Command::XRANGE {
+                    key,
+                    start,
+                    end,
+                    count,
+                })
+            }
+        }
+        "XLEN" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xlen' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            Ok(Command::XLEN { key })
+        }
+        "XDEL" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xdel' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut ids = Vec::new();
+            for i in 2..len {
+                ids.push(parse_stream_id(&items, i, 0)?);
+            }
+
+            Ok(Command::XDEL { key, ids })
+        }
+        "XTRIM" => {
+            let len = items.len();
+            if len < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xtrim' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let opt = expect_bulk(&items, 2, "strategy")?.to_uppercase();
+            if opt != "MAXLEN" && opt != "MINID" {
+                return Err(anyhow::anyhow!("syntax error"));
+            }
+            let (trim, next) = parse_stream_trim(&items, 2)?;
+            if next != len {
+                return Err(anyhow::anyhow!("syntax error"));
+            }
+
+            Ok(Command::XTRIM { key, trim })
+        }
+        "XREAD" => {
+            let len = items.len();
+            if len < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xread' command"
+                ));
+            }
+
+            let mut count = None;
+            let mut block = None;
+            let mut i = 1;
+            loop {
+                if i >= len {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                let opt = expect_bulk(&items, i, "option")?.to_uppercase();
+                match opt.as_str() {
+                    "COUNT" if i + 1 < len => {
+                        count = parse_read_count(&items, i + 1)?;
+                        i += 2;
+                    }
+                    "BLOCK" if i + 1 < len => {
+                        block = Some(parse_block_timeout(&items, i + 1)?);
+                        i += 2;
+                    }
+                    "STREAMS" => {
+                        i += 1;
+                        break;
+                    }
+                    _ => return Err(anyhow::anyhow!("syntax error")),
+                }
+            }
+
+            let (keys, ids) = parse_read_streams(&items, i, false)?;
+
+            Ok(Command::XREAD {
+                keys,
+                ids,
+                count,
+                block,
+            })
+        }
+        "XREADGROUP" => {
+            let len = items.len();
+            if len < 7 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xreadgroup' command"
+                ));
+            }
+
+            if expect_bulk(&items, 1, "option")?.to_uppercase() != "GROUP" {
+                return Err(anyhow::anyhow!("Missing GROUP option for XREADGROUP"));
+            }
+            let group = expect_bulk_bytes(&items, 2, "group")?;
+            let consumer = expect_bulk_bytes(&items, 3, "consumer")?;
+
+            let mut count = None;
+            let mut block = None;
+            let mut noack = false;
+            let mut i = 4;
+            loop {
+                if i >= len {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                let opt = expect_bulk(&items, i, "option")?.to_uppercase();
+                match opt.as_str() {
+                    "COUNT" if i + 1 < len => {
+                        count = parse_read_count(&items, i + 1)?;
+                        i += 2;
+                    }
+                    "BLOCK" if i + 1 < len => {
+                        block = Some(parse_block_timeout(&items, i + 1)?);
+                        i += 2;
+                    }
+                    "NOACK" => {
+                        noack = true;
+                        i += 1;
+                    }
+                    "STREAMS" => {
+                        i += 1;
+                        break;
+                    }
+                    _ => return Err(anyhow::anyhow!("syntax error")),
+                }
+            }
+
+            let (keys, ids) = parse_read_streams(&items, i, true)?;
+
+            Ok(Command::XREADGROUP {
+                group,
+                consumer,
+                keys,
+                ids,
+                count,
+                block,
+                noack,
+            })
+        }
+        "XGROUP" => {
+            if items.len() < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xgroup' command"
+                ));
+            }
+
+            let sub = expect_bulk(&items, 1, "subcommand")?.to_uppercase();
+            let arity_err = || {
+                anyhow::anyhow!(
+                    "wrong number of arguments for 'xgroup|{}' command",
+                    sub.to_lowercase()
+                )
+            };
+            match sub.as_str() {
+                "CREATE" | "SETID" => {
+                    let len = items.len();
+                    if len < 5 {
+                        return Err(arity_err());
+                    }
+                    let key = expect_bulk(&items, 2, "key")?;
+                    let group = expect_bulk_bytes(&items, 3, "group")?;
+                    let id = if expect_bulk_bytes(&items, 4, "id")? == b"$" {
+                        StreamReadId::Last
+                    } else {
+                        StreamReadId::After(parse_stream_id(&items, 4, 0)?)
+                    };
+
+                    let mut mkstream = false;
+                    let mut entries_read = None;
+                    let mut i = 5;
+                    while i < len {
+                        let opt = expect_bulk(&items, i, "option")?.to_uppercase();
+                        match opt.as_str() {
+                            "MKSTREAM" if sub == "CREATE" => {
+                                mkstream = true;
+                                i += 1;
+                            }
+                            "ENTRIESREAD" if i + 1 < len => {
+                                let n = expect_int(&items, i + 1, "entries_read")?;
+                                if n < -1 {
+                                    return Err(anyhow::anyhow!(
+                                        "value for ENTRIESREAD must be positive or -1"
+                                    ));
+                                }
+                                entries_read = (n >= 0).then_some(n as u64);
+                                i += 2;
+                            }
+                            _ => return Err(anyhow::anyhow!("syntax error")),
+                        }
+                    }
+
+                    if sub == "CREATE" {
+                        Ok(Command::XGroupCreate {
+                            key,
+                            group,
+                            id,
+                            mkstream,
+                            entries_read,
+                        })
+                    } else {
+                        Ok(Command::XGroupSetId {
+                            key,
+                            group,
+                            id,
+                            entries_read,
+                        })
+                    }
+                }
+                "DESTROY" => {
+                    if items.len() != 4 {
+                        return Err(arity_err());
+                    }
+                    let key = expect_bulk(&items, 2, "key")?;
+                    let group = expect_bulk_bytes(&items, 3, "group")?;
+                    Ok(Command::XGroupDestroy { key, group })
+                }
+                "CREATECONSUMER" | "DELCONSUMER" => {
+                    if items.len() != 5 {
+                        return Err(arity_err());
+                    }
+                    let key = expect_bulk(&items, 2, "key")?;
+                    let group = expect_bulk_bytes(&items, 3, "group")?;
+                    let consumer = expect_bulk_bytes(&items, 4, "consumer")?;
+                    if sub == "CREATECONSUMER" {
+                        Ok(Command::XGroupCreateConsumer {
+                            key,
+                            group,
+                            consumer,
+                        })
+                    } else {
+                        Ok(Command::XGroupDelConsumer {
+                            key,
+                            group,
+                            consumer,
+                        })
+                    }
+                }
+                _ => Err(anyhow::anyhow!(
+                    "unknown subcommand '{}'. Try XGROUP HELP.",
+                    sub.to_lowercase()
+                )),
+            }
+        }
+        "XACK" => {
+            let len = items.len();
+            if len < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xack' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let group = expect_bulk_bytes(&items, 2, "group")?;
+            let mut ids = Vec::new();
+            for i in 3..len {
+                ids.push(parse_stream_id(&items, i, 0)?);
+            }
+
+            Ok(Command::XACK { key, group, ids })
+        }
+        "XPENDING" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xpending' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let group = expect_bulk_bytes(&items, 2, "group")?;
+
+            let mut range = None;
+            let mut idle = None;
+            let mut consumer = None;
+            if len > 3 {
+                let mut i = 3;
+                if expect_bulk(&items, i, "option")?.to_uppercase() == "IDLE" {
+                    if i + 1 >= len {
+                        return Err(anyhow::anyhow!("syntax error"));
+                    }
+                    idle = Some(expect_int(&items, i + 1, "idle")?.max(0) as u64);
+                    i += 2;
+                }
+                if len != i + 3 && len != i + 4 {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                let start = parse_stream_range_bound(&items, i, true)?;
+                let end = parse_stream_range_bound(&items, i + 1, false)?;
+                let count = expect_int(&items, i + 2, "count")?.max(0) as usize;
+                range = Some((start, end, count));
+                if len == i + 4 {
+                    consumer = Some(expect_bulk_bytes(&items, i + 3, "consumer")?);
+                }
+            }
+
+            Ok(Command::XPENDING {
+                key,
+                group,
+                range,
+                idle,
+                consumer,
+            })
+        }
+        "XCLAIM" => {
+            let len = items.len();
+            if len < 6 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xclaim' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let group = expect_bulk_bytes(&items, 2, "group")?;
+            let consumer = expect_bulk_bytes(&items, 3, "consumer")?;
+            let min_idle = expect_bulk(&items, 4, "min-idle-time")?
+                .parse::<i64>()
+                .map_err(|_| anyhow::anyhow!("Invalid min-idle-time argument for XCLAIM"))?
+                .max(0) as u64;
+
+            let mut ids = Vec::new();
+            let mut i = 5;
+            while i < len {
+                let raw = expect_bulk_bytes(&items, i, "id")?;
+                let Some(id) = StreamId::parse(&raw, 0) else {
+                    break;
+                };
+                ids.push(id);
+                i += 1;
+            }
+
+            let mut options = XClaimOptions::default();
+            while i < len {
+                let opt = expect_bulk(&items, i, "option")?.to_uppercase();
+                match opt.as_str() {
+                    "FORCE" => options.force = true,
+                    "JUSTID" => options.justid = true,
+                    "IDLE" if i + 1 < len => {
+                        options.idle = Some(expect_int(&items, i + 1, "idle")?.max(0) as u64);
+                        i += 1;
+                    }
+                    "TIME" if i + 1 < len => {
+                        options.time = Some(expect_int(&items, i + 1, "time")?.max(0) as u64);
+                        i += 1;
+                    }
+                    "RETRYCOUNT" if i + 1 < len => {
+                        options.retry_count =
+                            Some(expect_int(&items, i + 1, "retrycount")?.max(0) as u64);
+                        i += 1;
+                    }
+                    "LASTID" if i + 1 < len => {
+                        options.last_id = Some(parse_stream_id(&items, i + 1, 0)?);
+                        i += 1;
+                    }
+                    _ => {
+                        return Err(anyhow::anyhow!(
+                            "Unrecognized XCLAIM option '{}'",
+                            expect_bulk(&items, i, "option")?
+                        ));
+                    }
+                }
+                i += 1;
+            }
+
+            Ok(Command::XCLAIM {
+                key,
+                group,
+                consumer,
+                min_idle,
+                ids,
+                options,
+            })
+        }
+        "XAUTOCLAIM" => {
+            let len = items.len();
+            if len < 6 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xautoclaim' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let group = expect_bulk_bytes(&items, 2, "group")?;
+            let consumer = expect_bulk_bytes(&items, 3, "consumer")?;
+            let min_idle = expect_bulk(&items, 4, "min-idle-time")?
+                .parse::<i64>()
+                .map_err(|_| anyhow::anyhow!("Invalid min-idle-time argument for XAUTOCLAIM"))?
+                .max(0) as u64;
+            let start = parse_stream_range_bound(&items, 5, true)?;
+
+            let mut count = 100usize;
+            let mut justid = false;
+            let mut i = 6;
+            while i < len {
+                let opt = expect_bulk(&items, i, "option")?.to_uppercase();
+                match opt.as_str() {
+                    "COUNT" if i + 1 < len => {
+                        let n = expect_int(&items, i + 1, "count")?;
+                        if n < 1 {
+                            return Err(anyhow::anyhow!("COUNT must be > 0"));
+                        }
+                        count = n as usize;
+                        i += 2;
+                    }
+                    "JUSTID" => {
+                        justid = true;
+                        i += 1;
+                    }
+                    _ => return Err(anyhow::anyhow!("syntax error")),
+                }
+            }
+
+            Ok(Command::XAUTOCLAIM {
+                key,
+                group,
+                consumer,
+                min_idle,
+                start,
+                count,
+                justid,
+            })
+        }
+        "XINFO" => {
+            if items.len() < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xinfo' command"
+                ));
+            }
+
+            let sub = expect_bulk(&items, 1, "subcommand")?.to_uppercase();
+            let arity = match sub.as_str() {
+                "STREAM" | "GROUPS" => 3,
+                "CONSUMERS" => 4,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "unknown subcommand '{}'. Try XINFO HELP.",
+                        sub.to_lowercase()
+                    ));
+                }
+            };
+            if items.len() != arity {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'xinfo|{}' command",
+                    sub.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 2, "key")?;
+            match sub.as_str() {
+                "STREAM" => Ok(Command::XInfoStream { key }),
+                "GROUPS" => Ok(Command::XInfoGroups { key }),
+                _ => {
+                    let group = expect_bulk_bytes(&items, 3, "group")?;
+                    Ok(Command::XInfoConsumers { key, group })
+                }
+            }
+        }
+        "LPUSHX" | "RPUSHX" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut values: Vec<Vec<u8>> = Vec::new();
+            for i in 2..len {
+                values.push(expect_bulk_bytes(&items, i, "value")?);
+            }
+
+            if cmd == "LPUSHX" {
+                Ok(Command::LPUSHX { key, values })
+            } else {
+                Ok(Command::RPUSHX { key, values })
+            }
+        }
+        "LRANGE" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'lrange' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let start = expect_int(&items, 2, "start")?;
+            let stop = expect_int(&items, 3, "stop")?;
+
+            Ok(Command::LRANGE { key, start, stop })
+        }
+        "LINDEX" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'lindex' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let index = expect_int(&items, 2, "index")?;
+
+            Ok(Command::LINDEX { key, index })
+        }
+        "LLEN" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'llen' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            Ok(Command::LLEN { key })
+        }
+        "LSET" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'lset' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let index = expect_int(&items, 2, "index")?;
+            let value = expect_bulk_bytes(&items, 3, "value")?;
+
+            Ok(Command::LSET { key, index, value })
+        }
+        "LINSERT" => {
+            if items.len() != 5 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'linsert' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let before = match expect_bulk(&items, 2, "where")?.to_uppercase().as_str() {
+                "BEFORE" => true,
+                "AFTER" => false,
+                _ => return Err(anyhow::anyhow!("syntax error")),
+            };
+            let pivot = expect_bulk_bytes(&items, 3, "pivot")?;
+            let value = expect_bulk_bytes(&items, 4, "value")?;
+
+            Ok(Command::LINSERT {
+                key,
+                before,
+                pivot,
+                value,
+            })
+        }
+        "LREM" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'lrem' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let count = expect_int(&items, 2, "count")?;
+            let value = expect_bulk_bytes(&items, 3, "value")?;
+
+            Ok(Command::LREM { key, count, value })
+        }
+        "LTRIM" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'ltrim' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let start = expect_int(&items, 2, "start")?;
+            let stop = expect_int(&items, 3, "stop")?;
+
+            Ok(Command::LTRIM { key, start, stop })
+        }
+        "LPOS" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'lpos' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let element = expect_bulk_bytes(&items, 2, "element")?;
+
+            let mut rank = 1i64;
+            let mut count = None;
+            let mut maxlen = 0usize;
+            let mut i = 3;
+            while i < len {
+                let opt = expect_bulk(&items, i, "option")?.to_uppercase();
+                if i + 1 >= len {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                let n = expect_int(&items, i + 1, "value")?;
+                match opt.as_str() {
+                    "RANK" => {
+                        if n == 0 || n == i64::MIN {
+                            return Err(anyhow::anyhow!(
+                                "RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the last match"
+                            ));
+                        }
+                        rank = n;
+                    }
+                    "COUNT" => {
+                        if n < 0 {
+                            return Err(anyhow::anyhow!("COUNT can't be negative"));
+                        }
+                        count = Some(n as usize);
+                    }
+                    "MAXLEN" => {
+                        if n < 0 {
+                            return Err(anyhow::anyhow!("MAXLEN can't be negative"));
+                        }
+                        maxlen = n as usize;
+                    }
+                    _ => return Err(anyhow::anyhow!("syntax error")),
+                }
+                i += 2;
+            }
+
+            Ok(Command::LPOS {
+                key,
+                element,
+                rank,
+                count,
+                maxlen,
+            })
+        }
+        "BLPOP" | "BRPOP" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let mut keys = Vec::new();
+            for i in 1..len - 1 {
+                keys.push(expect_bulk(&items, i, "key")?);
+            }
+            let timeout = parse_list_timeout(&items, len - 1)?;
+
+            if cmd == "BLPOP" {
+                Ok(Command::BLPOP { keys, timeout })
+            } else {
+                Ok(Command::BRPOP { keys, timeout })
+            }
+        }
+        "BLMOVE" => {
+            if items.len() != 6 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'blmove' command"
+                ));
+            }
+
+            let source = expect_bulk(&items, 1, "source")?;
+            let destination = expect_bulk(&items, 2, "destination")?;
+            let from_left = parse_list_side(&items, 3)?;
+            let to_left = parse_list_side(&items, 4)?;
+            let timeout = parse_list_timeout(&items, 5)?;
+
+            Ok(Command::BLMOVE {
+                source,
+                destination,
+                from_left,
+                to_left,
+                timeout,
+            })
+        }
+        "BLMPOP" => {
+            if items.len() < 5 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'blmpop' command"
+                ));
+            }
+
+            let timeout = parse_list_timeout(&items, 1)?;
+            let (keys, left, count) = parse_mpop(&items, 2)?;
+
+            Ok(Command::BLMPOP {
+                keys,
+                left,
+                count,
+                timeout,
+            })
+        }
+        "LMOVE" => {
+            if items.len() != 5 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'lmove' command"
+                ));
+            }
+
+            let source = expect_bulk(&items, 1, "source")?;
+            let destination = expect_bulk(&items, 2, "destination")?;
+            let from_left = parse_list_side(&items, 3)?;
+            let to_left = parse_list_side(&items, 4)?;
+
+            Ok(Command::LMOVE {
+                source,
+                destination,
+                from_left,
+                to_left,
+            })
+        }
+        "RPOPLPUSH" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'rpoplpush' command"
+                ));
+            }
+
+            let source = expect_bulk(&items, 1, "source")?;
+            let destination = expect_bulk(&items, 2, "destination")?;
+
+            Ok(Command::RPOPLPUSH {
+                source,
+                destination,
+            })
+        }
+        "LMPOP" => {
+            if items.len() < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'lmpop' command"
+                ));
+            }
+
+            let (keys, left, count) = parse_mpop(&items, 1)?;
+
+            Ok(Command::LMPOP { keys, left, count })
+        }
+        "SETBIT" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'setbit' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let offset = parse_bit_offset(&items, 2)?;
+            let value = match expect_bulk(&items, 3, "value")?.as_str() {
+                "0" => false,
+                "1" => true,
+                _ => return Err(anyhow::anyhow!("bit is not an integer or out of range")),
+            };
+
+            Ok(Command::SETBIT { key, offset, value })
+        }
+        "GETBIT" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'getbit' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let offset = parse_bit_offset(&items, 2)?;
+
+            Ok(Command::GETBIT { key, offset })
+        }
+        "BITCOUNT" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'bitcount' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let (range, bit_unit) = match len {
+                2 => (None, false),
+                4 | 5 => {
+                    let start = expect_int(&items, 2, "start")?;
+                    let end = expect_int(&items, 3, "end")?;
+                    let bit_unit = len == 5 && parse_bit_unit(&items, 4)?;
+                    (Some((start, end)), bit_unit)
+                }
+                _ => return Err(anyhow::anyhow!("syntax error")),
+            };
+
+            Ok(Command::BITCOUNT {
+                key,
+                range,
+                bit_unit,
+            })
+        }
+        "BITPOS" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'bitpos' command"
+                ));
+            }
+            if len > 6 {
+                return Err(anyhow::anyhow!("syntax error"));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let bit = match expect_int(&items, 2, "bit")? {
+                0 => false,
+                1 => true,
+                _ => return Err(anyhow::anyhow!("The bit argument must be 1 or 0.")),
+            };
+            let start = if len > 3 {
+                expect_int(&items, 3, "start")?
+            } else {
+                0
+            };
+            let end = if len > 4 {
+                Some(expect_int(&items, 4, "end")?)
+            } else {
+                None
+            };
+            let bit_unit = len == 6 && parse_bit_unit(&items, 5)?;
+
+            Ok(Command::BITPOS {
+                key,
+                bit,
+                start,
+                end,
+                bit_unit,
+            })
+        }
+        "BITOP" => {
+            let len = items.len();
+            if len < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'bitop' command"
+                ));
+            }
+
+            let op = match expect_bulk(&items, 1, "operation")?.to_uppercase().as_str() {
+                "AND" => BitOp::And,
+                "OR" => BitOp::Or,
+                "XOR" => BitOp::Xor,
+                "NOT" => BitOp::Not,
+                _ => return Err(anyhow::anyhow!("syntax error")),
+            };
+            if matches!(op, BitOp::Not) && len != 4 {
+                return Err(anyhow::anyhow!(
+                    "BITOP NOT must be called with a single source key."
+                ));
+            }
+
+            let destination = expect_bulk(&items, 2, "destkey")?;
+            let mut keys = Vec::new();
+            for i in 3..len {
+                keys.push(expect_bulk(&items, i, "key")?);
+            }
+
+            Ok(Command::BITOP {
+                op,
+                destination,
+                keys,
+            })
+        }
+        "BITFIELD" | "BITFIELD_RO" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let readonly = cmd == "BITFIELD_RO";
+            let mut ops = Vec::new();
+            let mut i = 2;
+            while i < len {
+                let sub = expect_bulk(&items, i, "subcommand")?.to_uppercase();
+                if readonly && sub != "GET" {
+                    return Err(anyhow::anyhow!(
+                        "BITFIELD_RO only supports the GET subcommand"
+                    ));
+                }
+                match sub.as_str() {
+                    "GET" if i + 2 < len => {
+                        let ty = parse_bitfield_type(&items, i + 1)?;
+                        let offset = parse_bitfield_offset(&items, i + 2, ty)?;
+                        ops.push(BitFieldOp::Get { ty, offset });
+                        i += 3;
+                    }
+                    "SET" if i + 3 < len => {
+                        let ty = parse_bitfield_type(&items, i + 1)?;
+                        let offset = parse_bitfield_offset(&items, i + 2, ty)?;
+                        let value = expect_int(&items, i + 3, "value")?;
+                        ops.push(BitFieldOp::Set { ty, offset, value });
+                        i += 4;
+                    }
+                    "INCRBY" if i + 3 < len => {
+                        let ty = parse_bitfield_type(&items, i + 1)?;
+                        let offset = parse_bitfield_offset(&items, i + 2, ty)?;
+                        let increment = expect_int(&items, i + 3, "increment")?;
+                        ops.push(BitFieldOp::IncrBy {
+                            ty,
+                            offset,
+                            increment,
+                        });
+                        i += 4;
+                    }
+                    "OVERFLOW" if i + 1 < len => {
+                        let mode = match expect_bulk(&items, i + 1, "overflow")?
+                            .to_uppercase()
+                            .as_str()
+                        {
+                            "WRAP" => BitOverflow::Wrap,
+                            "SAT" => BitOverflow::Sat,
+                            "FAIL" => BitOverflow::Fail,
+                            _ => return Err(anyhow::anyhow!("Invalid OVERFLOW type specified")),
+                        };
+                        ops.push(BitFieldOp::Overflow(mode));
+                        i += 2;
+                    }
+                    _ => return Err(anyhow::anyhow!("syntax error")),
+                }
+            }
+
+            if readonly {
+                let gets = ops
+                    .into_iter()
+                    .filter_map(|op| match op {
+                        BitFieldOp::Get { ty, offset } => Some((ty, offset)),
+                        _ => None,
+                    })
+                    .collect();
+                Ok(Command::BITFIELD_RO { key, gets })
+            } else {
+                Ok(Command::BITFIELD { key, ops })
+            }
+        }
+        "PFADD" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'pfadd' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut elements = Vec::new();
+            for i in 2..len {
+                elements.push(expect_bulk_bytes(&items, i, "element")?);
+            }
+
+            Ok(Command::PFADD { key, elements })
+        }
+        "PFCOUNT" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'pfcount' command"
+                ));
+            }
+
+            let mut keys = Vec::new();
+            for i in 1..len {
+                keys.push(expect_bulk(&items, i, "key")?);
+            }
+
+            Ok(Command::PFCOUNT { keys })
+        }
+        "PFMERGE" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'pfmerge' command"
+                ));
+            }
+
+            let destination = expect_bulk(&items, 1, "destkey")?;
+            let mut keys = Vec::new();
+            for i in 2..len {
+                keys.push(expect_bulk(&items, i, "key")?);
+            }
+
+            Ok(Command::PFMERGE { destination, keys })
+        }
+        "GEOADD" => {
+            let len = items.len();
+            if len < 5 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'geoadd' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut flags = ZAddFlags::default();
+            let mut i = 2;
+            while i < len {
+                match expect_bulk(&items, i, "option")?.to_uppercase().as_str() {
+                    "NX" => flags.nx = true,
+                    "XX" => flags.xx = true,
+                    "CH" => flags.ch = true,
+                    _ => break,
+                }
+                i += 1;
+            }
+
+            if flags.nx && flags.xx {
+                return Err(anyhow::anyhow!(
+                    "XX and NX options at the same time are not compatible"
+                ));
+            }
+            if i >= len || !(len - i).is_multiple_of(3) {
+                return Err(anyhow::anyhow!("syntax error"));
+            }
+
+            let mut points = Vec::new();
+            for j in (i..len).step_by(3) {
+                let (lon, lat) = parse_lon_lat(&items, j)?;
+                let member = expect_bulk_bytes(&items, j + 2, "member")?;
+                points.push((lon, lat, member));
+            }
+
+            Ok(Command::GEOADD { key, flags, points })
+        }
+        "GEOPOS" | "GEOHASH" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut members = Vec::new();
+            for i in 2..len {
+                members.push(expect_bulk_bytes(&items, i, "member")?);
+            }
+
+            if cmd == "GEOPOS" {
+                Ok(Command::GEOPOS { key, members })
+            } else {
+                Ok(Command::GEOHASH { key, members })
+            }
+        }
+        "GEODIST" => {
+            let len = items.len();
+            if len != 4 && len != 5 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'geodist' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let member1 = expect_bulk_bytes(&items, 2, "member")?;
+            let member2 = expect_bulk_bytes(&items, 3, "member")?;
+            let unit = if len == 5 {
+                parse_geo_unit(&items, 4)?
+            } else {
+                GeoUnit::M
+            };
+
+            Ok(Command::GEODIST {
+                key,
+                member1,
+                member2,
+                unit,
+            })
+        }
+        "GEOSEARCH" => {
+            if items.len() < 7 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'geosearch' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let (search, _) = parse_geo_search(&items, 2, "geosearch")?;
+
+            Ok(Command::GEOSEARCH { key, search })
+        }
+        "GEOSEARCHSTORE" => {
+            if items.len() < 8 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'geosearchstore' command"
+                ));
+            }
+
+            let destination = expect_bulk(&items, 1, "destination")?;
+            let source = expect_bulk(&items, 2, "source")?;
+            let (search, store_dist) = parse_geo_search(&items, 3, "geosearchstore")?;
+
+            Ok(Command::GEOSEARCHSTORE {
+                destination,
+                source,
+                search,
+                store_dist,
+            })
+        }
+        "JSON.SET" => {
+            let len = items.len();
+            if !(4..=5).contains(&len) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'json.set' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let path = parse_json_path(&items, 2)?;
+            let value = parse_json_value(&items, 3)?;
+            let (mut nx, mut xx) = (false, false);
+            if len == 5 {
+                match expect_bulk(&items, 4, "option")?.to_uppercase().as_str() {
+                    "NX" => nx = true,
+                    "XX" => xx = true,
+                    _ => return Err(anyhow::anyhow!("syntax error")),
+                }
+            }
+
+            Ok(Command::JsonSet {
+                key,
+                path,
+                value,
+                nx,
+                xx,
+            })
+        }
+        "JSON.GET" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'json.get' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut paths = Vec::new();
+            for i in 2..len {
+                paths.push(parse_json_path(&items, i)?);
+            }
+            if paths.is_empty() {
+                paths.push(JsonPath::root());
+            }
+
+            Ok(Command::JsonGet { key, paths })
+        }
+        "JSON.DEL" | "JSON.TYPE" | "JSON.STRLEN" | "JSON.OBJKEYS" => {
+            let len = items.len();
+            if !(2..=3).contains(&len) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let path = if len == 3 {
+                parse_json_path(&items, 2)?
+            } else {
+                JsonPath::root()
+            };
+
+            Ok(match cmd.as_str() {
+                "JSON.DEL" => Command::JsonDel { key, path },
+                "JSON.TYPE" => Command::JsonType { key, path },
+                "JSON.STRLEN" => Command::JsonStrLen { key, path },
+                _ => Command::JsonObjKeys { key, path },
+            })
+        }
+        "JSON.NUMINCRBY" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'json.numincrby' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let path = parse_json_path(&items, 2)?;
+            let by = parse_json_value(&items, 3)?;
+            if !matches!(by, Json::Int(_) | Json::Float(_)) {
+                return Err(anyhow::anyhow!("value is not a number"));
+            }
+
+            Ok(Command::JsonNumIncrBy { key, path, by })
+        }
+        "JSON.ARRAPPEND" => {
+            let len = items.len();
+            if len < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'json.arrappend' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let path = parse_json_path(&items, 2)?;
+            let mut values = Vec::new();
+            for i in 3..len {
+                values.push(parse_json_value(&items, i)?);
+            }
+
+            Ok(Command::JsonArrAppend { key, path, values })
+        }
+        "BF.RESERVE" => {
+            let len = items.len();
+            if len < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'bf.reserve' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let error_rate = expect_float(&items, 2, "error_rate")?;
+            if !(error_rate > 0.0 && error_rate < 1.0) {
+                return Err(anyhow::anyhow!("error rate should be between 0 and 1"));
+            }
+            let capacity = expect_int(&items, 3, "capacity")?;
+            if capacity <= 0 {
+                return Err(anyhow::anyhow!("capacity should be larger than 0"));
+            }
+            if BloomFilter::footprint(error_rate, capacity as u64).is_none() {
+                return Err(anyhow::anyhow!("filter would exceed the maximum size"));
+            }
+
+            let mut expansion = Some(BF_DEFAULT_EXPANSION);
+            let mut nonscaling = false;
+            let mut explicit_expansion = false;
+            let mut i = 4;
+            while i < len {
+                match expect_bulk(&items, i, "option")?.to_uppercase().as_str() {
+                    "EXPANSION" if i + 1 < len => {
+                        let rate = expect_int(&items, i + 1, "expansion")?;
+                        if rate < 1 || rate > u32::MAX as i64 {
+                            return Err(anyhow::anyhow!(
+                                "expansion should be greater or equal to 1"
+                            ));
+                        }
+                        expansion = Some(rate as u32);
+                        explicit_expansion = true;
+                        i += 2;
+                    }
+                    "NONSCALING" => {
+                        nonscaling = true;
+                        i += 1;
+                    }
+                    _ => return Err(anyhow::anyhow!("syntax error")),
+                }
+            }
+            if nonscaling {
+                if explicit_expansion {
+                    return Err(anyhow::anyhow!("nonscaling filters cannot expand"));
+                }
+                expansion = None;
+            }
+
+            Ok(Command::BfReserve {
+                key,
+                error_rate,
+                capacity: capacity as u64,
+                expansion,
+            })
+        }
+        "BF.ADD" | "BF.EXISTS" | "CF.ADD" | "CF.DEL" | "CF.EXISTS" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let item = expect_bulk_bytes(&items, 2, "item")?;
+
+            Ok(match cmd.as_str() {
+                "BF.ADD" => Command::BfAdd { key, item },
+                "BF.EXISTS" => Command::BfExists { key, item },
+                "CF.ADD" => Command::CfAdd { key, item },
+                "CF.DEL" => Command::CfDel { key, item },
+                _ => Command::CfExists { key, item },
+            })
+        }
+        "BF.MADD" | "BF.MEXISTS" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut filter_items = Vec::new();
+            for i in 2..len {
+                filter_items.push(expect_bulk_bytes(&items, i, "item")?);
+            }
+
+            Ok(if cmd == "BF.MADD" {
+                Command::BfMAdd {
+                    key,
+                    items: filter_items,
+                }
+            } else {
+                Command::BfMExists {
+                    key,
+                    items: filter_items,
+                }
+            })
+        }
+        "BF.INFO" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'bf.info' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+
+            Ok(Command::BfInfo { key })
+        }
+        "CMS.INITBYDIM" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'cms.initbydim' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let width = parse_sketch_dim(&items, 2, "width")?;
+            let depth = parse_sketch_dim(&items, 3, "depth")?;
+            check_sketch_size(width, depth, "CMS")?;
+
+            Ok(Command::CmsInitByDim { key, width, depth })
+        }
+        "CMS.INITBYPROB" => {
+            if items.len() != 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'cms.initbyprob' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let error = expect_float(&items, 2, "error")?;
+            if !(error > 0.0 && error < 1.0) {
+                return Err(anyhow::anyhow!("CMS: invalid overestimation value"));
+            }
+            let probability = expect_float(&items, 3, "probability")?;
+            if !(probability > 0.0 && probability < 1.0) {
+                return Err(anyhow::anyhow!("CMS: invalid prob value"));
+            }
+            let (width, depth) = CountMinSketch::dims_for(error, probability);
+            if width > SKETCH_MAX_DIM || depth > SKETCH_MAX_DIM {
+                return Err(anyhow::anyhow!("CMS: sketch would exceed the maximum size"));
+            }
+            check_sketch_size(width, depth, "CMS")?;
+
+            Ok(Command::CmsInitByProb {
+                key,
+                error,
+                probability,
+            })
+        }
+        "CMS.INCRBY" => {
+            let len = items.len();
+            if len < 4 || !len.is_multiple_of(2) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'cms.incrby' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut increments = Vec::new();
+            for i in (2..len).step_by(2) {
+                let item = expect_bulk_bytes(&items, i, "item")?;
+                let by = expect_int(&items, i + 1, "increment")
+                    .ok()
+                    .filter(|by| *by >= 0)
+                    .ok_or_else(|| anyhow::anyhow!("CMS: Cannot parse number"))?;
+                increments.push((item, by as u64));
+            }
+
+            Ok(Command::CmsIncrBy { key, increments })
+        }
+        "CMS.QUERY" | "TOPK.ADD" | "TOPK.QUERY" | "TOPK.COUNT" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let mut sketch_items = Vec::new();
+            for i in 2..len {
+                sketch_items.push(expect_bulk_bytes(&items, i, "item")?);
+            }
+
+            Ok(match cmd.as_str() {
+                "CMS.QUERY" => Command::CmsQuery {
+                    key,
+                    items: sketch_items,
+                },
+                "TOPK.ADD" => Command::TopKAdd {
+                    key,
+                    items: sketch_items,
+                },
+                "TOPK.QUERY" => Command::TopKQuery {
+                    key,
+                    items: sketch_items,
+                },
+                _ => Command::TopKCount {
+                    key,
+                    items: sketch_items,
+                },
+            })
+        }
+        "CMS.MERGE" => {
+            let len = items.len();
+            if len < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'cms.merge' command"
+                ));
+            }
+
+            let destination = expect_bulk(&items, 1, "destination")?;
+            let numkeys = expect_int(&items, 2, "numkeys")?;
+            if numkeys <= 0 || (len - 3) < numkeys as usize {
+                return Err(anyhow::anyhow!("CMS: invalid numkeys"));
+            }
+            let numkeys = numkeys as usize;
+
+            let mut sources = Vec::with_capacity(numkeys);
+            for i in 3..3 + numkeys {
+                sources.push(expect_bulk(&items, i, "source")?);
+            }
+
+            let mut weights = vec![1u64; numkeys];
+            let rest = 3 + numkeys;
+            if rest < len {
+                let keyword = expect_bulk(&items, rest, "option")?;
+                if !keyword.eq_ignore_ascii_case("WEIGHTS") || len - rest - 1 != numkeys {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                for (j, weight) in weights.iter_mut().enumerate() {
+                    *weight = expect_int(&items, rest + 1 + j, "weight")
+                        .ok()
+                        .filter(|w| *w >= 0)
+                        .ok_or_else(|| anyhow::anyhow!("CMS: invalid weight value"))?
+                        as u64;
+                }
+            }
+
+            Ok(Command::CmsMerge {
+                destination,
+                sources,
+                weights,
+            })
+        }
+        "TOPK.RESERVE" => {
+            let len = items.len();
+            if len != 3 && len != 6 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'topk.reserve' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let k = parse_sketch_dim(&items, 2, "topk")?;
+            let (mut width, mut depth, mut decay) =
+                (TOPK_DEFAULT_WIDTH, TOPK_DEFAULT_DEPTH, TOPK_DEFAULT_DECAY);
+            if len == 6 {
+                width = parse_sketch_dim(&items, 3, "width")?;
+                depth = parse_sketch_dim(&items, 4, "depth")?;
+                decay = expect_float(&items, 5, "decay")?;
+                if !(decay > 0.0 && decay <= 1.0) {
+                    return Err(anyhow::anyhow!("TopK: decay should be in (0, 1]"));
+                }
+                check_sketch_size(width, depth, "TopK")?;
+            }
+
+            Ok(Command::TopKReserve {
+                key,
+                k,
+                width,
+                depth,
+                decay,
+            })
+        }
+        "TOPK.LIST" => {
+            let len = items.len();
+            if !(2..=3).contains(&len) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'topk.list' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let with_count = len == 3;
+            if with_count && !expect_bulk(&items, 2, "option")?.eq_ignore_ascii_case("WITHCOUNT") {
+                return Err(anyhow::anyhow!("syntax error"));
+            }
+
+            Ok(Command::TopKList { key, with_count })
+        }
+        "TS.CREATE" => {
+            if items.len() < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'ts.create' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let (series, _) = parse_ts_options(&items, 2, false)?;
+
+            Ok(Command::TsCreate { key, series })
+        }
+        "TS.ADD" => {
+            if items.len() < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'ts.add' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let timestamp = parse_ts_timestamp(&items, 2)?;
+            let value = parse_ts_value(&items, 3)?;
+            let (series, on_duplicate) = parse_ts_options(&items, 4, true)?;
+
+            Ok(Command::TsAdd {
+                key,
+                timestamp,
+                value,
+                on_duplicate,
+                series,
+            })
+        }
+        "TS.MADD" => {
+            let len = items.len();
+            if len < 4 || !(len - 1).is_multiple_of(3) {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'ts.madd' command"
+                ));
+            }
+
+            let mut samples = Vec::with_capacity((len - 1) / 3);
+            for i in (1..len).step_by(3) {
+                let key = expect_bulk(&items, i, "key")?;
+                let timestamp = parse_ts_timestamp(&items, i + 1)?;
+                let value = parse_ts_value(&items, i + 2)?;
+                samples.push((key, timestamp, value));
+            }
+
+            Ok(Command::TsMAdd { samples })
+        }
+        "TS.RANGE" | "TS.REVRANGE" => {
+            if items.len() < 4 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let (range, _, _) = parse_ts_range(&items, 2, cmd == "TS.REVRANGE", false)?;
+
+            Ok(Command::TsRange { key, range })
+        }
+        "TS.MRANGE" => {
+            if items.len() < 5 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'ts.mrange' command"
+                ));
+            }
+
+            let (range, with_labels, filters) = parse_ts_range(&items, 1, false, true)?;
+
+            Ok(Command::TsMRange {
+                range,
+                with_labels,
+                filters,
+            })
+        }
+        "HEXPIRE" | "HPEXPIRE" | "HEXPIREAT" => {
+            if items.len() < 6 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let time = expect_int(&items, 2, "time")?;
+            let seconds = matches!(cmd.as_str(), "HEXPIRE" | "HEXPIREAT");
+            let relative = cmd != "HEXPIREAT";
+            if time < 0 || !deadline_fits(time, seconds, relative) {
+                return Err(anyhow::anyhow!(
+                    "invalid expire time in '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+            let expiry = match cmd.as_str() {
+                "HEXPIRE" => SetExpiry::Ex(time as u64),
+                "HPEXPIRE" => SetExpiry::Px(time as u64),
+                _ => SetExpiry::ExAt(time as u64),
+            };
+
+            let mut idx = 3;
+            let condition = ExpireCondition::parse(&expect_bulk(&items, idx, "option")?);
+            if condition.is_some() {
+                idx += 1;
+            }
+            let fields = parse_hash_fields(&items, idx)?;
+
+            Ok(Command::HEXPIRE {
+                key,
+                expiry,
+                condition,
+                fields,
+            })
+        }
+        "HTTL" | "HPTTL" | "HPERSIST" => {
+            if items.len() < 5 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let fields = parse_hash_fields(&items, 2)?;
+
+            Ok(match cmd.as_str() {
+                "HPERSIST" => Command::HPERSIST { key, fields },
+                _ => Command::HTTL {
+                    key,
+                    fields,
+                    millis: cmd == "HPTTL",
+                },
+            })
+        }
+        "SCAN" => {
+            if items.len() < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'scan' command"
+                ));
+            }
+
+            let cursor = parse_cursor(&items, 1)?;
+            let (pattern, count, _, type_name) = parse_scan_options(&items, 2, "SCAN")?;
+
+            Ok(Command::SCAN {
+                cursor,
+                pattern,
+                count,
+                type_name,
+            })
+        }
+        "SSCAN" | "ZSCAN" => {
+            if items.len() < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let cursor = parse_cursor(&items, 2)?;
+            let (pattern, count, _, _) = parse_scan_options(&items, 3, &cmd)?;
+
+            Ok(if cmd == "SSCAN" {
+                Command::SSCAN {
+                    key,
+                    cursor,
+                    pattern,
+                    count,
+                }
+            } else {
+                Command::ZSCAN {
+                    key,
+                    cursor,
+                    pattern,
+                    count,
+                }
+            })
+        }
+        "KEYS" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'keys' command"
+                ));
+            }
+
+            let pattern = expect_bulk_bytes(&items, 1, "pattern")?;
+
+            Ok(Command::KEYS { pattern })
+        }
+        "RENAME" | "RENAMENX" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            let source = expect_bulk(&items, 1, "key")?;
+            let destination = expect_bulk(&items, 2, "newkey")?;
+
+            Ok(Command::RENAME {
+                source,
+                destination,
+                nx: cmd == "RENAMENX",
+            })
+        }
+        "COPY" => {
+            let len = items.len();
+            if len < 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'copy' command"
+                ));
+            }
+
+            let source = expect_bulk(&items, 1, "source")?;
+            let destination = expect_bulk(&items, 2, "destination")?;
+            let mut replace = false;
+            for i in 3..len {
+                if !expect_bulk(&items, i, "option")?.eq_ignore_ascii_case("REPLACE") {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                replace = true;
+            }
+
+            Ok(Command::COPY {
+                source,
+                destination,
+                replace,
+            })
+        }
+        "RANDOMKEY" | "DBSIZE" => {
+            if items.len() != 1 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for '{}' command",
+                    cmd.to_lowercase()
+                ));
+            }
+
+            Ok(if cmd == "RANDOMKEY" {
+                Command::RANDOMKEY
+            } else {
+                Command::DBSIZE
+            })
+        }
+        "TOUCH" => {
+            let len = items.len();
+            if len < 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'touch' command"
+                ));
+            }
+
+            let mut keys = Vec::with_capacity(len - 1);
+            for i in 1..len {
+                keys.push(expect_bulk(&items, i, "key")?);
+            }
+
+            Ok(Command::TOUCH { keys })
+        }
+        "SELECT" => {
+            if items.len() != 2 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'select' command"
+                ));
+            }
+
+            let index = expect_int(&items, 1, "index")?;
+            Ok(Command::SELECT { index })
+        }
+        "MOVE" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'move' command"
+                ));
+            }
+
+            let key = expect_bulk(&items, 1, "key")?;
+            let db = expect_int(&items, 2, "db")?;
+            Ok(Command::MOVE { key, db })
+        }
+        "SWAPDB" => {
+            if items.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "wrong number of arguments for 'swapdb' command"
+                ));
+            }
+
+            let first = expect_int(&items, 1, "index1")?;
+            let second = expect_int(&items, 2, "index2")?;
+            Ok(Command::SWAPDB { first, second })
+        }
+        "FLUSHDB" | "FLUSHALL" => {
+            let lazy = match items.len() {
+                1 => false,
+                2 => match expect_bulk(&items, 1, "mode")?.to_uppercase().as_str() {
+                    "ASYNC" => true,
+                    "SYNC" => false,
+                    _ => return Err(anyhow::anyhow!("syntax error")),
+                },
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "wrong number of arguments for '{}' command",
+                        cmd.to_lowercase()
+                    ));
+                }
+            };
+
+            Ok(if cmd == "FLUSHDB" {
+                Command::FLUSHDB { lazy }
+            } else {
+                Command::FLUSHALL { lazy }
+            })
+        }
+        _ => Err(anyhow::anyhow!("unknown command")),
+    }
+}
+
+fn parse_cursor(items: &[RESP], idx: usize) -> Result<u64> {
+    expect_bulk(items, idx, "cursor")?
+        .parse::<u64>()
+        .map_err(|_| anyhow::anyhow!("invalid cursor"))
+}
+
+/// MATCH pattern, COUNT, NOVALUES and TYPE, in that order.
+type ScanOptions = (Option<Vec<u8>>, usize, bool, Option<String>);
+
+/// Parses the SCAN family's options: MATCH and COUNT everywhere, NOVALUES
+/// for HSCAN and TYPE for SCAN.
+fn parse_scan_options(items: &[RESP], start: usize, cmd: &str) -> Result<ScanOptions> {
+    let mut pattern = None;
+    let mut count = 10usize;
+    let mut no_values = false;
+    let mut type_name = None;
+
+    let mut i = start;
+    while i < items.len() {
+        let opt = expect_bulk(items, i, "option")?.to_uppercase();
+        match opt.as_str() {
+            "MATCH" if i + 1 < items.len() => {
+                pattern = Some(expect_bulk_bytes(items, i + 1, "pattern")?);
+                i += 2;
+            }
+            "COUNT" if i + 1 < items.len() => {
+                let n = expect_int(items, i + 1, "count")?;
+                if n < 1 {
+                    return Err(anyhow::anyhow!("syntax error"));
+                }
+                count = n as usize;
+                i += 2;
+            }
+            "NOVALUES" if cmd == "HSCAN" => {
+                no_values = true;
+                i += 1;
+            }
+            "TYPE" if cmd == "SCAN" && i + 1 < items.len() => {
+                type_name = Some(expect_bulk(items, i + 1, "type")?);
+                i += 2;
+            }
+            _ => return Err(anyhow::anyhow!("syntax error")),
+        }
+    }
+
+    Ok((pattern, count, no_values, type_name))
+}
+
+fn parse_score_bound(items: &[RESP], idx: usize) -> Result<ScoreBound> {
+    let raw = expect_bulk_bytes(items, idx, "min or max")?;
+    let (exclusive, number) = match raw.strip_prefix(b"(") {
+        Some(rest) => (true, rest),
+        None => (false, raw.as_slice()),
+    };
+
+    match parse_float(number) {
+        Some(value) => Ok(ScoreBound { value, exclusive }),
+        None => Err(anyhow::anyhow!("min or max is not a float")),
+    }
+}
+
+fn parse_lex_bound(items: &[RESP], idx: usize) -> Result<LexBound> {
+    let raw = expect_bulk_bytes(items, idx, "min or max")?;
+    match raw.first() {
+        Some(b'-') if raw.len() == 1 => Ok(LexBound::NegInf),
+        Some(b'+') if raw.len() == 1 => Ok(LexBound::PosInf),
+        Some(b'[') => Ok(LexBound::Inclusive(raw[1..].to_vec())),
+        Some(b'(') => Ok(LexBound::Exclusive(raw[1..].to_vec())),
+        _ => Err(anyhow::anyhow!("min or max not valid string range item")),
+    }
+}
+
+/// Parses `<start> <stop> [BYSCORE|BYLEX] [REV] [LIMIT offset count] [WITHSCORES]`.
+fn parse_zrange_spec(items: &[RESP], start: usize, allow_withscores: bool) -> Result<ZRangeSpec> {
+    let mut by_score = false;
+    let mut by_lex = false;
+    let mut rev = false;
+    let mut limit = None;
+    let mut with_scores = false;
+
+    let mut i = start + 2;
+    while i < items.len() {
+        let opt = expect_bulk(items, i, "option")?.to_uppercase();
+        match opt.as_str() {
+            "BYSCORE" => by_score = true,
+            "BYLEX" => by_lex = true,
+            "REV" => rev = true,
+            "WITHSCORES" if allow_withscores => with_scores = true,
+            "LIMIT" if i + 2 < items.len() => {
+                let offset = expect_int(items, i + 1, "offset")?;
+                let count = expect_int(items, i + 2, "count")?;
+                limit = Some((offset, count));
+                i += 2;
+            }
+            _ => return Err(anyhow::anyhow!("syntax error")),
+        }
+        i += 1;
+    }
+
+    if by_score && by_lex {
+        return Err(anyhow::anyhow!("syntax error"));
+    }
+    if limit.is_some() && !by_score && !by_lex {
+        return Err(anyhow::anyhow!(
+            "syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX"
+        ));
+    }
+    if with_scores && by_lex {
+        return Err(anyhow::anyhow!(
+            "syntax error, WITHSCORES not supported in combination with BYLEX"
+        ));
+    }
+
+    let (low, high) = if rev && (by_score || by_lex) {
+        (start + 1, start)
+    } else {
+        (start, start + 1)
+    };
+
+    let by = if by_score {
+        ZRangeBy::Score(
+            parse_score_bound(items, low)?,
+            parse_score_bound(items, high)?,
+        )
+    } else if by_lex {
+        ZRangeBy::Lex(parse_lex_bound(items, low)?, parse_lex_bound(items, high)?)
+    } else {
+        ZRangeBy::Rank(
+            expect_int(items, start, "start")?,
+            expect_int(items, start + 1, "stop")?,
+        )
+    };
+
+    Ok(ZRangeSpec {
+        by,
+        rev,
+        limit,
+        with_scores,
+    })
+}
+
+const INVALID_STREAM_ID: &str = "Invalid stream ID specified as stream command argument";
+
+fn parse_stream_id(items: &[RESP], idx: usize, default_seq: u64) -> Result<StreamId> {
+    let raw = expect_bulk_bytes(items, idx, "id")?;
+    StreamId::parse(&raw, default_seq).ok_or_else(|| anyhow::anyhow!(INVALID_STREAM_ID))
+}
+
+/// Parses an XRANGE-style bound: `-`, `+`, a full or partial ID, or an
+/// exclusive `(id`. The result is always an inclusive bound.
+fn parse_stream_range_bound(items: &[RESP], idx: usize, is_start: bool) -> Result<StreamId> {
+    let raw = expect_bulk_bytes(items, idx, "id")?;
+    match raw.as_slice() {
+        b"-" => return Ok(StreamId::MIN),
+        b"+" => return Ok(StreamId::MAX),
+        _ => {}
+    }
+
+    let default_seq = if is_start { 0 } else { u64::MAX };
+    let Some(rest) = raw.strip_prefix(b"(") else {
+        return parse_stream_id(items, idx, default_seq);
+    };
+    let id =
+        StreamId::parse(rest, default_seq).ok_or_else(|| anyhow::anyhow!(INVALID_STREAM_ID))?;
+    let bound = if is_start { id.next() } else { id.prev() };
+    bound.ok_or_else(|| {
+        anyhow::anyhow!(
+            "invalid {} ID for the interval",
+            if is_start { "start" } else { "end" }
+        )
+    })
+}
+
+/// Parses `MAXLEN|MINID [=|~] threshold [LIMIT count]` starting at `start`,
+/// returning the trim and the index just past it.
+fn parse_stream_trim(items: &[RESP], start: usize) -> Result<(StreamTrim, usize)> {
+    let kind = expect_bulk(items, start, "strategy")?.to_uppercase();
+    let mut i = start + 1;
+
+    let mut approx = false;
+    match expect_bulk_bytes(items, i, "threshold")?.as_slice() {
+        b"=" => i += 1,
+        b"~" => {
+            approx = true;
+            i += 1;
+        }
+        _ => {}
+    }
+
+    let strategy = if kind == "MAXLEN" {
+        let max = expect_int(items, i, "threshold")?;
+        if max < 0 {
+            return Err(anyhow::anyhow!("The MAXLEN argument must be >= 0."));
+        }
+        TrimStrategy::MaxLen(max as u64)
+    } else {
+        TrimStrategy::MinId(parse_stream_id(items, i, 0)?)
+    };
+    i += 1;
+
+    let mut limit = None;
+    if let Some(RESP::BulkStrings(Some(b))) = items.get(i)
+        && b.eq_ignore_ascii_case(b"LIMIT")
+    {
+        let n = expect_int(items, i + 1, "limit")?;
+        if n < 0 {
+            return Err(anyhow::anyhow!("The LIMIT argument must be >= 0."));
+        }
+        if !approx {
+            return Err(anyhow::anyhow!(
+                "syntax error, LIMIT cannot be used without the special ~ option"
+            ));
+        }
+        limit = Some(n as u64);
+        i += 2;
+    }
+
+    Ok((StreamTrim { strategy, limit }, i))
+}
+
+fn parse_read_count(items: &[RESP], idx: usize) -> Result<Option<usize>> {
+    let n = expect_int(items, idx, "count")?;
+    Ok((n > 0).then_some(n as usize))
+}
+
+fn parse_block_timeout(items: &[RESP], idx: usize) -> Result<u64> {
+    let timeout = expect_bulk(items, idx, "timeout")?
+        .parse::<i64>()
+        .map_err(|_| anyhow::anyhow!("timeout is not an integer or out of range"))?;
+    if timeout < 0 {
+        return Err(anyhow::anyhow!("timeout is negative"));
+    }
+    Ok(timeout as u64)
+}
+
+/// Splits the `STREAMS key [key ...] id [id ...]` tail of XREAD/XREADGROUP.
+fn parse_read_streams(
+    items: &[RESP],
+    start: usize,
+    group: bool,
+) -> Result<(Vec<String>, Vec<StreamReadId>)> {
+    let remaining = items.len().saturating_sub(start);
+    if remaining == 0 || !remaining.is_multiple_of(2) {
+        return Err(anyhow::anyhow!(
+            "Unbalanced '{}' list of streams: for each stream key an ID or '{}' must be specified.",
+            if group { "xreadgroup" } else { "xread" },
+            if group { ">" } else { "$" }
+        ));
+    }
+
+    let half = remaining / 2;
+    let mut keys = Vec::with_capacity(half);
+    let mut ids = Vec::with_capacity(half);
+    for i in start..start + half {
+        keys.push(expect_bulk(items, i, "key")?);
+        let idx = i + half;
+        let id = match expect_bulk_bytes(items, idx, "id")?.as_slice() {
+            b">" if group => StreamReadId::Undelivered,
+            b">" => {
+                return Err(anyhow::anyhow!(
+                    "The > ID can be specified only when calling XREADGROUP using the GROUP <group> <consumer> option."
+                ));
+            }
+            b"$" if group => {
+                return Err(anyhow::anyhow!(
+                    "The $ ID is meaningless in the context of XREADGROUP: you want to read the history of this consumer by specifying a proper ID, or use the > ID to get new messages. The $ ID would just return an empty result set."
+                ));
+            }
+            b"$" => StreamReadId::Last,
+            b"+" if !group => StreamReadId::LastEntry,
+            _ => StreamReadId::After(parse_stream_id(items, idx, 0)?),
+        };
+        ids.push(id);
+    }
+
+    Ok((keys, ids))
+}
+
+/// BLPOP-style timeout in seconds (fractions allowed), returned in ms.
+fn parse_list_timeout(items: &[RESP], idx: usize) -> Result<u64> {
+    let timeout = expect_bulk(items, idx, "timeout")?
+        .parse::<f64>()
+        .ok()
+        .filter(|t| t.is_finite())
+        .ok_or_else(|| anyhow::anyhow!("timeout is not a float or out of range"))?;
+    if timeout < 0.0 {
+        return Err(anyhow::anyhow!("timeout is negative"));
+    }
+    Ok((timeout * 1000.0).round() as u64)
+}
+
+/// `LEFT` or `RIGHT`; returns `true` for LEFT.
+fn parse_list_side(items: &[RESP], idx: usize) -> Result<bool> {
+    match expect_bulk(items, idx, "where")?.to_uppercase().as_str() {
+        "LEFT" => Ok(true),
+        "RIGHT" => Ok(false),
+        _ => Err(anyhow::anyhow!("syntax error")),
+    }
+}
+
+/// Parses `numkeys key [key ...] LEFT|RIGHT [COUNT count]` starting at
+/// `start`, as shared by LMPOP and BLMPOP.
+fn parse_mpop(items: &[RESP], start: usize) -> Result<(Vec<String>, bool, usize)> {
+    let len = items.len();
+    let numkeys = expect_int(items, start, "numkeys")?;
+    if numkeys <= 0 {
+        return Err(anyhow::anyhow!("numkeys should be greater than 0"));
+    }
+    let numkeys = numkeys as usize;
+    let side = start + 1 + numkeys;
+    if side >= len {
+        return Err(anyhow::anyhow!("syntax error"));
+    }
+
+    let mut keys = Vec::with_capacity(numkeys);
+    for i in start + 1..side {
+        keys.push(expect_bulk(items, i, "key")?);
+    }
+    let left = parse_list_side(items, side)?;
+
+    let mut count = 1;
+    match len - side {
+        1 => {}
+        3 if expect_bulk(items, side + 1, "option")?.eq_ignore_ascii_case("COUNT") => {
+            let n = expect_int(items, side + 2, "count")?;
+            if n <= 0 {
+                return Err(anyhow::anyhow!("count should be greater than 0"));
+            }
+            count = n as usize;
+        }
+        _ => return Err(anyhow::anyhow!("syntax error")),
+    }
+
+    Ok((keys, left, count))
+}
+
+/// Validates the time given to SET/GETEX EX/PX/EXAT/PXAT. Seconds must still
+/// fit once converted to milliseconds.
+fn parse_set_expiry(cmd: &str, unit: &str, time: i64) -> Result<SetExpiry> {
+    let invalid = || anyhow::anyhow!("invalid expire time in '{}' command", cmd);
+    if time <= 0 {
+        return Err(invalid());
+    }
+    if !deadline_fits(time, matches!(unit, "EX" | "EXAT"), matches!(unit, "EX" | "PX")) {
+        return Err(invalid());
+    }
+    let time = time as u64;
+    Ok(match unit {
+        "EX" => SetExpiry::Ex(time),
+        "PX" => SetExpiry::Px(time),
+        "EXAT" => SetExpiry::ExAt(time),
+        _ => SetExpiry::PxAt(time),
+    })
+}
+
+/// Whether an expire time, in seconds or milliseconds and counted from now
+/// or from the epoch, gives a deadline that fits an i64 of Unix
+/// milliseconds, as Redis requires.
+fn deadline_fits(time: i64, seconds: bool, relative: bool) -> bool {
+    let millis = if seconds { time.checked_mul(1000) } else { Some(time) };
+    let base = if relative { unix_millis() as i64 } else { 0 };
+    millis.and_then(|ms| ms.checked_add(base)).is_some()
+}
+
+/// Largest bit offset, keeping bitmaps within a 512MB string.
+const MAX_BIT_OFFSET: u64 = 512 * 1024 * 1024 * 8;
+
+fn parse_bit_offset(items: &[RESP], idx: usize) -> Result<u64> {
+    expect_bulk(items, idx, "offset")?
+        .parse::<u64>()
+        .ok()
+        .filter(|offset| *offset < MAX_BIT_OFFSET)
+        .ok_or_else(|| anyhow::anyhow!("bit offset is not an integer or out of range"))
+}
+
+/// `BYTE` or `BIT` for BITCOUNT/BITPOS ranges; returns `true` for BIT.
+fn parse_bit_unit(items: &[RESP], idx: usize) -> Result<bool> {
+    match expect_bulk(items, idx, "unit")?.to_uppercase().as_str() {
+        "BYTE" => Ok(false),
+        "BIT" => Ok(true),
+        _ => Err(anyhow::anyhow!("syntax error")),
+    }
+}
+
+/// `i1`..`i64` or `u1`..`u63`.
+fn parse_bitfield_type(items: &[RESP], idx: usize) -> Result<BitFieldType> {
+    let raw = expect_bulk(items, idx, "type")?;
+    let ty = match raw.split_at_checked(1) {
+        Some(("i" | "I", bits)) => bits.parse::<u8>().ok().filter(|b| (1..=64).contains(b)),
+        Some(("u" | "U", bits)) => bits.parse::<u8>().ok().filter(|b| (1..=63).contains(b)),
+        _ => None,
+    };
+    ty.map(|bits| BitFieldType {
+        signed: raw.starts_with(['i', 'I']),
+        bits,
+    })
+    .ok_or_else(|| {
+        anyhow::anyhow!(
+            "Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported but i64 is."
+        )
+    })
+}
+
+/// A bit offset, or `#N` for the N-th field of the given type's width.
+fn parse_bitfield_offset(items: &[RESP], idx: usize, ty: BitFieldType) -> Result<u64> {
+    let raw = expect_bulk(items, idx, "offset")?;
+    let offset = match raw.strip_prefix('#') {
+        Some(n) => n
+            .parse::<u64>()
+            .ok()
+            .and_then(|n| n.checked_mul(ty.bits as u64)),
+        None => raw.parse::<u64>().ok(),
+    };
+    offset
+        .filter(|offset| {
+            offset
+                .checked_add(ty.bits as u64)
+                .is_some_and(|end| end <= MAX_BIT_OFFSET)
+        })
+        .ok_or_else(|| anyhow::anyhow!("bit offset is not an integer or out of range"))
+}
+
+/// A `longitude latitude` pair within the range geohash scores can encode.
+fn parse_lon_lat(items: &[RESP], idx: usize) -> Result<(f64, f64)> {
+    let lon = expect_float(items, idx, "longitude")?;
+    let lat = expect_float(items, idx + 1, "latitude")?;
+    if !geo_valid(lon, lat) {
+        return Err(anyhow::anyhow!(
+            "invalid longitude,latitude pair {:.6},{:.6}",
+            lon,
+            lat
+        ));
+    }
+    Ok((lon, lat))
+}
+
+fn parse_geo_unit(items: &[RESP], idx: usize) -> Result<GeoUnit> {
+    GeoUnit::parse(&expect_bulk(items, idx, "unit")?)
+        .ok_or_else(|| anyhow::anyhow!("unsupported unit provided. please use M, KM, FT, MI"))
+}
+
+/// Parses the GEOSEARCH options starting at `start`. STOREDIST is only
+/// accepted for GEOSEARCHSTORE and the WITH* options only for GEOSEARCH;
+/// the returned flag is STOREDIST.
+fn parse_geo_search(items: &[RESP], start: usize, cmd: &str) -> Result<(GeoSearch, bool)> {
+    let store = cmd == "geosearchstore";
+    let len = items.len();
+    let mut from = None;
+    let mut shape = None;
+    let mut unit = GeoUnit::M;
+    let mut asc = None;
+    let mut count = None;
+    let mut any = false;
+    let (mut with_coord, mut with_dist, mut with_hash) = (false, false, false);
+    let mut store_dist = false;
+
+    let mut i = start;
+    while i < len {
+        let opt = expect_bulk(items, i, "option")?.to_uppercase();
+        match opt.as_str() {
+            "FROMMEMBER" if i + 1 < len && from.is_none() => {
+                from = Some(GeoFrom::Member(expect_bulk_bytes(items, i + 1, "member")?));
+                i += 2;
+            }
+            "FROMLONLAT" if i + 2 < len && from.is_none() => {
+                let (lon, lat) = parse_lon_lat(items, i + 1)?;
+                from = Some(GeoFrom::LonLat(lon, lat));
+                i += 3;
+            }
+            "FROMMEMBER" | "FROMLONLAT" if from.is_some() => {
+                return Err(anyhow::anyhow!(
+                    "exactly one of FROMMEMBER or FROMLONLAT can be specified for {}",
+                    cmd
+                ));
+            }
+            "BYRADIUS" if i + 2 < len && shape.is_none() => {
+                let radius = expect_float(items, i + 1, "radius")?;
+                if radius < 0.0 {
+                    return Err(anyhow::anyhow!("radius cannot be negative"));
+                }
+                unit = parse_geo_unit(items, i + 2)?;
+                shape = Some(GeoShape::Radius(radius * unit.meters()));
+                i += 3;
+            }
+            "BYBOX" if i + 3 < len && shape.is_none() => {
+                let width = expect_float(items, i + 1, "width")?;
+                let height = expect_float(items, i + 2, "height")?;
+                if width < 0.0 || height < 0.0 {
+                    return Err(anyhow::anyhow!("height or width cannot be negative"));
+                }
+                unit = parse_geo_unit(items, i + 3)?;
+                shape = Some(GeoShape::Box {
+                    width: width * unit.meters(),
+                    height: height * unit.meters(),
+                });
+                i += 4;
+            }
+            "BYRADIUS" | "BYBOX" if shape.is_some() => {
+                return Err(anyhow::anyhow!(
+                    "exactly one of BYRADIUS and BYBOX can be specified for {}",
+                    cmd
+                ));
+            }
+            "ASC" => {
+                asc = Some(true);
+                i += 1;
+            }
+            "DESC" => {
+                asc = Some(false);
+                i += 1;
+            }
+            "COUNT" if i + 1 < len => {
+                let n = expect_int(items, i + 1, "count")?;
+                if n <= 0 {
+                    return Err(anyhow::anyhow!("COUNT must be > 0"));
+                }
+                count = Some(n as usize);
+                i += 2;
+                if i < len && expect_bulk(items, i, "option")?.eq_ignore_ascii_case("ANY") {
+                    any = true;
+                    i += 1;
+                }
+            }
+            "WITHCOORD" if !store => {
+                with_coord = true;
+                i += 1;
+            }
+            "WITHDIST" if !store => {
+                with_dist = true;
+                i += 1;
+            }
+            "WITHHASH" if !store => {
+                with_hash = true;
+                i += 1;
+            }
+            "STOREDIST" if store => {
+                store_dist = true;
+                i += 1;
+            }
+            _ => return Err(anyhow::anyhow!("syntax error")),
+        }
+    }
+
+    let Some(from) = from else {
+        return Err(anyhow::anyhow!(
+            "exactly one of FROMMEMBER or FROMLONLAT can be specified for {}",
+            cmd
+        ));
+    };
+    let Some(shape) = shape else {
+        return Err(anyhow::anyhow!(
+            "exactly one of BYRADIUS and BYBOX can be specified for {}",
+            cmd
+        ));
+    };
+
+    let search = GeoSearch {
+        from,
+        shape,
+        unit,
+        asc,
+        count,
+        any,
+        with_coord,
+        with_dist,
+        with_hash,
+    };
+    Ok((search, store_dist))
+}
+
+fn parse_json_path(items: &[RESP], idx: usize) -> Result<JsonPath> {
+    let raw = expect_bulk(items, idx, "path")?;
+    JsonPath::parse(&raw).ok_or_else(|| anyhow::anyhow!("invalid JSONPath '{}'", raw))
+}
+
+fn parse_json_value(items: &[RESP], idx: usize) -> Result<Json> {
+    let text = expect_bulk_bytes(items, idx, "value")?;
+    Json::parse(&text).map_err(|err| anyhow::anyhow!("invalid JSON: {}", err))
+}
+
+fn parse_sketch_dim(items: &[RESP], idx: usize, name: &str) -> Result<usize> {
+    let value = expect_int(items, idx, name)?;
+    if value <= 0 {
+        return Err(anyhow::anyhow!("{} should be larger than 0", name));
+    }
+    if value as usize > SKETCH_MAX_DIM {
+        return Err(anyhow::anyhow!(
+            "{} should be at most {}",
+            name,
+            SKETCH_MAX_DIM
+        ));
+    }
+    Ok(value as usize)
+}
+
+/// Rejects sketches whose counter grid is over `SKETCH_MAX_CELLS`.
+fn check_sketch_size(width: usize, depth: usize, prefix: &str) -> Result<()> {
+    if width * depth > SKETCH_MAX_CELLS {
+        return Err(anyhow::anyhow!(
+            "{}: sketch would exceed the maximum size",
+            prefix
+        ));
+    }
+    Ok(())
+}
+
+fn parse_ts_timestamp(items: &[RESP], idx: usize) -> Result<Option<u64>> {
+    let raw = expect_bulk(items, idx, "timestamp")?;
+    if raw == "*" {
+        return Ok(None);
+    }
+    // Replies carry timestamps as RESP integers, so they must fit an i64.
+    let timestamp = raw
+        .parse::<u64>()
+        .ok()
+        .filter(|t| *t <= i64::MAX as u64)
+        .ok_or_else(|| anyhow::anyhow!("TSDB: invalid timestamp"))?;
+    Ok(Some(timestamp))
+}
+
+fn parse_ts_value(items: &[RESP], idx: usize) -> Result<f64> {
+    expect_float(items, idx, "value").map_err(|_| anyhow::anyhow!("TSDB: invalid value"))
+}
+
+fn parse_ts_policy(items: &[RESP], idx: usize) -> Result<DuplicatePolicy> {
+    DuplicatePolicy::parse(&expect_bulk(items, idx, "policy")?)
+        .ok_or_else(|| anyhow::anyhow!("TSDB: Unknown DUPLICATE_POLICY"))
+}
+
+/// Parses the TS.CREATE options into an empty series, plus ON_DUPLICATE
+/// when `on_duplicate` allows it as TS.ADD does. LABELS takes every
+/// remaining argument as label/value pairs.
+fn parse_ts_options(
+    items: &[RESP],
+    start: usize,
+    on_duplicate: bool,
+) -> Result<(TimeSeries, Option<DuplicatePolicy>)> {
+    let mut series = TimeSeries::default();
+    let mut override_policy = None;
+    let mut i = start;
+    while i < items.len() {
+        let option = expect_bulk(items, i, "option")?.to_uppercase();
+        match option.as_str() {
+            "RETENTION" if i + 1 < items.len() => {
+                series.retention = expect_int(items, i + 1, "retention")
+                    .ok()
+                    .filter(|r| *r >= 0)
+                    .ok_or_else(|| anyhow::anyhow!("TSDB: invalid retention value"))?
+                    as u64;
+                i += 2;
+            }
+            "DUPLICATE_POLICY" if i + 1 < items.len() => {
+                series.duplicate_policy = parse_ts_policy(items, i + 1)?;
+                i += 2;
+            }
+            "ON_DUPLICATE" if on_duplicate && i + 1 < items.len() => {
+                override_policy = Some(parse_ts_policy(items, i + 1)?);
+                i += 2;
+            }
+            "LABELS" => {
+                let rest = items.len() - i - 1;
+                if rest == 0 || !rest.is_multiple_of(2) {
+                    return Err(anyhow::anyhow!("TSDB: Invalid labels"));
+                }
+                for j in (i + 1..items.len()).step_by(2) {
+                    let label = expect_bulk(items, j, "label")?;
+                    let value = expect_bulk(items, j + 1, "value")?;
+                    series.labels.retain(|(l, _)| *l != label);
+                    series.labels.push((label, value));
+                }
+                i = items.len();
+            }
+            _ => return Err(anyhow::anyhow!("syntax error")),
+        }
+    }
+    Ok((series, override_policy))
+}
+
+/// Parses `from to [COUNT n] [AGGREGATION agg bucket]` starting at `start`.
+/// With `mrange` it also takes WITHLABELS and a trailing FILTER list, which
+/// must hold at least one `label=value` matcher.
+fn parse_ts_range(
+    items: &[RESP],
+    start: usize,
+    rev: bool,
+    mrange: bool,
+) -> Result<(TsRange, bool, Vec<LabelFilter>)> {
+    let bound = |idx: usize, open: &str, unbounded: u64| -> Result<u64> {
+        let raw = expect_bulk(items, idx, "timestamp")?;
+        if raw == open {
+            return Ok(unbounded);
+        }
+        raw.parse::<u64>()
+            .map_err(|_| anyhow::anyhow!("TSDB: invalid timestamp"))
+    };
+    let mut range = TsRange {
+        from: bound(start, "-", 0)?,
+        to: bound(start + 1, "+", u64::MAX)?,
+        rev,
+        count: None,
+        aggregation: None,
+    };
+    let mut with_labels = false;
+    let mut filters = Vec::new();
+
+    let mut i = start + 2;
+    while i < items.len() {
+        let option = expect_bulk(items, i, "option")?.to_uppercase();
+        match option.as_str() {
+            "COUNT" if i + 1 < items.len() => {
+                let count = expect_int(items, i + 1, "count")
+                    .ok()
+                    .filter(|c| *c > 0)
+                    .ok_or_else(|| anyhow::anyhow!("TSDB: Invalid COUNT value"))?;
+                range.count = Some(count as usize);
+                i += 2;
+            }
+            "AGGREGATION" if i + 2 < items.len() => {
+                let aggregator = Aggregator::parse(&expect_bulk(items, i + 1, "aggregator")?)
+                    .ok_or_else(|| anyhow::anyhow!("TSDB: Unknown aggregation type"))?;
+                let bucket = expect_int(items, i + 2, "bucket")
+                    .ok()
+                    .filter(|b| *b > 0)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("TSDB: bucketDuration must be greater than zero")
+                    })?;
+                range.aggregation = Some((aggregator, bucket as u64));
+                i += 3;
+            }
+            "WITHLABELS" if mrange => {
+                with_labels = true;
+                i += 1;
+            }
+            "FILTER" if mrange => {
+                for j in i + 1..items.len() {
+                    let raw = expect_bulk(items, j, "filter")?;
+                    filters.push(
+                        LabelFilter::parse(&raw)
+                            .ok_or_else(|| anyhow::anyhow!("TSDB: failed parsing labels"))?,
+                    );
+                }
+                i = items.len();
+            }
+            _ => return Err(anyhow::anyhow!("syntax error")),
+        }
+    }
+
+    if mrange && !filters.iter().any(LabelFilter::is_equals) {
+        return Err(anyhow::anyhow!("TSDB: please provide at least one matcher"));
+    }
+    Ok((range, with_labels, filters))
+}
+
+/// Parses the `FIELDS numfields field ...` tail of the hash field expiry
+/// commands, which must run to the end of the arguments.
+fn parse_hash_fields(items: &[RESP], idx: usize) -> Result<Vec<Vec<u8>>> {
+    if !expect_bulk(items, idx, "option")?.eq_ignore_ascii_case("FIELDS") {
+        return Err(anyhow::anyhow!(
+            "Mandatory argument FIELDS is missing or not at the right position"
+        ));
+    }
+    let numfields = expect_int(items, idx + 1, "numfields")?;
+    if numfields <= 0 {
+        return Err(anyhow::anyhow!(
+            "Parameter `numFields` should be greater than 0"
+        ));
+    }
+    if items.len() - idx - 2 != numfields as usize {
+        return Err(anyhow::anyhow!(
+            "The `numfields` parameter must match the number of arguments"
+        ));
+    }
+
+    let mut fields = Vec::with_capacity(numfields as usize);
+    for i in idx + 2..items.len() {
+        fields.push(expect_bulk_bytes(items, i, "field")?);
+    }
+    Ok(fields)
+}
+
+/// Parses the count of SRANDMEMBER and HRANDFIELD. A negative count repeats
+/// picks, so like Redis it is bounded to keep the reply size representable.
+fn expect_random_count(items: &[RESP], idx: usize) -> Result<i64> {
+    let count = expect_int(items, idx, "count")?;
+    if count < -(i64::MAX / 2) {
+        return Err(anyhow::anyhow!("value is out of range"));
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(args: &[&str]) -> Vec<RESP> {
+        args.iter()
+            .map(|arg| RESP::BulkStrings(Some(arg.as_bytes().to_vec())))
+            .collect()
+    }
+
+    #[test]
+    fn bitfield_offset_near_u64_max_is_out_of_range() {
+        let max = u64::MAX.to_string();
+        assert!(parse_command(command(&["BITFIELD", "k", "GET", "u8", &max])).is_err());
+        assert!(parse_command(command(&["BITFIELD", "k", "GET", "u8", "0"])).is_ok());
     }
 }
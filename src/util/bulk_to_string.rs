@@ -1,3 +1,3 @@
-pub fn bulk_to_string(binary: &Vec<u8>) -> Option<String> {
+pub fn bulk_to_string(binary: &[u8]) -> Option<String> {
     String::from_utf8(binary.to_vec()).ok()
 }
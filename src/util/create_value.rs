@@ -0,0 +1,61 @@
+use super::drop_expired;
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Entry, Value},
+};
+use anyhow::Result;
+
+pub enum Create {
+    Created,
+    Exists,
+    OutOfMemory,
+}
+
+/// Stores the value built by `make` at `key` unless a live key is already
+/// there, the way the RESERVE and INIT commands of the probabilistic types
+/// create their keys. `footprint`, the heap bytes the value will take, is
+/// charged to `lru` before `make` runs, so a value too big for `maxmemory`
+/// is never allocated; the charge is corrected to the real size afterwards.
+pub async fn create_value(
+    db: &DB,
+    lru: &LruManager,
+    key: &str,
+    footprint: usize,
+    make: impl FnOnce() -> Value,
+) -> Result<Create> {
+    let mut guard = db.write().await;
+    let freed = drop_expired(&mut guard, key);
+    if freed > 0 {
+        lru.adjust_used_bytes(-(freed as isize));
+    }
+    if guard.contains_key(key) {
+        return Ok(Create::Exists);
+    }
+    drop(guard);
+
+    let reserved = footprint as isize;
+    if !lru.try_reserve(reserved).await? {
+        lru.adjust_used_bytes(-reserved);
+        return Ok(Create::OutOfMemory);
+    }
+
+    let key = key.to_string();
+    let new_entry = Entry {
+        value: make(),
+        expires_at: None,
+    };
+    let new_size = estimate_entry_bytes(&key, &new_entry);
+
+    let mut guard = db.write().await;
+    if guard.contains_key(&key) {
+        // Another client created the key while the value was being built.
+        drop(guard);
+        lru.adjust_used_bytes(-reserved);
+        return Ok(Create::Exists);
+    }
+    guard.insert(key, new_entry);
+    drop(guard);
+
+    lru.adjust_used_bytes(new_size as isize - reserved);
+    Ok(Create::Created)
+}
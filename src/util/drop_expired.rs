@@ -0,0 +1,14 @@
+use super::is_expired;
+use crate::{lru::estimate_entry_bytes, model::Keyspace};
+
+/// Removes `key` if its TTL has already passed so write commands can treat
+/// it as missing. Returns the estimated bytes the stale entry occupied.
+pub fn drop_expired(db: &mut Keyspace, key: &str) -> usize {
+    if !db.get(key).is_some_and(is_expired) {
+        return 0;
+    }
+    match db.remove_entry(key) {
+        Some((stored_key, entry)) => estimate_entry_bytes(&stored_key, &entry),
+        None => 0,
+    }
+}
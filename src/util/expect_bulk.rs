@@ -10,3 +10,10 @@ pub fn expect_bulk(items: &[RESP], idx: usize, name: &str) -> Result<String> {
         _ => Err(anyhow::anyhow!("invalid {}", name)),
     }
 }
+
+pub fn expect_bulk_bytes(items: &[RESP], idx: usize, name: &str) -> Result<Vec<u8>> {
+    match items.get(idx) {
+        Some(RESP::BulkStrings(Some(b))) => Ok(b.clone()),
+        _ => Err(anyhow::anyhow!("invalid {}", name)),
+    }
+}
@@ -0,0 +1,27 @@
+use super::expect_bulk;
+use crate::model::RESP;
+use anyhow::Result;
+
+pub fn expect_int(items: &[RESP], idx: usize, name: &str) -> Result<i64> {
+    expect_bulk(items, idx, name)?
+        .parse::<i64>()
+        .map_err(|_| anyhow::anyhow!("value is not an integer or out of range"))
+}
+
+pub fn expect_float(items: &[RESP], idx: usize, name: &str) -> Result<f64> {
+    let value = expect_bulk(items, idx, name)?;
+    match parse_float(value.as_bytes()) {
+        Some(v) if !v.is_nan() => Ok(v),
+        _ => Err(anyhow::anyhow!("value is not a valid float")),
+    }
+}
+
+pub fn parse_float(bytes: &[u8]) -> Option<f64> {
+    let s = std::str::from_utf8(bytes).ok()?;
+    match s.to_ascii_lowercase().as_str() {
+        "inf" | "+inf" | "infinity" | "+infinity" => Some(f64::INFINITY),
+        "-inf" | "-infinity" => Some(f64::NEG_INFINITY),
+        _ if s.is_empty() || s.trim() != s => None,
+        _ => s.parse::<f64>().ok().filter(|v| v.is_finite()),
+    }
+}
@@ -0,0 +1,23 @@
+use crate::model::{Entry, MinHeap};
+
+/// Heap records for everything in `entry` that expires, filed under `key`.
+/// Commands that move an entry to a new name push these so the background
+/// cleaner finds it there; records left under the old name no longer match
+/// and are skipped.
+pub fn expiry_records(key: &str, entry: &Entry) -> Vec<MinHeap> {
+    let mut records: Vec<MinHeap> = entry
+        .expires_at
+        .into_iter()
+        .map(|expires_at| MinHeap {
+            key: key.to_string(),
+            expires_at,
+        })
+        .collect();
+    if let Some(hash) = entry.value.as_hash() {
+        records.extend(hash.deadlines().map(|expires_at| MinHeap {
+            key: key.to_string(),
+            expires_at,
+        }));
+    }
+    records
+}
@@ -0,0 +1,11 @@
+pub fn format_float(value: f64) -> String {
+    if value.is_infinite() {
+        if value > 0.0 {
+            "inf".to_string()
+        } else {
+            "-inf".to_string()
+        }
+    } else {
+        format!("{}", value)
+    }
+}
@@ -1,10 +1,15 @@
-use std::time::Instant;
-
-use crate::model::Entry;
+use crate::model::{Entry, Value, unix_millis};
 
+/// Whether the key's TTL has passed, or it is a hash whose fields have all
+/// expired on their own.
 pub fn is_expired(entry: &Entry) -> bool {
+    if let Value::Hash(hash) = &entry.value
+        && hash.all_expired()
+    {
+        return true;
+    }
     match entry.expires_at {
-        Some(t) => Instant::now() >= t,
+        Some(t) => unix_millis() >= t,
         None => false,
     }
 }
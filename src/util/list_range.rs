@@ -0,0 +1,27 @@
+/// Resolves Redis-style `start`/`stop` indexes (negative counts from the end)
+/// against a list of `len` items. Returns the inclusive bounds, or `None` when
+/// the range is empty.
+pub fn list_range(len: usize, start: i64, stop: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let stop = if stop < 0 {
+        len + stop
+    } else {
+        stop.min(len - 1)
+    };
+    if start > stop || start >= len {
+        return None;
+    }
+    Some((start as usize, stop as usize))
+}
+
+/// Resolves a single list index, with negative values counting from the end.
+pub fn list_index(len: usize, index: i64) -> Option<usize> {
+    let len = len as i64;
+    let index = if index < 0 { len + index } else { index };
+    (0..len).contains(&index).then_some(index as usize)
+}
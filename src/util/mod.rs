@@ -1,11 +1,37 @@
 mod bulk_to_string;
+mod create_value;
+mod drop_expired;
 mod expect_bulk;
+mod expect_int;
+mod expiry_records;
 mod find_crlf;
+mod format_float;
+mod glob_match;
 mod is_expired;
+mod list_range;
+mod pop_list;
+mod random;
+mod read_value;
 mod resp_encode;
+mod scan_page;
+mod string_range;
+mod update_value;
 
 pub use bulk_to_string::bulk_to_string;
-pub use expect_bulk::expect_bulk;
+pub use create_value::{Create, create_value};
+pub use drop_expired::drop_expired;
+pub use expect_bulk::{expect_bulk, expect_bulk_bytes};
+pub use expect_int::{expect_float, expect_int, parse_float};
+pub use expiry_records::expiry_records;
 pub use find_crlf::find_crlf;
+pub use format_float::format_float;
+pub use glob_match::glob_match;
 pub use is_expired::is_expired;
+pub use list_range::{list_index, list_range};
+pub use pop_list::pop_list;
+pub use random::{random_index, sample_distinct, sample_repeated};
+pub use read_value::{Lookup, read_value};
 pub use resp_encode::*;
+pub use scan_page::{scan_hash, scan_page, scan_sorted};
+pub use string_range::string_range;
+pub use update_value::update_value;
@@ -0,0 +1,64 @@
+use super::{Lookup, drop_expired};
+use crate::{lru::estimate_entry_bytes, model::Keyspace};
+
+/// Result of `pop_list`.
+pub struct ListPop {
+    pub lookup: Lookup<Vec<Vec<u8>>>,
+    /// Size change to charge to the LRU, including a dropped expired entry.
+    pub delta: isize,
+    /// The list emptied and its key was removed; the caller owes
+    /// `lru.remove_key`.
+    pub removed: bool,
+}
+
+/// Pops up to `count` items from the head (`left`) or tail of the list at
+/// `key` under a write lock the caller already holds. Expired keys count as
+/// missing, and a list that empties loses its key as in `lpop_cmd`.
+pub fn pop_list(db: &mut Keyspace, key: &str, left: bool, count: usize) -> ListPop {
+    let mut delta = -(drop_expired(db, key) as isize);
+    let key = key.to_string();
+
+    let Some(entry) = db.get_mut(&key) else {
+        return ListPop {
+            lookup: Lookup::Missing,
+            delta,
+            removed: false,
+        };
+    };
+    let old_size = estimate_entry_bytes(&key, entry);
+
+    let Some(list) = entry.value.as_list_mut() else {
+        return ListPop {
+            lookup: Lookup::WrongType,
+            delta,
+            removed: false,
+        };
+    };
+
+    let mut items = Vec::new();
+    for _ in 0..count {
+        let item = if left {
+            list.pop_front()
+        } else {
+            list.pop_back()
+        };
+        match item {
+            Some(item) => items.push(item),
+            None => break,
+        }
+    }
+
+    let removed = list.is_empty();
+    if removed {
+        db.remove(&key);
+        delta -= old_size as isize;
+    } else {
+        delta += estimate_entry_bytes(&key, entry) as isize - old_size as isize;
+    }
+
+    ListPop {
+        lookup: Lookup::Found(items),
+        delta,
+        removed,
+    }
+}
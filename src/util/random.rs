@@ -0,0 +1,105 @@
+use std::{
+    cell::Cell,
+    collections::{BTreeSet, hash_map::RandomState},
+    hash::BuildHasher,
+};
+
+thread_local! {
+    static STATE: Cell<u64> = Cell::new(RandomState::new().hash_one(0u64) | 1);
+}
+
+/// Cheap xorshift generator for random member selection; not cryptographic.
+fn random_u64() -> u64 {
+    STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        state.set(x);
+        x
+    })
+}
+
+pub fn random_index(len: usize) -> usize {
+    (random_u64() % len as u64) as usize
+}
+
+/// Picks `take` distinct items out of the `len` that `items` yields, in one
+/// pass that keeps only the picked items, and returns them in random order.
+pub fn sample_distinct<T>(items: impl Iterator<Item = T>, len: usize, take: usize) -> Vec<T> {
+    let take = take.min(len);
+    // Floyd's algorithm draws the positions without a pass over the items.
+    let mut wanted = BTreeSet::new();
+    for j in len - take..len {
+        let position = random_index(j + 1);
+        if !wanted.insert(position) {
+            wanted.insert(j);
+        }
+    }
+
+    let mut picked = pick_positions(items, wanted.iter().copied());
+    for i in (1..picked.len()).rev() {
+        picked.swap(i, random_index(i + 1));
+    }
+    picked
+}
+
+/// Picks `count` of the `len` items that `items` yields, with repetition, in
+/// one pass that keeps only the picked items. The picks are drawn up front,
+/// so callers use it for counts up to `len` and draw from the whole
+/// collection beyond that.
+pub fn sample_repeated<T: Clone>(
+    items: impl Iterator<Item = T>,
+    len: usize,
+    count: usize,
+) -> Vec<T> {
+    let picks: Vec<usize> = (0..count).map(|_| random_index(len)).collect();
+    let mut wanted = picks.clone();
+    wanted.sort_unstable();
+    wanted.dedup();
+
+    let found = pick_positions(items, wanted.iter().copied());
+    picks
+        .iter()
+        .map(|pick| found[wanted.binary_search(pick).unwrap()].clone())
+        .collect()
+}
+
+/// The items at the given ascending positions, stopping after the last.
+fn pick_positions<T>(
+    items: impl Iterator<Item = T>,
+    positions: impl Iterator<Item = usize>,
+) -> Vec<T> {
+    let mut positions = positions.peekable();
+    let mut found = Vec::new();
+    for (i, item) in items.enumerate() {
+        let Some(&position) = positions.peek() else {
+            break;
+        };
+        if i == position {
+            found.push(item);
+            positions.next();
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_keep_only_the_picks() {
+        let items: Vec<usize> = (0..100).collect();
+
+        let mut picked = sample_distinct(items.iter(), items.len(), 10);
+        picked.sort();
+        picked.dedup();
+        assert_eq!(picked.len(), 10);
+        assert_eq!(sample_distinct(items.iter(), items.len(), 500).len(), 100);
+
+        let picks = sample_repeated(items.iter(), items.len(), 50);
+        assert_eq!(picks.len(), 50);
+        assert!(picks.iter().all(|pick| **pick < 100));
+    }
+}
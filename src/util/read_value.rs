@@ -0,0 +1,46 @@
+use super::is_expired;
+use crate::model::{DB, Heap, MinHeap, Value};
+
+pub enum Lookup<T> {
+    Missing,
+    WrongType,
+    Found(T),
+}
+
+/// Runs `read` against the live value stored at `key` under the read lock.
+///
+/// Expired entries are reported as missing and handed back to the heap for
+/// the background cleaner, the same way `get_cmd` does. `read` returns
+/// `None` when the value has the wrong type for the calling command.
+pub async fn read_value<T>(
+    db: &DB,
+    heap: &Heap,
+    key: &str,
+    read: impl FnOnce(&Value) -> Option<T>,
+) -> Lookup<T> {
+    let mut expired_at = None;
+    let lookup = {
+        let db = db.read().await;
+        match db.get(key) {
+            None => Lookup::Missing,
+            Some(entry) if is_expired(entry) => {
+                expired_at = entry.expires_at;
+                Lookup::Missing
+            }
+            Some(entry) => match read(&entry.value) {
+                Some(found) => Lookup::Found(found),
+                None => Lookup::WrongType,
+            },
+        }
+    };
+
+    if let Some(expires_at) = expired_at {
+        let mut heap = heap.lock().await;
+        heap.push(MinHeap {
+            key: key.to_string(),
+            expires_at,
+        });
+    }
+
+    lookup
+}
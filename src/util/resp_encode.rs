@@ -13,3 +13,42 @@ pub fn bulk_str(s: &str) -> Vec<u8> {
 pub fn integer(n: i64) -> Vec<u8> {
     format!(":{}\r\n", n).into_bytes()
 }
+
+pub fn integer_array(values: &[i64]) -> Vec<u8> {
+    let mut out = array_len(values.len());
+    for value in values {
+        out.extend_from_slice(&integer(*value));
+    }
+    out
+}
+
+pub fn bulk_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut out = format!("${}\r\n", bytes.len()).into_bytes();
+    out.extend_from_slice(bytes);
+    out.extend_from_slice(b"\r\n");
+    out
+}
+
+pub fn null_bulk() -> Vec<u8> {
+    b"$-1\r\n".to_vec()
+}
+
+pub fn null_array() -> Vec<u8> {
+    b"*-1\r\n".to_vec()
+}
+
+/// Replies sized by a client-supplied count are flushed to the socket at this size.
+pub const REPLY_CHUNK_BYTES: usize = 64 * 1024;
+
+pub const WRONGTYPE_ERR: &[u8] = b"-WRONGTYPE key holds wrong kind of value\r\n";
+pub const OOM_ERR: &[u8] = b"-OOM command not allowed when used memory > 'maxmemory'.\r\n";
+pub const HLL_WRONGTYPE_ERR: &[u8] = b"-WRONGTYPE Key is not a valid HyperLogLog string value.\r\n";
+
+pub fn nogroup_err(key: &str, group: &[u8]) -> Vec<u8> {
+    format!(
+        "-NOGROUP No such key '{}' or consumer group '{}'\r\n",
+        key,
+        String::from_utf8_lossy(group)
+    )
+    .into_bytes()
+}
@@ -0,0 +1,97 @@
+use std::{
+    collections::BTreeMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+/// Position of `item` in the cursor space walked by the SCAN family.
+pub fn scan_hash(item: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the next page of a cursor walk over `(scan_hash, item)` pairs.
+///
+/// Items are visited in ascending hash order and the returned cursor is the
+/// first hash not yet visited, so anything present for the whole walk is
+/// returned at least once regardless of inserts in between calls. A cursor
+/// of `0` in the result means the walk is complete.
+///
+/// The pairs may come in any order. Only the page being built is held, so a
+/// call costs one pass over the pairs and never sorts the whole collection.
+pub fn scan_page<T>(
+    items: impl Iterator<Item = (u64, T)>,
+    cursor: u64,
+    count: usize,
+) -> (u64, Vec<T>) {
+    let count = count.max(1);
+    // The smallest hashes from the cursor on. Items sharing a hash stay
+    // together, since the cursor cannot point between them.
+    let mut page: BTreeMap<u64, Vec<T>> = BTreeMap::new();
+    let mut held = 0;
+    let mut next: Option<u64> = None;
+    for (hash, item) in items {
+        if hash < cursor {
+            continue;
+        }
+        if held >= count && page.last_key_value().is_some_and(|(last, _)| hash > *last) {
+            next = Some(next.map_or(hash, |n| n.min(hash)));
+            continue;
+        }
+        page.entry(hash).or_default().push(item);
+        held += 1;
+        // Once the smaller hashes fill the page, the largest one moves off it.
+        if let Some(last) = page.last_entry()
+            && held - last.get().len() >= count
+        {
+            held -= last.get().len();
+            let (hash, _) = last.remove_entry();
+            next = Some(next.map_or(hash, |n| n.min(hash)));
+        }
+    }
+    (next.unwrap_or(0), page.into_values().flatten().collect())
+}
+
+/// `scan_page` over pairs that already come in ascending hash order from the
+/// cursor on, such as a keyspace's SCAN index. Only the returned page and
+/// the pair after it are visited.
+pub fn scan_sorted<T>(items: impl Iterator<Item = (u64, T)>, count: usize) -> (u64, Vec<T>) {
+    let count = count.max(1);
+    let mut page = Vec::new();
+    let mut last = None;
+    for (hash, item) in items {
+        // Items sharing the last hash stay on this page, since the cursor
+        // cannot point between them.
+        if page.len() >= count && last != Some(hash) {
+            return (hash, page);
+        }
+        last = Some(hash);
+        page.push(item);
+    }
+    (0, page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsorted_walk_visits_everything_in_hash_order() {
+        // Shared hashes must land on the same page.
+        let items: Vec<(u64, u32)> = (0..50u32).map(|i| (u64::from(i % 17) + 1, i)).collect();
+
+        let mut cursor = 0;
+        let mut seen = Vec::new();
+        loop {
+            let (next, page) = scan_page(items.iter().rev().copied(), cursor, 4);
+            assert!(page.iter().all(|i| u64::from(i % 17) + 1 >= cursor));
+            seen.extend(page);
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+        seen.sort();
+        assert_eq!(seen, (0..50).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,24 @@
+/// Resolves GETRANGE-style `start`/`end` indexes against a string of `len`
+/// units. Unlike list ranges, a negative end past the front clamps to the
+/// first unit instead of emptying the range. Returns the inclusive bounds,
+/// or `None` when the range is empty.
+pub fn string_range(len: usize, start: i64, end: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    if len == 0 || start < 0 && end < 0 && start > end {
+        return None;
+    }
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let end = if end < 0 {
+        (len + end).max(0)
+    } else {
+        end.min(len - 1)
+    };
+    if start > end {
+        return None;
+    }
+    Some((start as usize, end as usize))
+}
@@ -0,0 +1,57 @@
+use super::{Lookup, is_expired};
+use crate::{
+    lru::{estimate_entry_bytes, LruManager},
+    model::{DB, Heap, MinHeap, Value},
+};
+
+/// Runs `update` against the live value stored at `key` under the write lock
+/// and charges the resulting size change to `lru`.
+///
+/// Meant for in-place edits that never remove the key. Expired entries are
+/// reported as missing and handed to the heap, as in `read_value`; `update`
+/// returns `None` when the value has the wrong type.
+pub async fn update_value<T>(
+    db: &DB,
+    heap: &Heap,
+    lru: &LruManager,
+    key: &str,
+    update: impl FnOnce(&mut Value) -> Option<T>,
+) -> Lookup<T> {
+    let mut expired_at = None;
+    let mut delta = 0isize;
+    let lookup = {
+        let mut db = db.write().await;
+        match db.get_mut(key) {
+            None => Lookup::Missing,
+            Some(entry) if is_expired(entry) => {
+                expired_at = entry.expires_at;
+                Lookup::Missing
+            }
+            Some(entry) => {
+                let key = key.to_string();
+                let old_size = estimate_entry_bytes(&key, entry);
+                match update(&mut entry.value) {
+                    Some(found) => {
+                        delta = estimate_entry_bytes(&key, entry) as isize - old_size as isize;
+                        Lookup::Found(found)
+                    }
+                    None => Lookup::WrongType,
+                }
+            }
+        }
+    };
+
+    if delta != 0 {
+        lru.adjust_used_bytes(delta);
+    }
+
+    if let Some(expires_at) = expired_at {
+        let mut heap = heap.lock().await;
+        heap.push(MinHeap {
+            key: key.to_string(),
+            expires_at,
+        });
+    }
+
+    lookup
+}